@@ -14,8 +14,7 @@ fn main() {
         .array_key_suffix("$")
         .hide_indices_in_path()
         .skip_parents()
-        .build()
-        .unwrap();
+        .build();
     let items: Vec<_> = Iter::new(&value).use_style(style).collect();
 
     assert_eq!(items.len(), 7);
@@ -0,0 +1,271 @@
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+use std::ops::Range;
+use std::sync::Arc;
+use std::vec;
+
+/// Element that keeps the whole document alive through an `Arc`, so it is `'static` and can be sent to other threads
+///
+/// The value is located again from the root, by the keys and indices leading to it, on each call to `value`.
+#[derive(Debug, Clone)]
+pub struct ArcElement {
+    /// The full path from the base of a json structure to the value, see `Element::path`
+    pub path: String,
+    /// The full set of _array_ indices in the path, see `Element::indices`
+    pub indices: Vec<usize>,
+    root: Arc<Value>,
+    steps: Vec<Step>,
+}
+
+impl ArcElement {
+    /// The value described by the path
+    pub fn value(&self) -> &Value {
+        locate(&self.root, &self.steps)
+    }
+
+    /// The document this element was found in
+    pub fn root(&self) -> &Arc<Value> {
+        &self.root
+    }
+}
+
+impl PartialEq for ArcElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.indices == other.indices && self.value() == other.value()
+    }
+}
+
+/// Object key or array index leading from a container to one of its values
+#[derive(Debug, Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// Follows the steps down from the root, which always lead to a value as the shared document cannot change
+fn locate<'v>(root: &'v Value, steps: &[Step]) -> &'v Value {
+    steps.iter().fold(root, |value, step| match step {
+        Step::Key(key) => &value[key.as_str()],
+        Step::Index(index) => &value[*index],
+    })
+}
+
+/// Value reached but not yet yielded, located by the steps from the root
+#[derive(Debug)]
+struct ArcNode {
+    steps: Vec<Step>,
+    path: String,
+    indices: Vec<usize>,
+}
+
+/// Container being walked, whose children are only built as they are reached
+#[derive(Debug)]
+struct ArcFrame {
+    node: ArcNode,
+    children: Children,
+}
+
+#[derive(Debug)]
+enum Children {
+    Keys(vec::IntoIter<String>),
+    Indices(Range<usize>),
+}
+
+/// Iterator over a document shared through an `Arc`, yielding `'static` elements for fan-out across threads
+///
+/// Example:
+/// ```rust
+/// use std::sync::{mpsc, Arc};
+/// use std::thread;
+/// use serde_json::json;
+/// use json_keypath_iter::ArcIter;
+///
+/// let value = Arc::new(json!({"a": [1, 2]}));
+/// let (sender, receiver) = mpsc::channel();
+///
+/// let handle = thread::spawn(move || {
+///     for el in ArcIter::new(value) {
+///         sender.send(el).unwrap();
+///     }
+/// });
+/// handle.join().unwrap();
+///
+/// let items: Vec<_> = receiver.iter().map(|el| (el.path.clone(), el.value().clone())).collect();
+/// assert_eq!(items, vec![("[\"a\"][0]".into(), json!(1)), ("[\"a\"][1]".into(), json!(2))]);
+/// ```
+#[derive(Debug)]
+pub struct ArcIter<'s> {
+    style: Style<'s>,
+    root: Arc<Value>,
+    base: Option<ArcNode>,
+    frames: Vec<ArcFrame>,
+}
+
+impl<'s> ArcIter<'s> {
+    /// Create a new iterator over a shared document, see `Iterator::new`
+    pub fn new(json: Arc<Value>) -> Self {
+        Self {
+            style: PresetStyle::SquareBrackets.into(),
+            root: json,
+            base: Some(ArcNode {
+                steps: Vec::new(),
+                path: String::new(),
+                indices: Vec::new(),
+            }),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Optionally used to set a custom style for the path in elements, see `Iterator::use_style`
+    pub fn use_style(mut self, style: Style<'s>) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Next value to visit, either the base or the next child of the innermost container still being walked
+    fn next_node(&mut self) -> Option<ArcNode> {
+        if let Some(base) = self.base.take() {
+            return Some(base);
+        }
+        let style = &self.style;
+        while let Some(frame) = self.frames.last_mut() {
+            let parent = &frame.node;
+            let child = match &mut frame.children {
+                Children::Keys(keys) => keys.next().map(|key| ArcNode {
+                    path: style.object_format(&parent.path, &key),
+                    indices: parent.indices.clone(),
+                    steps: child_steps(&parent.steps, Step::Key(key)),
+                }),
+                Children::Indices(indices) => indices.next().map(|index| {
+                    let mut child_indices = parent.indices.clone();
+                    child_indices.push(index);
+                    ArcNode {
+                        path: style.array_format(&parent.path, index),
+                        indices: child_indices,
+                        steps: child_steps(&parent.steps, Step::Index(index)),
+                    }
+                }),
+            };
+            match child {
+                Some(child) => return Some(child),
+                None => {
+                    self.frames.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+fn child_steps(parent: &[Step], step: Step) -> Vec<Step> {
+    let mut steps = Vec::with_capacity(parent.len() + 1);
+    steps.extend_from_slice(parent);
+    steps.push(step);
+    steps
+}
+
+impl<'s> From<Arc<Value>> for ArcIter<'s> {
+    fn from(json: Arc<Value>) -> Self {
+        ArcIter::new(json)
+    }
+}
+
+impl<'s> Iterator for ArcIter<'s> {
+    type Item = ArcElement;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next_node() {
+            let (children, skip) = match locate(&self.root, &node.steps) {
+                Value::Object(obj) => (
+                    Some(Children::Keys(
+                        obj.keys().cloned().collect::<Vec<_>>().into_iter(),
+                    )),
+                    self.style.should_skip_object_parents(),
+                ),
+                Value::Array(arr) => (
+                    Some(Children::Indices(0..arr.len())),
+                    self.style.should_skip_array_parents(),
+                ),
+                _ => (None, false),
+            };
+
+            let element = match skip {
+                true => None,
+                false => Some(ArcElement {
+                    path: self.style.finish_path(node.path.clone()),
+                    indices: node.indices.clone(),
+                    root: Arc::clone(&self.root),
+                    steps: node.steps.clone(),
+                }),
+            };
+            if let Some(children) = children {
+                self.frames.push(ArcFrame { node, children });
+            }
+            if element.is_some() {
+                return element;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::{Element, Iter, PathMatches, Values};
+    use crate::style::StyleBuilder;
+    use serde_json::json;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn iterators_are_send_and_sync() {
+        assert_send_sync::<Iter<'static>>();
+        assert_send_sync::<Element<'static>>();
+        assert_send_sync::<PathMatches<'static>>();
+        assert_send_sync::<Values<'static>>();
+        assert_send_sync::<ArcIter<'static>>();
+        assert_send_sync::<ArcElement>();
+    }
+
+    #[test]
+    fn matches_borrowed_iterator() {
+        let value = json!({"a/b": {"c~d": [1, {}]}, "e": null});
+        let style = StyleBuilder::new()
+            .include_object_parents()
+            .include_array_parents()
+            .build();
+        let borrowed: Vec<_> = Iter::new(&value)
+            .use_style(style.clone())
+            .map(|el| (el.path, el.indices, el.value.clone()))
+            .collect();
+        let shared: Vec<_> = ArcIter::new(Arc::new(value.clone()))
+            .use_style(style)
+            .map(|el| (el.path.clone(), el.indices.clone(), el.value().clone()))
+            .collect();
+
+        assert_eq!(borrowed, shared);
+    }
+
+    #[test]
+    fn walks_one_container_at_a_time() {
+        let value = json!({"a": [[1, 2], {}, 3], "b": 4});
+        let expected: Vec<_> = Iter::new(&value)
+            .map(|el| (el.path, el.value.clone()))
+            .collect();
+        let mut iter = ArcIter::new(Arc::new(value));
+
+        let first = iter.next().unwrap();
+        assert_eq!(
+            (first.path.as_str(), first.value()),
+            ("[\"a\"][0][0]", &json!(1))
+        );
+        assert_eq!(iter.frames.len(), 3);
+
+        let rest = iter.map(|el| (el.path.clone(), el.value().clone()));
+        let shared: Vec<_> = std::iter::once((first.path.clone(), first.value().clone()))
+            .chain(rest)
+            .collect();
+        assert_eq!(shared, expected);
+    }
+}
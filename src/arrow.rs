@@ -0,0 +1,302 @@
+use crate::iter::Iter;
+use crate::style::{Style, StyleBuilder};
+use arrow_array::builder::{
+    ArrayBuilder, BooleanBuilder, Float64Builder, Int64Builder, ListBuilder, NullBuilder,
+    StringBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{ArrowError, Field, Schema};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Options controlling how `to_record_batches` builds arrow data
+#[derive(Debug, Clone)]
+pub struct ArrowOptions<'a> {
+    /// Style used to name each column, by default `CommonJs`-like paths with wildcarded array indices
+    pub style: Style<'a>,
+    /// Maximum number of rows in each `RecordBatch`
+    pub batch_size: usize,
+}
+
+impl<'a> Default for ArrowOptions<'a> {
+    fn default() -> Self {
+        ArrowOptions {
+            style: StyleBuilder::new()
+                .object_key_prefix(".")
+                .object_key_suffix("")
+                .wildcard_array_keys_in_path()
+                .build(),
+            batch_size: 8192,
+        }
+    }
+}
+
+/// Error produced while converting json into arrow data
+#[derive(Debug)]
+pub enum ArrowConvertError {
+    /// The input value was not an array of records
+    NotAnArray,
+    /// Arrow rejected the generated columns
+    Arrow(ArrowError),
+}
+
+impl fmt::Display for ArrowConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrowConvertError::NotAnArray => write!(f, "expected an array of records"),
+            ArrowConvertError::Arrow(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ArrowConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ArrowConvertError::NotAnArray => None,
+            ArrowConvertError::Arrow(err) => Some(err),
+        }
+    }
+}
+
+impl From<ArrowError> for ArrowConvertError {
+    fn from(err: ArrowError) -> Self {
+        ArrowConvertError::Arrow(err)
+    }
+}
+
+/// Arrow type inferred from every non-null value in a column
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnType {
+    Null,
+    Boolean,
+    Int64,
+    Float64,
+    Utf8,
+}
+
+impl ColumnType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => ColumnType::Null,
+            Value::Bool(_) => ColumnType::Boolean,
+            Value::Number(n) if n.is_i64() => ColumnType::Int64,
+            Value::Number(_) => ColumnType::Float64,
+            _ => ColumnType::Utf8,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ColumnType::Null, other) | (other, ColumnType::Null) => other,
+            (ColumnType::Int64, ColumnType::Float64) | (ColumnType::Float64, ColumnType::Int64) => {
+                ColumnType::Float64
+            }
+            _ => ColumnType::Utf8,
+        }
+    }
+}
+
+/// Values found under one column name, grouped by row
+struct Column<'a> {
+    name: String,
+    cells: Vec<Vec<&'a Value>>,
+}
+
+/// Converts an array of records into arrow `RecordBatch`es sharing a single inferred schema
+///
+/// Each record is flattened with `options.style`, and each distinct path becomes a nullable column.
+/// Columns holding only booleans, integers, or numbers become `Boolean`, `Int64`, or `Float64`,
+/// and anything mixed becomes `Utf8` with non-string values rendered as json.
+/// When wildcarded array indices let a record hold several values for one column, that column becomes a `List`.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use arrow_array::{Array, Int64Array, ListArray};
+/// use json_keypath_iter::{to_record_batches, ArrowOptions};
+///
+/// let value = json!([
+///     {"id": 1, "tags": ["a", "b"]},
+///     {"id": 2, "tags": ["c"], "extra": true},
+/// ]);
+/// let batches = to_record_batches(&value, &ArrowOptions::default()).unwrap();
+/// let batch = &batches[0];
+///
+/// assert_eq!(batch.num_rows(), 2);
+/// assert_eq!(batch.schema().field(0).name(), ".id");
+/// assert_eq!(batch.schema().field(1).name(), ".tags[*]");
+/// assert_eq!(batch.schema().field(2).name(), ".extra");
+///
+/// let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+/// assert_eq!(ids.value(1), 2);
+/// let tags = batch.column(1).as_any().downcast_ref::<ListArray>().unwrap();
+/// assert_eq!(tags.value(0).len(), 2);
+/// assert!(batch.column(2).is_null(0));
+/// ```
+pub fn to_record_batches(
+    json: &Value,
+    options: &ArrowOptions,
+) -> Result<Vec<RecordBatch>, ArrowConvertError> {
+    let records = json.as_array().ok_or(ArrowConvertError::NotAnArray)?;
+    let style = StyleBuilder::from(&options.style).skip_parents().build();
+
+    let mut columns: Vec<Column> = Vec::new();
+    let mut positions = HashMap::new();
+    for (row, record) in records.iter().enumerate() {
+        for el in Iter::new(record).use_style(style.clone()) {
+            let position = *positions.entry(el.path.clone()).or_insert_with(|| {
+                columns.push(Column {
+                    name: el.path.clone(),
+                    cells: Vec::new(),
+                });
+                columns.len() - 1
+            });
+            let cells = &mut columns[position].cells;
+            cells.resize_with(row + 1, Vec::new);
+            cells[row].push(el.value);
+        }
+    }
+    for column in &mut columns {
+        column.cells.resize_with(records.len(), Vec::new);
+    }
+
+    let batch_size = options.batch_size.max(1);
+    let mut batches = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + batch_size).min(records.len());
+        let mut fields = Vec::with_capacity(columns.len());
+        let mut arrays = Vec::with_capacity(columns.len());
+        for column in &columns {
+            let array = build_column(column, start..end);
+            fields.push(Field::new(&column.name, array.data_type().clone(), true));
+            arrays.push(array);
+        }
+        batches.push(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?);
+
+        start = end;
+        if start >= records.len() {
+            break;
+        }
+    }
+
+    Ok(batches)
+}
+
+fn build_column(column: &Column, rows: std::ops::Range<usize>) -> ArrayRef {
+    let column_type = column
+        .cells
+        .iter()
+        .flatten()
+        .fold(ColumnType::Null, |acc, value| {
+            acc.merge(ColumnType::of(value))
+        });
+    let is_list = column.cells.iter().any(|cell| cell.len() > 1);
+    let cells = &column.cells[rows];
+
+    match column_type {
+        ColumnType::Null => build(cells, is_list, |b: &mut NullBuilder, _| b.append_null()),
+        ColumnType::Boolean => build(cells, is_list, |b: &mut BooleanBuilder, v| {
+            b.append_option(v.and_then(Value::as_bool))
+        }),
+        ColumnType::Int64 => build(cells, is_list, |b: &mut Int64Builder, v| {
+            b.append_option(v.and_then(Value::as_i64))
+        }),
+        ColumnType::Float64 => build(cells, is_list, |b: &mut Float64Builder, v| {
+            b.append_option(v.and_then(Value::as_f64))
+        }),
+        ColumnType::Utf8 => build(cells, is_list, |b: &mut StringBuilder, v| match v {
+            None | Some(Value::Null) => b.append_null(),
+            Some(Value::String(s)) => b.append_value(s),
+            Some(other) => b.append_value(other.to_string()),
+        }),
+    }
+}
+
+fn build<B, F>(cells: &[Vec<&Value>], is_list: bool, append: F) -> ArrayRef
+where
+    B: ArrayBuilder + Default,
+    F: Fn(&mut B, Option<&Value>),
+{
+    if is_list {
+        let mut builder = ListBuilder::new(B::default());
+        for cell in cells {
+            for value in cell {
+                append(builder.values(), Some(value));
+            }
+            builder.append(!cell.is_empty());
+        }
+        ArrayBuilder::finish(&mut builder)
+    } else {
+        let mut builder = B::default();
+        for cell in cells {
+            append(&mut builder, cell.first().copied());
+        }
+        builder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, Float64Array, StringArray};
+    use arrow_schema::DataType;
+    use serde_json::json;
+
+    #[test]
+    fn infers_widened_types() {
+        let value = json!([
+            {"n": 1, "mixed": "a"},
+            {"n": 2.5, "mixed": 3},
+            {"n": null, "mixed": {"nested": true}},
+        ]);
+        let batches = to_record_batches(&value, &ArrowOptions::default()).unwrap();
+        let schema = batches[0].schema();
+
+        assert_eq!(schema.field(0).name(), ".mixed");
+        assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(1).name(), ".n");
+        assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(2).name(), ".mixed.nested");
+
+        let mixed = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(mixed.value(1), "3");
+        let n = batches[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(n.value(0), 1.0);
+        assert!(n.is_null(2));
+    }
+
+    #[test]
+    fn splits_into_batches_with_shared_schema() {
+        let value = json!([{"a": 1}, {"a": 2}, {"b": "x"}]);
+        let options = ArrowOptions {
+            batch_size: 2,
+            ..ArrowOptions::default()
+        };
+        let batches = to_record_batches(&value, &options).unwrap();
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(batches[1].num_rows(), 1);
+        assert_eq!(batches[0].schema(), batches[1].schema());
+    }
+
+    #[test]
+    fn rejects_non_arrays() {
+        assert!(matches!(
+            to_record_batches(&json!({}), &ArrowOptions::default()),
+            Err(ArrowConvertError::NotAnArray)
+        ));
+    }
+}
@@ -0,0 +1,370 @@
+//! `jkp`, flattens json into `path = value` lines, and extracts or prunes values by glob, for use in shell pipelines
+//!
+//! Built only with the `cli` feature: `cargo install json-keypath-iter --features cli`
+
+use json_keypath_iter::{Iterator, PathGlob, PathSegment, PresetStyle, Style, StyleBuilder};
+use serde_json::Value;
+use std::fmt;
+use std::io::{self, BufWriter, Read, Write};
+use std::process;
+
+const USAGE: &str = "\
+usage: jkp [options] [file]
+       jkp get [options] <glob> [file]
+       jkp prune [options] <glob> [file]
+
+Prints every value in the json document as a `path = value` line.
+`get` prints only the values, objects and arrays included, whose path matches the glob.
+`prune` prints the document back as json, with every value whose path matches the glob removed.
+Reads standard input when no file, or `-`, is given.
+
+options:
+  -s, --style <name>     path style: square-brackets (default), commonjs, postgres-json, slashes, env-var
+  -d, --max-depth <n>    print objects and arrays nested deeper than <n> whole, as compact json
+  -f, --filter <glob>    only print values whose path matches the glob, such as `users[*].email`
+  -h, --help             print this message";
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("jkp: {}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(error) = run(&args) {
+        eprintln!("jkp: {}", error);
+        process::exit(1);
+    }
+}
+
+/// What to do with the document
+#[derive(Debug)]
+enum Command {
+    Flatten,
+    Get(PathGlob),
+    Prune(PathGlob),
+}
+
+/// Options read from the command line
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    style: Style<'static>,
+    max_depth: Option<usize>,
+    filter: Option<PathGlob>,
+    file: Option<String>,
+}
+
+impl Args {
+    /// Reads the options, or `None` when help was asked for
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, CliError> {
+        let mut parsed = Args {
+            command: Command::Flatten,
+            style: PresetStyle::SquareBrackets.into(),
+            max_depth: None,
+            filter: None,
+            file: None,
+        };
+        let mut args = args.into_iter().peekable();
+        let subcommand = match args.peek().map(String::as_str) {
+            Some("get") => Some(Command::Get as fn(PathGlob) -> Command),
+            Some("prune") => Some(Command::Prune as fn(PathGlob) -> Command),
+            _ => None,
+        };
+        if subcommand.is_some() {
+            args.next();
+        }
+        let mut positional = Vec::new();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.find('=') {
+                Some(at) if arg.starts_with("--") => (&arg[..at], Some(arg[at + 1..].to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| CliError::Usage(format!("{} needs a value", flag)))
+            };
+            match flag {
+                "-h" | "--help" => return Ok(None),
+                "-s" | "--style" => {
+                    let name = value()?;
+                    let preset: PresetStyle = name
+                        .parse()
+                        .map_err(|error| CliError::Usage(format!("{}", error)))?;
+                    parsed.style = preset.into();
+                }
+                "-d" | "--max-depth" => {
+                    let depth = value()?;
+                    let depth = depth.parse().map_err(|_| {
+                        CliError::Usage(format!("--max-depth expects a number, got `{}`", depth))
+                    })?;
+                    parsed.max_depth = Some(depth);
+                }
+                "-f" | "--filter" => {
+                    let glob = PathGlob::new(&value()?)
+                        .map_err(|error| CliError::Usage(format!("invalid filter: {}", error)))?;
+                    parsed.filter = Some(glob);
+                }
+                "-" => positional.push(arg),
+                _ if flag.starts_with('-') => {
+                    return Err(CliError::Usage(format!("unknown option `{}`", flag)))
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        if let Some(command) = subcommand {
+            let glob = positional
+                .next()
+                .ok_or_else(|| CliError::Usage("missing the glob to match".into()))?;
+            let glob = PathGlob::new(&glob)
+                .map_err(|error| CliError::Usage(format!("invalid glob: {}", error)))?;
+            parsed.command = command(glob);
+        }
+        parsed.file = positional.next();
+        match positional.next() {
+            Some(arg) => Err(CliError::Usage(format!("unexpected argument `{}`", arg))),
+            None => Ok(Some(parsed)),
+        }
+    }
+}
+
+/// Errors that stop `jkp`, from bad arguments, from reading the document, or from serializing the result
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Io(String, io::Error),
+    Json(String, serde_json::Error),
+    Output(serde_json::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Io(source, error) => write!(f, "could not read {}: {}", source, error),
+            CliError::Json(source, error) => write!(f, "{} is not valid json: {}", source, error),
+            CliError::Output(error) => write!(f, "could not serialize the result: {}", error),
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), CliError> {
+    let (source, text) = match args.file.as_deref() {
+        None | Some("-") => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|error| CliError::Io("standard input".into(), error))?;
+            ("standard input".to_string(), text)
+        }
+        Some(file) => {
+            let text =
+                std::fs::read_to_string(file).map_err(|error| CliError::Io(file.into(), error))?;
+            (file.to_string(), text)
+        }
+    };
+    let value: Value =
+        serde_json::from_str(&text).map_err(|error| CliError::Json(source, error))?;
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let lines = match &args.command {
+        Command::Flatten => flatten_lines(&value, args),
+        Command::Get(glob) => get_lines(&value, glob, &args.style),
+        Command::Prune(glob) => {
+            vec![serde_json::to_string_pretty(&prune(&value, glob)).map_err(CliError::Output)?]
+        }
+    };
+    let written = lines
+        .iter()
+        .try_for_each(|line| writeln!(out, "{}", line))
+        .and_then(|_| out.flush());
+    match written {
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        written => written.map_err(|error| CliError::Io("standard output".into(), error)),
+    }
+}
+
+/// Formats a `path = value` line for every leaf and empty container, and for every container at the maximum depth
+fn flatten_lines(value: &Value, args: &Args) -> Vec<String> {
+    let walk_style = StyleBuilder::new().include_parents().build();
+    let mut segments = Iterator::new(value).use_style(walk_style).segments();
+    let mut lines = Vec::new();
+    while let Some((path, value)) = segments.next_segments() {
+        let depth = path.len();
+        if args.max_depth.is_some_and(|max| depth > max) {
+            continue;
+        }
+        let whole = match value {
+            Value::Object(obj) => obj.is_empty() || args.max_depth == Some(depth),
+            Value::Array(arr) => arr.is_empty() || args.max_depth == Some(depth),
+            _ => true,
+        };
+        if !whole || args.filter.as_ref().is_some_and(|glob| !glob.matches(path)) {
+            continue;
+        }
+        lines.push(format!("{} = {}", format_path(&args.style, path), value));
+    }
+    lines
+}
+
+/// Formats a `path = value` line for every element `Iterator::find_all_paths` matches, parents included
+fn get_lines(value: &Value, glob: &PathGlob, style: &Style<'static>) -> Vec<String> {
+    let style = PresetStyle::Custom(style.clone())
+        .builder()
+        .include_parents()
+        .build();
+    Iterator::new(value)
+        .use_style(style)
+        .find_all_paths(glob.clone())
+        .map(|el| format!("{} = {}", el.path, el.value))
+        .collect()
+}
+
+/// Copies the document without any of the values the glob matches, leaving `null` when it matches the whole document
+fn prune(value: &Value, glob: &PathGlob) -> Value {
+    let walk_style = StyleBuilder::new().include_parents().build();
+    let mut segments = Iterator::new(value).use_style(walk_style).segments();
+    let mut matched = Vec::new();
+    while let Some((path, value)) = segments.next_segments() {
+        if glob.matches(path) && glob.matches_value(value) {
+            matched.push(path.to_vec());
+        }
+    }
+
+    // Removing in reverse document order takes children before their parents, and later array items before
+    // earlier ones, so every remaining path still points where it did in the original
+    let mut pruned = value.clone();
+    for path in matched.iter().rev() {
+        match path.split_last() {
+            Some((last, parent)) => remove(&mut pruned, parent, last),
+            None => pruned = Value::Null,
+        }
+    }
+    pruned
+}
+
+fn remove(json: &mut Value, parent: &[PathSegment], last: &PathSegment) {
+    let mut current = json;
+    for segment in parent {
+        current = match (current, segment) {
+            (Value::Object(obj), PathSegment::Key(key)) => match obj.get_mut(*key) {
+                Some(child) => child,
+                None => return,
+            },
+            (Value::Array(arr), PathSegment::Index(index)) => match arr.get_mut(*index) {
+                Some(child) => child,
+                None => return,
+            },
+            _ => return,
+        };
+    }
+    match (current, last) {
+        (Value::Object(obj), PathSegment::Key(key)) => {
+            #[cfg(feature = "preserve_order")]
+            obj.shift_remove(*key);
+            #[cfg(not(feature = "preserve_order"))]
+            obj.remove(*key);
+        }
+        (Value::Array(arr), PathSegment::Index(index)) if *index < arr.len() => {
+            arr.remove(*index);
+        }
+        _ => {}
+    }
+}
+
+fn format_path(style: &Style, segments: &[PathSegment]) -> String {
+    let path = segments
+        .iter()
+        .fold(String::new(), |path, segment| match segment {
+            PathSegment::Key(key) => style.object_format(&path, key),
+            PathSegment::Index(index) => style.array_format(&path, *index),
+        });
+    style.wrap_path(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn flattens_with_depth_and_filter() {
+        let value = json!({"a": {"b": [1, {"c": null}], "d": {}}, "e": "x"});
+
+        assert_eq!(
+            flatten_lines(&value, &args(&["--style", "commonjs"])),
+            vec![".a.b[0] = 1", ".a.b[1].c = null", ".a.d = {}", ".e = \"x\""],
+        );
+        assert_eq!(
+            flatten_lines(&value, &args(&["-s", "slashes", "--max-depth=2"])),
+            vec!["/a/b = [1,{\"c\":null}]", "/a/d = {}", "/e = \"x\""],
+        );
+        assert_eq!(
+            flatten_lines(&value, &args(&["--filter", "a.**", "-d", "1", "file.json"])),
+            vec!["[\"a\"] = {\"b\":[1,{\"c\":null}],\"d\":{}}"],
+        );
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        let error = |list: &[&str]| {
+            Args::parse(list.iter().map(|arg| arg.to_string()))
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            error(&["--max-depth", "two"]),
+            "--max-depth expects a number, got `two`"
+        );
+        assert_eq!(error(&["--style"]), "--style needs a value");
+        assert_eq!(error(&["--color"]), "unknown option `--color`");
+        assert_eq!(error(&["a.json", "b.json"]), "unexpected argument `b.json`");
+        assert_eq!(error(&["get"]), "missing the glob to match");
+        assert!(Args::parse(vec!["-h".to_string()]).unwrap().is_none());
+    }
+
+    #[test]
+    fn gets_and_prunes_matching_values() {
+        let value = json!({"key": 3, "users": [{"key": 1, "name": "a"}, {"key": 2, "name": "b"}]});
+
+        let parsed = args(&["get", "-s", "commonjs", "users[*]", "-"]);
+        assert_eq!(parsed.file.as_deref(), Some("-"));
+        match &parsed.command {
+            Command::Get(glob) => assert_eq!(
+                get_lines(&value, glob, &parsed.style),
+                vec![
+                    ".users[0] = {\"key\":1,\"name\":\"a\"}",
+                    ".users[1] = {\"key\":2,\"name\":\"b\"}",
+                ],
+            ),
+            other => panic!("expected get, got {:?}", other),
+        }
+
+        let glob = PathGlob::new("**.key").unwrap();
+        assert_eq!(
+            prune(&value, &glob),
+            json!({"users": [{"name": "a"}, {"name": "b"}]})
+        );
+        assert_eq!(
+            prune(&value, &PathGlob::new("users[*]").unwrap()),
+            json!({"users": [], "key": 3})
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::iter::Iter;
+use crate::node::JsonNode;
+use bumpalo::Bump;
+use serde_json::Value;
+
+/// Element whose path and indices live in a bump arena, see `Iterator::in_arena`
+#[derive(Debug, PartialEq)]
+pub struct BumpElement<'a, 'bump, V = Value> {
+    /// The full path from the base of a json structure to the value, see `Element::path`
+    pub path: &'bump str,
+    /// The full set of _array_ indices in the path, see `Element::indices`
+    pub indices: &'bump [usize],
+    /// The value described by the path
+    pub value: &'a V,
+}
+
+/// Iterator allocating every path and set of indices from a caller-supplied arena
+///
+/// Created by `Iterator::in_arena`
+#[derive(Debug)]
+pub struct BumpIter<'a, 'bump, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+    bump: &'bump Bump,
+}
+
+impl<'a, V: JsonNode> Iter<'a, V> {
+    /// Consumes the iterator, yielding elements whose paths and indices are allocated from the arena
+    ///
+    /// Everything is freed at once when the arena is reset or dropped, instead of once per element.
+    ///
+    /// Example:
+    /// ```rust
+    /// use bumpalo::Bump;
+    /// use serde_json::json;
+    /// use json_keypath_iter::Iterator;
+    ///
+    /// let bump = Bump::new();
+    /// let value = json!({"a": [1, 2]});
+    /// let paths: Vec<&str> = Iterator::new(&value).in_arena(&bump).map(|el| el.path).collect();
+    ///
+    /// assert_eq!(paths, vec!["[\"a\"][0]", "[\"a\"][1]"]);
+    /// ```
+    pub fn in_arena<'bump>(self, bump: &'bump Bump) -> BumpIter<'a, 'bump, V> {
+        BumpIter { iter: self, bump }
+    }
+}
+
+impl<'a, 'bump, V: JsonNode> Iterator for BumpIter<'a, 'bump, V> {
+    type Item = BumpElement<'a, 'bump, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.iter.advance()?;
+        let path = match self.iter.unchanged_path() {
+            Some(path) => self.bump.alloc_str(path),
+            None => self.bump.alloc_str(&self.iter.formatted_path()),
+        };
+        Some(BumpElement {
+            path,
+            indices: self.bump.alloc_slice_copy(self.iter.current_indices()),
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn elements_outlive_the_iterator() {
+        let bump = Bump::new();
+        let value = json!([[true]]);
+        let el = {
+            let mut iter = Iter::new(&value).in_arena(&bump);
+            iter.next().unwrap()
+        };
+
+        assert_eq!(
+            el,
+            BumpElement {
+                path: "[0][0]",
+                indices: &[0, 0],
+                value: &json!(true),
+            }
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::iter::Iter;
+use crate::owned::IntoIter;
+use ciborium::value::Value as CborValue;
+use serde_json::{Map, Number, Value};
+use std::convert::TryFrom;
+
+/// How cbor byte strings are represented once converted to json
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BytesEncoding {
+    /// A lowercase hexadecimal string, such as `"01ff"`
+    Hex,
+    /// An array of numbers, such as `[1, 255]`, yielding one element per byte
+    Array,
+}
+
+/// How cbor tags are represented once converted to json
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TagPolicy {
+    /// Drop the tag and use the tagged value directly
+    Transparent,
+    /// Wrap the tagged value as `{"tag": 1, "value": ...}`
+    Wrap,
+}
+
+/// Options controlling how `Iterator::from_cbor` converts cbor values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CborOptions {
+    /// Representation of byte strings
+    pub bytes: BytesEncoding,
+    /// Representation of tagged values
+    pub tags: TagPolicy,
+}
+
+impl Default for CborOptions {
+    fn default() -> Self {
+        CborOptions {
+            bytes: BytesEncoding::Hex,
+            tags: TagPolicy::Transparent,
+        }
+    }
+}
+
+impl<'a> Iter<'a> {
+    /// Converts a cbor value into json and creates an iterator that owns the result
+    ///
+    /// Map keys that are not text are rendered as strings: integers as decimal, byte strings per `options.bytes`,
+    /// and anything else as its compact json. Integers and floats that json cannot represent are yielded as strings.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use ciborium::value::Value;
+    /// use json_keypath_iter::{BytesEncoding, CborOptions, Iterator, OwnedElement, TagPolicy};
+    ///
+    /// let cbor = Value::Map(vec![
+    ///     (Value::Integer(1.into()), Value::Bytes(vec![0x01, 0xff])),
+    ///     (Value::Text("t".into()), Value::Tag(1, Box::new(Value::Integer(1600000000.into())))),
+    /// ]);
+    /// let options = CborOptions { bytes: BytesEncoding::Hex, tags: TagPolicy::Wrap };
+    /// let items: Vec<_> = Iterator::from_cbor(&cbor, options).collect();
+    ///
+    /// assert_eq!(items[0], OwnedElement { path: "[\"1\"]".into(), indices: vec![], value: json!("01ff"), });
+    /// assert_eq!(items[1], OwnedElement { path: "[\"t\"][\"tag\"]".into(), indices: vec![], value: json!(1), });
+    /// assert_eq!(items[2], OwnedElement { path: "[\"t\"][\"value\"]".into(), indices: vec![], value: json!(1600000000), });
+    /// ```
+    pub fn from_cbor(value: &CborValue, options: CborOptions) -> IntoIter<'static> {
+        IntoIter::new(to_json(value, options))
+    }
+}
+
+fn to_json(value: &CborValue, options: CborOptions) -> Value {
+    match value {
+        CborValue::Null => Value::Null,
+        CborValue::Bool(b) => Value::Bool(*b),
+        CborValue::Integer(i) => {
+            let i = i128::from(*i);
+            if let Ok(i) = i64::try_from(i) {
+                Value::Number(i.into())
+            } else if let Ok(u) = u64::try_from(i) {
+                Value::Number(u.into())
+            } else {
+                Value::String(i.to_string())
+            }
+        }
+        CborValue::Float(f) => {
+            Number::from_f64(*f).map_or_else(|| Value::String(f.to_string()), Value::Number)
+        }
+        CborValue::Text(s) => Value::String(s.clone()),
+        CborValue::Bytes(bytes) => match options.bytes {
+            BytesEncoding::Hex => Value::String(to_hex(bytes)),
+            BytesEncoding::Array => Value::Array(bytes.iter().map(|b| (*b).into()).collect()),
+        },
+        CborValue::Array(arr) => Value::Array(arr.iter().map(|v| to_json(v, options)).collect()),
+        CborValue::Map(entries) => {
+            let mut obj = Map::new();
+            for (key, val) in entries {
+                obj.insert(key_to_string(key, options), to_json(val, options));
+            }
+            Value::Object(obj)
+        }
+        CborValue::Tag(tag, val) => match options.tags {
+            TagPolicy::Transparent => to_json(val, options),
+            TagPolicy::Wrap => {
+                let mut obj = Map::new();
+                obj.insert("tag".into(), (*tag).into());
+                obj.insert("value".into(), to_json(val, options));
+                Value::Object(obj)
+            }
+        },
+        _ => Value::Null,
+    }
+}
+
+fn key_to_string(key: &CborValue, options: CborOptions) -> String {
+    match key {
+        CborValue::Text(s) => s.clone(),
+        CborValue::Bytes(bytes) if options.bytes == BytesEncoding::Hex => to_hex(bytes),
+        CborValue::Tag(_, val) => key_to_string(val, options),
+        _ => match to_json(key, options) {
+            Value::String(s) => s,
+            other => other.to_string(),
+        },
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bytes_as_arrays() {
+        let cbor = CborValue::Array(vec![CborValue::Bytes(vec![7, 8])]);
+        let options = CborOptions {
+            bytes: BytesEncoding::Array,
+            ..CborOptions::default()
+        };
+        let items: Vec<_> = Iter::from_cbor(&cbor, options)
+            .map(|el| (el.path, el.indices, el.value))
+            .collect();
+
+        assert_eq!(
+            items,
+            vec![
+                ("[0][0]".into(), vec![0, 0], json!(7)),
+                ("[0][1]".into(), vec![0, 1], json!(8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn unrepresentable_numbers_become_strings() {
+        let cbor = CborValue::Array(vec![
+            CborValue::Float(f64::NAN),
+            CborValue::Integer(ciborium::value::Integer::try_from(-(1i128 << 64)).unwrap()),
+        ]);
+        let values: Vec<_> = Iter::from_cbor(&cbor, CborOptions::default())
+            .map(|el| el.value)
+            .collect();
+
+        assert_eq!(values, vec![json!("NaN"), json!("-18446744073709551616")]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::iter::Iter;
+use crate::node::JsonNode;
+use crate::path::PathSegment;
+use serde_json::Value;
+use std::fmt;
+
+/// Children of the base of the json structure still to be split into chunks
+enum Children<'a, V: JsonNode + 'a> {
+    Object(V::Entries<'a>),
+    Array(std::iter::Enumerate<V::Items<'a>>),
+    None,
+}
+
+impl<'a, V: JsonNode> Iter<'a, V> {
+    /// Consumes the iterator, yielding each top-level key or index along with an iterator over just that section
+    ///
+    /// Every section iterator has the same style and options, and yields exactly the elements this iterator would
+    /// have yielded for that section, so sections can be processed independently or handed to other threads.
+    /// The base of the json structure itself is never yielded, and a base that is neither an object nor an array
+    /// produces no sections.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathSegment, PresetStyle};
+    ///
+    /// let value = json!({"users": [{"id": 1}], "version": 2});
+    /// let sections: Vec<_> = Iterator::new(&value)
+    ///     .use_style(PresetStyle::CommonJs.into())
+    ///     .chunk_by_top_level()
+    ///     .map(|(segment, section)| (segment, section.map(|el| el.path).collect::<Vec<_>>()))
+    ///     .collect();
+    ///
+    /// assert_eq!(sections, vec![
+    ///     (PathSegment::Key("users"), vec![".users[0].id".to_string()]),
+    ///     (PathSegment::Key("version"), vec![".version".to_string()]),
+    /// ]);
+    /// ```
+    pub fn chunk_by_top_level(mut self) -> TopLevelChunks<'a, V> {
+        let children = match self.take_root() {
+            Some(root) => match (root.entries(), root.items()) {
+                (Some(entries), _) => Children::Object(entries),
+                (None, Some(items)) => Children::Array(items.enumerate()),
+                (None, None) => Children::None,
+            },
+            None => Children::None,
+        };
+        TopLevelChunks {
+            iter: self,
+            children,
+        }
+    }
+}
+
+/// Iterator yielding one section iterator per top-level key or index
+///
+/// Created by `Iterator::chunk_by_top_level`
+pub struct TopLevelChunks<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+    children: Children<'a, V>,
+}
+
+impl<'a, V: JsonNode> fmt::Debug for TopLevelChunks<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TopLevelChunks")
+            .field("iter", &self.iter)
+            .finish()
+    }
+}
+
+impl<'a, V: JsonNode> Iterator for TopLevelChunks<'a, V> {
+    type Item = (PathSegment<'a>, Iter<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (segment, value) = match &mut self.children {
+            Children::Object(entries) => entries
+                .next()
+                .map(|(key, val)| (PathSegment::Key(key), val))?,
+            Children::Array(items) => items
+                .next()
+                .map(|(index, val)| (PathSegment::Index(index), val))?,
+            Children::None => return None,
+        };
+        Some((segment, self.iter.subtree(segment, value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::StyleBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn sections_match_a_full_traversal() {
+        let value = json!([{"a": [1, {"b": 2}]}, [], 3]);
+        let style = || StyleBuilder::new().include_parents().build();
+        let whole: Vec<_> = Iter::new(&value).use_style(style()).skip(1).collect();
+        let sections: Vec<_> = Iter::new(&value)
+            .use_style(style())
+            .chunk_by_top_level()
+            .flat_map(|(_, section)| section)
+            .collect();
+
+        assert_eq!(sections, whole);
+    }
+
+    #[test]
+    fn leaf_base_has_no_sections() {
+        assert_eq!(Iter::new(&json!(1)).chunk_by_top_level().count(), 0);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::glob::PathGlob;
+use crate::mutate::for_each_mut_with;
+use crate::style::{PresetStyle, Style};
+use serde_json::{Number, Value};
+use std::fmt;
+
+/// Type a leaf is converted to by `coerce`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Coercion {
+    /// Numbers are kept, and strings holding a json number such as `" 12.5 "` are parsed
+    Number,
+    /// Booleans are kept, strings `true`, `false`, `yes`, `no`, `1`, and `0` in any case are parsed, and numbers `1` and `0` are converted
+    Bool,
+    /// Strings are kept, and numbers and booleans are written out as strings
+    String,
+    /// Whole seconds since the unix epoch, as a number or a string, are written out as an RFC 3339 UTC timestamp
+    Rfc3339,
+}
+
+impl fmt::Display for Coercion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Coercion::Number => "number",
+            Coercion::Bool => "bool",
+            Coercion::String => "string",
+            Coercion::Rfc3339 => "rfc3339",
+        })
+    }
+}
+
+/// Value that could not be coerced, left in place in the document
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoercionFailure {
+    /// Styled path of the value
+    pub path: String,
+    /// The value as it was found
+    pub value: Value,
+    /// The type it could not be converted to
+    pub target: Coercion,
+}
+
+/// Converts the values matching each glob to its type, returning every value that could not be converted
+/// with its path formatted in the default style
+///
+/// Rules are applied in order, so a later rule sees the output of an earlier one. `null` is left alone
+/// without counting as a failure, and matching objects and arrays always fail.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{coerce, Coercion};
+///
+/// let mut value = json!({"items": [{"qty": "2", "gift": "yes"}, {"qty": "two", "gift": 0}], "at": 1700000000});
+/// let rules = vec![
+///     ("items[*].qty".parse().unwrap(), Coercion::Number),
+///     ("items[*].gift".parse().unwrap(), Coercion::Bool),
+///     ("at".parse().unwrap(), Coercion::Rfc3339),
+/// ];
+/// let failures = coerce(&mut value, &rules);
+///
+/// assert_eq!(value, json!({"items": [{"qty": 2, "gift": true}, {"qty": "two", "gift": false}], "at": "2023-11-14T22:13:20Z"}));
+/// assert_eq!(failures[0].path, "[\"items\"][1][\"qty\"]");
+/// assert_eq!(failures.len(), 1);
+/// ```
+pub fn coerce(json: &mut Value, rules: &[(PathGlob, Coercion)]) -> Vec<CoercionFailure> {
+    coerce_with(json, rules, &PresetStyle::SquareBrackets.into())
+}
+
+/// Converts the values matching each glob to its type, returning failures with paths in the given style,
+/// see `coerce`
+pub fn coerce_with(
+    json: &mut Value,
+    rules: &[(PathGlob, Coercion)],
+    style: &Style,
+) -> Vec<CoercionFailure> {
+    let mut failures = Vec::new();
+    for (glob, target) in rules {
+        for_each_mut_with(json, glob, style, |path, value| {
+            if value.is_null() {
+                return;
+            }
+            match convert(value, *target) {
+                Some(converted) => *value = converted,
+                None => failures.push(CoercionFailure {
+                    path: path.into(),
+                    value: value.clone(),
+                    target: *target,
+                }),
+            }
+        });
+    }
+    failures
+}
+
+fn convert(value: &Value, target: Coercion) -> Option<Value> {
+    match (target, value) {
+        (Coercion::Number, Value::Number(_)) => Some(value.clone()),
+        (Coercion::Number, Value::String(s)) => s.trim().parse::<Number>().ok().map(Value::Number),
+        (Coercion::Bool, Value::Bool(_)) => Some(value.clone()),
+        (Coercion::Bool, Value::String(s)) => match s.trim().to_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(Value::Bool(true)),
+            "false" | "no" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (Coercion::Bool, Value::Number(n)) => match n.as_u64() {
+            Some(1) => Some(Value::Bool(true)),
+            Some(0) => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (Coercion::String, Value::String(_)) => Some(value.clone()),
+        (Coercion::String, Value::Number(n)) => Some(Value::String(n.to_string())),
+        (Coercion::String, Value::Bool(b)) => Some(Value::String(b.to_string())),
+        (Coercion::Rfc3339, Value::Number(n)) => n.as_i64().map(rfc3339),
+        (Coercion::Rfc3339, Value::String(s)) => s.trim().parse().ok().map(rfc3339),
+        _ => None,
+    }
+}
+
+/// Formats seconds since the unix epoch as an RFC 3339 UTC timestamp, using the proleptic Gregorian calendar
+fn rfc3339(seconds: i64) -> Value {
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // civil date from days since 1970-01-01, counting eras of 400 years from 0000-03-01
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    Value::String(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn formats_dates_around_leap_days_and_the_epoch() {
+        assert_eq!(rfc3339(0), json!("1970-01-01T00:00:00Z"));
+        assert_eq!(rfc3339(-1), json!("1969-12-31T23:59:59Z"));
+        assert_eq!(rfc3339(951_782_400), json!("2000-02-29T00:00:00Z"));
+        assert_eq!(rfc3339(4_107_542_399), json!("2100-02-28T23:59:59Z"));
+    }
+
+    #[test]
+    fn nulls_are_skipped_and_containers_fail() {
+        let mut value = json!({"a": null, "b": [1]});
+        let rules = vec![("*".parse().unwrap(), Coercion::String)];
+        let failures = coerce(&mut value, &rules);
+
+        assert_eq!(
+            failures,
+            vec![CoercionFailure {
+                path: "[\"b\"]".into(),
+                value: json!([1]),
+                target: Coercion::String,
+            }]
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use crate::glob::{GlobError, PathGlob};
+use crate::iter::Iter;
+use crate::style::StyleBuilder;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Error produced while extracting a typed column of values
+#[derive(Debug)]
+pub enum ColumnError {
+    /// The column pattern could not be parsed as a `PathGlob`
+    Glob(GlobError),
+    /// A matched value could not be deserialized into the requested type
+    Deserialize {
+        /// Path of the value that failed, formatted with the default style
+        path: String,
+        /// The underlying deserialization error
+        source: serde_json::Error,
+    },
+}
+
+impl fmt::Display for ColumnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnError::Glob(err) => err.fmt(f),
+            ColumnError::Deserialize { path, source } => {
+                write!(f, "failed to deserialize value at {}: {}", path, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColumnError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ColumnError::Glob(err) => Some(err),
+            ColumnError::Deserialize { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<GlobError> for ColumnError {
+    fn from(err: GlobError) -> Self {
+        ColumnError::Glob(err)
+    }
+}
+
+/// Deserializes every value matched by the pattern into `T`, in document order
+///
+/// Object and array values are matched as well as leaves, so a pattern such as `items[*]`
+/// can deserialize whole records. The first failure is returned along with the path of the offending value.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::collect_column;
+///
+/// let value = json!({"items": [{"price": 3.5}, {"price": 5}]});
+/// let prices: Vec<f64> = collect_column(&value, "items[*].price").unwrap();
+/// assert_eq!(prices, vec![3.5, 5.0]);
+///
+/// let value = json!({"items": [{"price": 3.5}, {"price": "free"}]});
+/// let err = collect_column::<f64>(&value, "items[*].price").unwrap_err();
+/// assert!(err.to_string().starts_with("failed to deserialize value at [\"items\"][1][\"price\"]"));
+/// ```
+pub fn collect_column<'a, T>(json: &'a Value, pattern: &str) -> Result<Vec<T>, ColumnError>
+where
+    T: Deserialize<'a>,
+{
+    let glob = PathGlob::new(pattern)?;
+    let style = StyleBuilder::new()
+        .include_object_parents()
+        .include_array_parents()
+        .build();
+
+    Iter::new(json)
+        .use_style(style)
+        .find_all_paths(glob)
+        .map(|el| {
+            T::deserialize(el.value).map_err(|source| ColumnError::Deserialize {
+                path: el.path,
+                source,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn collects_whole_records() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Item<'a> {
+            name: &'a str,
+        }
+
+        let value = json!({"items": [{"name": "a"}, {"name": "b"}]});
+        let items: Vec<Item> = collect_column(&value, "items[*]").unwrap();
+
+        assert_eq!(items, vec![Item { name: "a" }, Item { name: "b" }]);
+    }
+
+    #[test]
+    fn reports_glob_errors() {
+        let value = json!(null);
+
+        assert!(matches!(
+            collect_column::<bool>(&value, "items[").unwrap_err(),
+            ColumnError::Glob(_)
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::iter::Iter;
+use crate::node::{JsonNode, NodeKind};
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Differences in shape between two documents, found by `compare_paths`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathsDiff {
+    /// Paths in the expected document that the actual document does not have, sorted
+    pub missing: Vec<String>,
+    /// Paths in the actual document that the expected document does not have, sorted
+    pub extra: Vec<String>,
+    /// Paths in both documents whose values are of different kinds, sorted by path
+    pub type_mismatches: Vec<TypeMismatch>,
+}
+
+impl PathsDiff {
+    /// Whether both documents have the same shape
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.type_mismatches.is_empty()
+    }
+}
+
+/// Path whose values are of different kinds in the two documents
+///
+/// With wildcard indices one path can stand for many values, so every distinct kind is listed in document order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeMismatch {
+    /// The wildcarded path
+    pub path: String,
+    /// Kinds of the values at the path in the expected document
+    pub expected: Vec<NodeKind>,
+    /// Kinds of the values at the path in the actual document
+    pub actual: Vec<NodeKind>,
+}
+
+/// Compares the shape of two documents, ignoring values and array lengths
+///
+/// Every value, including objects and arrays, is compared by its path with array indices replaced by `[*]`,
+/// so an expected array with one entry describes the shape of every entry in the actual array.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{compare_paths, NodeKind, TypeMismatch};
+///
+/// let expected = json!({"id": 1, "tags": [""], "owner": {"name": ""}});
+/// let actual = json!({"id": "7", "tags": ["a", "b"], "extra": null});
+/// let diff = compare_paths(&expected, &actual);
+///
+/// assert_eq!(diff.missing, vec!["[\"owner\"]", "[\"owner\"][\"name\"]"]);
+/// assert_eq!(diff.extra, vec!["[\"extra\"]"]);
+/// assert_eq!(diff.type_mismatches, vec![TypeMismatch {
+///     path: "[\"id\"]".into(),
+///     expected: vec![NodeKind::Number],
+///     actual: vec![NodeKind::String],
+/// }]);
+/// ```
+pub fn compare_paths(expected: &Value, actual: &Value) -> PathsDiff {
+    let style = PresetStyle::SquareBrackets
+        .builder()
+        .include_parents()
+        .wildcard_array_keys_in_path()
+        .build();
+    let expected = kinds_by_path(expected, &style);
+    let mut actual = kinds_by_path(actual, &style);
+
+    let mut diff = PathsDiff::default();
+    for (path, expected_kinds) in expected {
+        match actual.remove(&path) {
+            None => diff.missing.push(path),
+            Some(actual_kinds) if !same_kinds(&expected_kinds, &actual_kinds) => {
+                diff.type_mismatches.push(TypeMismatch {
+                    path,
+                    expected: expected_kinds,
+                    actual: actual_kinds,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    diff.extra = actual.into_keys().collect();
+    diff
+}
+
+/// Distinct kinds of the values at each path, in the order they were first seen
+fn kinds_by_path(json: &Value, style: &Style) -> BTreeMap<String, Vec<NodeKind>> {
+    let mut kinds: BTreeMap<String, Vec<NodeKind>> = BTreeMap::new();
+    for (path, value) in Iter::new(json).use_style(style.clone()).pairs() {
+        let seen = kinds.entry(path).or_default();
+        if !seen.contains(&value.kind()) {
+            seen.push(value.kind());
+        }
+    }
+    kinds
+}
+
+fn same_kinds(left: &[NodeKind], right: &[NodeKind]) -> bool {
+    left.len() == right.len() && left.iter().all(|kind| right.contains(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ignores_values_and_array_lengths() {
+        let expected = json!({"rows": [{"a": 1}]});
+        let actual = json!({"rows": [{"a": 2}, {"a": 3}, {"a": 4}]});
+
+        assert!(compare_paths(&expected, &actual).is_empty());
+    }
+
+    #[test]
+    fn mixed_kinds_compare_as_sets() {
+        let expected = json!([1, "a"]);
+
+        assert!(compare_paths(&expected, &json!(["b", 2])).is_empty());
+        assert_eq!(
+            compare_paths(&expected, &json!([1])).type_mismatches[0].actual,
+            vec![NodeKind::Number]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::iter::Iter;
+use crate::table::{to_table_with, TableError, TableOptions};
+use serde_json::Value;
+use std::fmt;
+use std::io;
+
+/// Shape of the rows written by `write_csv`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvLayout {
+    /// One row per leaf with the columns `path`, `indices`, and `value`, streamed without buffering
+    Long,
+    /// One row per element of an array of objects with one column per flattened path, see `to_table_with`
+    Wide,
+}
+
+/// Options controlling how `write_csv` lays out rows
+#[derive(Debug, Clone)]
+pub struct CsvOptions<'a> {
+    /// Row layout, `CsvLayout::Long` by default
+    pub layout: CsvLayout,
+    /// Style and policies used to flatten values, only `table.style` applies to `CsvLayout::Long`
+    pub table: TableOptions<'a>,
+}
+
+impl<'a> Default for CsvOptions<'a> {
+    fn default() -> Self {
+        CsvOptions {
+            layout: CsvLayout::Long,
+            table: TableOptions::default(),
+        }
+    }
+}
+
+/// Error produced while writing csv rows
+#[derive(Debug)]
+pub enum CsvError {
+    /// The value could not be laid out as a wide table
+    Table(TableError),
+    /// The csv writer failed
+    Csv(csv::Error),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Table(err) => err.fmt(f),
+            CsvError::Csv(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Table(err) => Some(err),
+            CsvError::Csv(err) => Some(err),
+        }
+    }
+}
+
+impl From<TableError> for CsvError {
+    fn from(err: TableError) -> Self {
+        CsvError::Table(err)
+    }
+}
+
+impl From<csv::Error> for CsvError {
+    fn from(err: csv::Error) -> Self {
+        CsvError::Csv(err)
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(err: io::Error) -> Self {
+        CsvError::Csv(err.into())
+    }
+}
+
+/// Writes flattened rows of a json value into a csv writer, including a header row, then flushes it
+///
+/// Strings are written without quotes, `null` as an empty cell, and everything else as json.
+/// In the long layout, indices are joined with `.` so `[1][0]` becomes `1.0`.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{write_csv, CsvLayout, CsvOptions, PresetStyle, TableOptions};
+///
+/// let value = json!([{"id": 1, "tags": ["a"]}, {"id": 2, "tags": ["b"]}]);
+/// let table = TableOptions { style: PresetStyle::CommonJs.into(), ..TableOptions::default() };
+///
+/// let options = CsvOptions { layout: CsvLayout::Long, table: table.clone() };
+/// let mut writer = csv::Writer::from_writer(Vec::new());
+/// write_csv(&value, &mut writer, &options).unwrap();
+/// let long = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+/// assert_eq!(long, "path,indices,value\n[0].id,0,1\n[0].tags[0],0.0,a\n[1].id,1,2\n[1].tags[0],1.0,b\n");
+///
+/// let options = CsvOptions { layout: CsvLayout::Wide, table };
+/// let mut writer = csv::Writer::from_writer(Vec::new());
+/// write_csv(&value, &mut writer, &options).unwrap();
+/// let wide = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+/// assert_eq!(wide, ".id,.tags[0]\n1,a\n2,b\n");
+/// ```
+pub fn write_csv<W: io::Write>(
+    json: &Value,
+    writer: &mut csv::Writer<W>,
+    options: &CsvOptions,
+) -> Result<(), CsvError> {
+    match options.layout {
+        CsvLayout::Long => {
+            writer.write_record(["path", "indices", "value"])?;
+            for el in Iter::new(json).use_style(options.table.style.clone()) {
+                let indices: Vec<_> = el.indices.iter().map(usize::to_string).collect();
+                writer.write_record([el.path, indices.join("."), cell(el.value)])?;
+            }
+        }
+        CsvLayout::Wide => {
+            let table = to_table_with(json, &options.table)?;
+            writer.write_record(&table.headers)?;
+            for row in &table.rows {
+                writer.write_record(row.iter().map(cell))?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::PresetStyle;
+    use serde_json::json;
+
+    #[test]
+    fn long_layout_uses_table_style() {
+        let value = json!({"a": [null, {"b": true}]});
+        let options = CsvOptions {
+            table: TableOptions {
+                style: PresetStyle::CommonJs.into(),
+                ..TableOptions::default()
+            },
+            ..CsvOptions::default()
+        };
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        write_csv(&value, &mut writer, &options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.into_inner().unwrap()).unwrap(),
+            "path,indices,value\n.a[0],0,\n.a[1].b,1,true\n"
+        );
+    }
+
+    #[test]
+    fn wide_layout_reports_table_errors() {
+        let options = CsvOptions {
+            layout: CsvLayout::Wide,
+            ..CsvOptions::default()
+        };
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        assert!(matches!(
+            write_csv(&json!({}), &mut writer, &options),
+            Err(CsvError::Table(TableError::NotAnArray))
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+use crate::path::{PathParseError, PathSegment};
+use crate::style::Style;
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Error produced while rebuilding a nested value from flattened `(path, value)` pairs
+#[derive(Debug)]
+pub enum UnflattenError {
+    /// A path could not be parsed with the style
+    Parse(PathParseError),
+    /// A path needs a value to be an object, array, or leaf when another path already made it something else,
+    /// or its index would pad arrays with more `null`s than there are pairs
+    Conflict {
+        /// The path that could not be inserted
+        path: String,
+    },
+    /// The rebuilt value could not be deserialized into the requested type
+    Deserialize(serde_json::Error),
+}
+
+impl fmt::Display for UnflattenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnflattenError::Parse(err) => err.fmt(f),
+            UnflattenError::Conflict { path } => {
+                write!(f, "path {:?} conflicts with an earlier path", path)
+            }
+            UnflattenError::Deserialize(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for UnflattenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UnflattenError::Parse(err) => Some(err),
+            UnflattenError::Conflict { .. } => None,
+            UnflattenError::Deserialize(err) => Some(err),
+        }
+    }
+}
+
+impl From<PathParseError> for UnflattenError {
+    fn from(err: PathParseError) -> Self {
+        UnflattenError::Parse(err)
+    }
+}
+
+/// Rebuilds a nested value from flattened `(path, value)` pairs formatted with the given style
+///
+/// Missing array entries before the highest index are filled with `null`, up to one per pair in all,
+/// so an index far past the other pairs is a conflict rather than a huge allocation.
+/// A later pair for the same path replaces the earlier leaf.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{unflatten, PresetStyle};
+///
+/// let pairs = vec![
+///     (".db.hosts[1]", json!("replica")),
+///     (".db.hosts[0]", json!("primary")),
+///     (".db.port", json!(5432)),
+/// ];
+/// let value = unflatten(pairs, &PresetStyle::CommonJs.into()).unwrap();
+///
+/// assert_eq!(value, json!({"db": {"hosts": ["primary", "replica"], "port": 5432}}));
+/// ```
+pub fn unflatten<I, K>(pairs: I, style: &Style) -> Result<Value, UnflattenError>
+where
+    I: IntoIterator<Item = (K, Value)>,
+    K: AsRef<str>,
+{
+    let pairs: Vec<_> = pairs.into_iter().collect();
+    let mut padding = pairs.len();
+    let mut root = Value::Null;
+    for (path, value) in pairs {
+        let path = path.as_ref();
+        let segments = style.parse_path(path)?;
+        insert(&mut root, &segments, value, &mut padding)
+            .ok_or_else(|| UnflattenError::Conflict { path: path.into() })?;
+    }
+    Ok(root)
+}
+
+/// Inserts a value below a node, treating `null` as not yet set, or `None` on conflict
+///
+/// Arrays are padded with `null` up to the index, taking the added entries from `padding`,
+/// and an index that needs more than is left is a conflict.
+pub(crate) fn insert(
+    node: &mut Value,
+    segments: &[PathSegment],
+    value: Value,
+    padding: &mut usize,
+) -> Option<()> {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            return match node {
+                Value::Object(obj) if !obj.is_empty() => None,
+                Value::Array(arr) if !arr.is_empty() => None,
+                _ => {
+                    *node = value;
+                    Some(())
+                }
+            };
+        }
+    };
+
+    match segment {
+        PathSegment::Key(key) => {
+            if node.is_null() {
+                *node = Value::Object(Map::new());
+            }
+            let child = node.as_object_mut()?.entry(*key).or_insert(Value::Null);
+            insert(child, rest, value, padding)
+        }
+        PathSegment::Index(index) => {
+            if node.is_null() {
+                *node = Value::Array(Vec::new());
+            }
+            let arr = node.as_array_mut()?;
+            if arr.len() <= *index {
+                *padding = padding.checked_sub(index - arr.len())?;
+                arr.resize(index + 1, Value::Null);
+            }
+            insert(&mut arr[*index], rest, value, padding)
+        }
+    }
+}
+
+/// Whether `insert` would succeed with no padding left, without changing anything
+pub(crate) fn insertable(node: &Value, segments: &[PathSegment]) -> bool {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            return match node {
+                Value::Object(obj) => obj.is_empty(),
+                Value::Array(arr) => arr.is_empty(),
+                _ => true,
+            };
+        }
+    };
+
+    match (segment, node) {
+        (PathSegment::Key(_), Value::Null) => insertable(&Value::Null, rest),
+        (PathSegment::Index(index), Value::Null) => *index == 0 && insertable(&Value::Null, rest),
+        (PathSegment::Key(key), Value::Object(obj)) => match obj.get(*key) {
+            Some(child) => insertable(child, rest),
+            None => insertable(&Value::Null, rest),
+        },
+        (PathSegment::Index(index), Value::Array(arr)) => match arr.get(*index) {
+            Some(child) => insertable(child, rest),
+            None => *index == arr.len() && insertable(&Value::Null, rest),
+        },
+        _ => false,
+    }
+}
+
+/// Deserializer that rebuilds nested structure from flattened `(path, value)` pairs, such as
+/// environment variables or key-value store entries, and deserializes it into any `T: Deserialize`
+///
+/// Example:
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_json::json;
+/// use json_keypath_iter::{FlatDeserializer, PresetStyle};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let pairs = vec![
+///     ("[\"name\"]", json!("api")),
+///     ("[\"ports\"][0]", json!(80)),
+///     ("[\"ports\"][1]", json!(443)),
+/// ];
+/// let deserializer = FlatDeserializer::from_pairs(pairs, &PresetStyle::SquareBrackets.into()).unwrap();
+/// let config = Config::deserialize(deserializer).unwrap();
+///
+/// assert_eq!(config, Config { name: "api".into(), ports: vec![80, 443] });
+/// ```
+#[derive(Debug)]
+pub struct FlatDeserializer {
+    value: Value,
+}
+
+impl FlatDeserializer {
+    /// Rebuilds the nested value that will be deserialized, see `unflatten`
+    pub fn from_pairs<I, K>(pairs: I, style: &Style) -> Result<Self, UnflattenError>
+    where
+        I: IntoIterator<Item = (K, Value)>,
+        K: AsRef<str>,
+    {
+        unflatten(pairs, style).map(|value| FlatDeserializer { value })
+    }
+}
+
+/// Deserializes a `T` from flattened `(path, value)` pairs, see `FlatDeserializer`
+pub fn from_flat_pairs<T, I, K>(pairs: I, style: &Style) -> Result<T, UnflattenError>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, Value)>,
+    K: AsRef<str>,
+{
+    let deserializer = FlatDeserializer::from_pairs(pairs, style)?;
+    T::deserialize(deserializer).map_err(UnflattenError::Deserialize)
+}
+
+macro_rules! delegate_to_value {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+                self.value.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for FlatDeserializer {
+    type Error = serde_json::Error;
+
+    delegate_to_value! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::Iter;
+    use crate::style::PresetStyle;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_iterated_leaves() {
+        let value = json!({"a": [1, {"b": [true, null]}], "c": {"d": "e"}});
+        let pairs: Vec<_> = Iter::new(&value)
+            .map(|el| (el.path, el.value.clone()))
+            .collect();
+
+        assert_eq!(
+            unflatten(pairs, &PresetStyle::SquareBrackets.into()).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn reports_conflicting_paths() {
+        let pairs = vec![(".a", json!(1)), (".a[0]", json!(2))];
+
+        assert!(matches!(
+            unflatten(pairs, &PresetStyle::CommonJs.into()),
+            Err(UnflattenError::Conflict { path }) if path == ".a[0]"
+        ));
+    }
+
+    #[test]
+    fn fills_sparse_arrays_with_nulls() {
+        let pairs = vec![("->2", json!("x")), ("->3", json!("y"))];
+
+        assert_eq!(
+            unflatten(pairs, &PresetStyle::PostgresJson.into()).unwrap(),
+            json!([null, null, "x", "y"])
+        );
+    }
+
+    #[test]
+    fn pads_with_at_most_one_null_per_pair() {
+        let style = PresetStyle::SquareBrackets.into();
+        for path in ["[18446744073709551615]", "[100000000000]", "[2]"].iter() {
+            assert!(matches!(
+                unflatten(vec![(*path, json!(1))], &style),
+                Err(UnflattenError::Conflict { path: p }) if p == *path
+            ));
+        }
+
+        let pairs = vec![("[\"a\"][1]", json!(1)), ("[\"b\"][1]", json!(2))];
+        assert_eq!(
+            unflatten(pairs.clone(), &style).unwrap(),
+            json!({"a": [null, 1], "b": [null, 2]})
+        );
+        let pairs = vec![pairs[0].clone(), ("[\"b\"][2]", json!(2))];
+        assert!(unflatten(pairs, &style).is_err());
+    }
+
+    #[test]
+    fn deserialize_errors_are_wrapped() {
+        let pairs = vec![("[\"a\"]", json!("not a number"))];
+        let result: Result<std::collections::HashMap<String, u8>, _> =
+            from_flat_pairs(pairs, &PresetStyle::SquareBrackets.into());
+
+        assert!(matches!(result, Err(UnflattenError::Deserialize(_))));
+    }
+}
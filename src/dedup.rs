@@ -0,0 +1,168 @@
+use crate::iter::{Iter, Pairs};
+use serde_json::Value;
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+impl<'a> Iter<'a> {
+    /// Consumes the iterator, yielding `(path, value)` tuples like `pairs` but skipping any pair already yielded
+    ///
+    /// Pairs repeat when the style collapses paths, such as with wildcard indices. Every distinct pair is remembered,
+    /// so memory grows with the number of distinct pairs rather than staying constant.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, StyleBuilder};
+    ///
+    /// let value = json!({"tags": ["a", "b", "a"]});
+    /// let style = StyleBuilder::new().wildcard_array_keys_in_path().build();
+    /// let pairs: Vec<_> = Iterator::new(&value).use_style(style).dedup_pairs().collect();
+    ///
+    /// assert_eq!(pairs, vec![
+    ///     ("[\"tags\"][*]".into(), &json!("a")),
+    ///     ("[\"tags\"][*]".into(), &json!("b")),
+    /// ]);
+    /// ```
+    pub fn dedup_pairs(self) -> DedupPairs<'a> {
+        self.dedup_pairs_with_hasher(RandomState::new())
+    }
+
+    /// Same as `dedup_pairs`, but remembering pairs in a set built with the given hasher
+    pub fn dedup_pairs_with_hasher<S: BuildHasher>(self, hasher: S) -> DedupPairs<'a, S> {
+        DedupPairs {
+            pairs: self.pairs(),
+            seen: HashSet::with_hasher(hasher),
+        }
+    }
+}
+
+/// Iterator yielding each distinct `(path, value)` tuple once, in the order first seen
+///
+/// Created by `Iterator::dedup_pairs`
+#[derive(Debug)]
+pub struct DedupPairs<'a, S = RandomState> {
+    pairs: Pairs<'a, Value>,
+    seen: HashSet<SeenPair<'a>, S>,
+}
+
+impl<'a, S: BuildHasher> Iterator for DedupPairs<'a, S> {
+    type Item = (String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (path, value) in self.pairs.by_ref() {
+            if self.seen.insert(SeenPair(path.clone(), value)) {
+                return Some((path, value));
+            }
+        }
+        None
+    }
+}
+
+/// Pair already yielded, hashed by walking the value since `serde_json::Value` does not implement `Hash`
+#[derive(Debug, PartialEq, Eq)]
+struct SeenPair<'a>(String, &'a Value);
+
+impl<'a> Hash for SeenPair<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+        hash_value(self.1, state);
+    }
+}
+
+/// Value compared and hashed whole, so values can be kept in a `HashSet` although `serde_json::Value` is not `Hash`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct HashedValue<'a>(pub(crate) &'a Value);
+
+impl<'a> Hash for HashedValue<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_value(self.0, state);
+    }
+}
+
+fn hash_value<H: Hasher>(value: &Value, state: &mut H) {
+    match value {
+        Value::Null => state.write_u8(0),
+        Value::Bool(b) => {
+            state.write_u8(1);
+            b.hash(state);
+        }
+        Value::Number(n) => {
+            state.write_u8(2);
+            // `-0.0` equals `0.0` but prints differently, so every zero hashes alike
+            match n.as_f64() {
+                Some(0.0) => state.write_u8(0),
+                _ => n.to_string().hash(state),
+            }
+        }
+        Value::String(s) => {
+            state.write_u8(3);
+            s.hash(state);
+        }
+        Value::Array(arr) => {
+            state.write_u8(4);
+            state.write_usize(arr.len());
+            for item in arr {
+                hash_value(item, state);
+            }
+        }
+        Value::Object(obj) => {
+            // objects are equal whatever order their entries are in, so the entry hashes are summed
+            state.write_u8(5);
+            state.write_usize(obj.len());
+            let entries = obj.iter().fold(0u64, |sum, (key, val)| {
+                let mut entry = DefaultHasher::new();
+                key.hash(&mut entry);
+                hash_value(val, &mut entry);
+                sum.wrapping_add(entry.finish())
+            });
+            state.write_u64(entries);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::StyleBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_equal_values_at_different_paths() {
+        let value = json!({"a": 1, "b": 1, "c": [{"d": 1}, {"d": 1}]});
+        let style = StyleBuilder::new()
+            .wildcard_array_keys_in_path()
+            .include_array_parents()
+            .build();
+        let paths: Vec<_> = Iter::new(&value)
+            .use_style(style)
+            .dedup_pairs()
+            .map(|(path, _)| path)
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec!["[\"a\"]", "[\"b\"]", "[\"c\"]", "[\"c\"][*][\"d\"]"]
+        );
+    }
+
+    #[test]
+    fn equal_objects_hash_alike_whatever_their_order() {
+        let hash = |text: &str| {
+            let value: Value = serde_json::from_str(text).unwrap();
+            let mut state = DefaultHasher::new();
+            hash_value(&value, &mut state);
+            (value, state.finish())
+        };
+        let (first, first_hash) = hash(r#"{"a": 1, "b": {"c": [2], "d": null}}"#);
+        let (second, second_hash) = hash(r#"{"b": {"d": null, "c": [2]}, "a": 1}"#);
+
+        assert_eq!(first, second);
+        assert_eq!(first_hash, second_hash);
+
+        let (zero, zero_hash) = hash("0.0");
+        let (negative_zero, negative_zero_hash) = hash("-0.0");
+        // the two are only equal without `arbitrary_precision`
+        assert!(zero != negative_zero || zero_hash == negative_zero_hash);
+    }
+}
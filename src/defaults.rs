@@ -0,0 +1,77 @@
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+
+/// Inserts every path of the template that is missing from the document, with the template's value,
+/// returning the paths added formatted with the default style
+///
+/// Values already in the document always win, including `null`. Only objects are merged key by key,
+/// so an array, or a value of a different kind where the template has an object, is kept whole.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::fill_defaults;
+///
+/// let mut config = json!({"hosts": ["a"], "server": {"port": 8080}});
+/// let template = json!({"debug": false, "hosts": ["x", "y"], "server": {"port": 80, "tls": {"enabled": false}}});
+/// let added = fill_defaults(&mut config, &template);
+///
+/// assert_eq!(config, json!({"debug": false, "hosts": ["a"], "server": {"port": 8080, "tls": {"enabled": false}}}));
+/// assert_eq!(added, vec!["[\"debug\"]", "[\"server\"][\"tls\"]"]);
+/// ```
+pub fn fill_defaults(json: &mut Value, template: &Value) -> Vec<String> {
+    fill_defaults_with(json, template, &PresetStyle::SquareBrackets.into())
+}
+
+/// Inserts every path of the template that is missing from the document, returning the paths added in the style,
+/// see `fill_defaults`
+pub fn fill_defaults_with(json: &mut Value, template: &Value, style: &Style) -> Vec<String> {
+    let mut added = Vec::new();
+    fill(json, template, style, &mut String::new(), &mut added);
+    added
+}
+
+fn fill(
+    node: &mut Value,
+    template: &Value,
+    style: &Style,
+    path: &mut String,
+    added: &mut Vec<String>,
+) {
+    let (obj, defaults) = match (node, template) {
+        (Value::Object(obj), Value::Object(defaults)) => (obj, defaults),
+        _ => return,
+    };
+
+    let path_len = path.len();
+    for (key, default) in defaults {
+        style.push_object_segment(path, key);
+        match obj.get_mut(key) {
+            Some(existing) => fill(existing, default, style, path, added),
+            None => {
+                obj.insert(key.clone(), default.clone());
+                added.push(style.wrap_path(path));
+            }
+        }
+        path.truncate(path_len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn existing_values_win_over_objects() {
+        let mut value = json!({"a": null, "b": 1});
+        let added = fill_defaults_with(
+            &mut value,
+            &json!({"a": {"x": 1}, "b": {"y": 2}}),
+            &PresetStyle::CommonJs.into(),
+        );
+
+        assert!(added.is_empty());
+        assert_eq!(value, json!({"a": null, "b": 1}));
+    }
+}
@@ -0,0 +1,136 @@
+use super::{walk, DiffOptions, Event};
+use crate::path::PathSegment;
+use serde_json::{json, Map, Value};
+
+/// Describes the differences between two documents as a jsondiffpatch delta, or `None` when they are equal
+///
+/// Added values are written as `[new]`, modified values as `[old, new]`, and removed values as `[old, 0, 0]`,
+/// nested in objects that mirror the document. Arrays are marked with `"_t": "a"`, with entries keyed by their index
+/// in the new array, or by `_` and their index in the old array when removed.
+/// Elements of unordered arrays that changed position are written as `["", new_index, 3]` under `_` and their
+/// old index, alongside any changes inside them under their new index.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::diff_delta;
+///
+/// let old = json!({"name": "app", "ports": [80, 443], "debug": true});
+/// let new = json!({"name": "app", "ports": [8080], "tls": {}});
+///
+/// assert_eq!(diff_delta(&old, &new), Some(json!({
+///     "debug": [true, 0, 0],
+///     "ports": {"_t": "a", "0": [80, 8080], "_1": [443, 0, 0]},
+///     "tls": [{}],
+/// })));
+/// assert_eq!(diff_delta(&old, &old), None);
+/// ```
+pub fn diff_delta(old: &Value, new: &Value) -> Option<Value> {
+    diff_delta_with(old, new, &DiffOptions::default())
+}
+
+/// Describes the differences between two documents as a jsondiffpatch delta with the given options, see `diff_delta`
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{diff_delta_with, DiffOptions};
+///
+/// let options = DiffOptions::new().unordered_arrays("tags".parse().unwrap(), None);
+/// let old = json!({"tags": ["a", "b", "c"]});
+/// let new = json!({"tags": ["c", "a"]});
+///
+/// assert_eq!(diff_delta_with(&old, &new, &options), Some(json!({
+///     "tags": {"_t": "a", "_0": ["", 1, 3], "_1": ["b", 0, 0], "_2": ["", 0, 3]},
+/// })));
+/// ```
+pub fn diff_delta_with(old: &Value, new: &Value, options: &DiffOptions) -> Option<Value> {
+    let mut delta = None;
+    walk(old, new, options, |segments, event| {
+        let (entry, from) = match event {
+            Event::Added(value) => (json!([value]), None),
+            Event::Removed(value) => (json!([value, 0, 0]), last_index(segments)),
+            Event::Modified(old, new) => (json!([old, new]), None),
+            Event::Moved(from) => (json!(["", last_index(segments), 3]), Some(from)),
+        };
+        if let Some(slot) = entry_for(delta.get_or_insert(Value::Null), segments, from) {
+            *slot = entry;
+        }
+    });
+    delta
+}
+
+/// Index of the last of the segments, if it is an array element
+fn last_index(segments: &[PathSegment]) -> Option<usize> {
+    match segments.last() {
+        Some(PathSegment::Index(index)) => Some(*index),
+        _ => None,
+    }
+}
+
+/// Slot of the delta for the change at the segments, adding the objects leading to it,
+/// or `None` if a change was already written at one of the parents
+///
+/// The last segment is keyed by `_` and the index `from` in the old array when given, for removals and moves.
+fn entry_for<'d>(
+    delta: &'d mut Value,
+    segments: &[PathSegment],
+    from: Option<usize>,
+) -> Option<&'d mut Value> {
+    let mut node = delta;
+    for (position, segment) in segments.iter().enumerate() {
+        if node.is_null() {
+            *node = match segment {
+                PathSegment::Key(_) => Value::Object(Map::new()),
+                PathSegment::Index(_) => json!({"_t": "a"}),
+            };
+        }
+        let key = match (segment, from) {
+            (PathSegment::Key(key), _) => key.to_string(),
+            (PathSegment::Index(_), Some(from)) if position + 1 == segments.len() => {
+                format!("_{}", from)
+            }
+            (PathSegment::Index(index), _) => index.to_string(),
+        };
+        node = match node.as_object_mut() {
+            Some(obj) => obj.entry(key).or_insert(Value::Null),
+            None => return None,
+        };
+    }
+    Some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_changes_inside_arrays_and_at_the_root() {
+        let old = json!([{"a": 1}, 2]);
+        let new = json!([{"a": 1, "b": 2}]);
+
+        assert_eq!(
+            diff_delta(&old, &new),
+            Some(json!({"_t": "a", "0": {"b": [2]}, "_1": [2, 0, 0]}))
+        );
+        assert_eq!(diff_delta(&json!(1), &json!("x")), Some(json!([1, "x"])));
+    }
+
+    #[test]
+    fn unordered_elements_that_moved_keep_their_changes_at_the_new_index() {
+        let key = [PathSegment::Key("id")];
+        let options = DiffOptions::new().unordered_arrays("".parse().unwrap(), Some(&key));
+        let old = json!([{"id": 1, "v": "a"}, {"id": 2, "v": "b"}]);
+        let new = json!([{"id": 2, "v": "c"}, {"id": 1, "v": "a"}]);
+
+        assert_eq!(
+            diff_delta_with(&old, &new, &options),
+            Some(json!({
+                "_t": "a",
+                "_1": ["", 0, 3],
+                "0": {"v": ["b", "c"]},
+                "_0": ["", 1, 3],
+            }))
+        );
+    }
+}
@@ -0,0 +1,411 @@
+mod delta;
+mod similarity;
+mod three_way;
+pub use delta::{diff_delta, diff_delta_with};
+pub use similarity::{similarity, similarity_with};
+pub use three_way::{diff3, diff3_with, Diff3, MergeConflict, MergeDecision, Side};
+
+use crate::glob::PathGlob;
+use crate::path::{get, PathSegment};
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+use std::ops::Range;
+
+/// Single difference between two documents, found by `diff`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A value only in the new document
+    Added {
+        /// Path of the value in the new document
+        path: String,
+        /// The added value
+        value: Value,
+    },
+    /// A value only in the old document
+    Removed {
+        /// Path of the value in the old document
+        path: String,
+        /// The removed value
+        value: Value,
+    },
+    /// A value that differs between the documents, where they are not both objects or arrays
+    Modified {
+        /// Path of the value in the new document
+        path: String,
+        /// The value in the old document
+        old: Value,
+        /// The value in the new document
+        new: Value,
+    },
+}
+
+impl Change {
+    /// Path the change was found at
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Modified { path, .. } => path,
+        }
+    }
+}
+
+/// Options controlling how `diff_with` compares documents
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions<'k> {
+    unordered: Vec<(PathGlob, Option<&'k [PathSegment<'k>]>)>,
+}
+
+impl<'k> DiffOptions<'k> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compares the arrays matching the glob as multisets, ignoring the order of their elements
+    ///
+    /// Elements are paired up by the value found at `key` within them, and the changes inside each pair are reported
+    /// at the element's index in the new array. Without a key, or for elements without a value at `key`,
+    /// only equal elements are paired, so every other element shows up as removed or added.
+    /// Pairs that changed position are not changes here, though `diff_delta_with` writes them as moves.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{diff_with, Change, DiffOptions, PathSegment, PresetStyle};
+    ///
+    /// let old = json!({"tags": ["a", "b"], "users": [{"id": 1, "name": "ada"}, {"id": 2, "name": "bob"}]});
+    /// let new = json!({"tags": ["b", "a"], "users": [{"id": 2, "name": "bob"}, {"id": 1, "name": "eve"}]});
+    /// let options = DiffOptions::new()
+    ///     .unordered_arrays("tags".parse().unwrap(), None)
+    ///     .unordered_arrays("users".parse().unwrap(), Some(&[PathSegment::Key("id")]));
+    ///
+    /// assert_eq!(diff_with(&old, &new, &options, &PresetStyle::CommonJs.into()), vec![Change::Modified {
+    ///     path: ".users[1].name".into(),
+    ///     old: json!("ada"),
+    ///     new: json!("eve"),
+    /// }]);
+    /// ```
+    pub fn unordered_arrays(mut self, glob: PathGlob, key: Option<&'k [PathSegment<'k>]>) -> Self {
+        self.unordered.push((glob, key));
+        self
+    }
+}
+
+/// Lists the differences between two documents, formatting paths with the default style
+///
+/// Objects are compared key by key, and arrays are aligned on a longest common subsequence of equal elements,
+/// so inserting one element reports a single addition. Elements between aligned ones are compared by position,
+/// and what is left over on either side is reported as removed or added. Changes inside arrays are reported at
+/// the index in the new array, except removals which use the index in the old array. Changes are listed in
+/// document order, with added object entries after the rest of their object.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{diff, Change};
+///
+/// let old = json!({"debug": true, "name": "app", "ports": [80, 443]});
+/// let new = json!({"name": "app", "ports": [8080], "tls": {}});
+///
+/// assert_eq!(diff(&old, &new), vec![
+///     Change::Removed { path: "[\"debug\"]".into(), value: json!(true) },
+///     Change::Modified { path: "[\"ports\"][0]".into(), old: json!(80), new: json!(8080) },
+///     Change::Removed { path: "[\"ports\"][1]".into(), value: json!(443) },
+///     Change::Added { path: "[\"tls\"]".into(), value: json!({}) },
+/// ]);
+/// ```
+pub fn diff(old: &Value, new: &Value) -> Vec<Change> {
+    diff_with(
+        old,
+        new,
+        &DiffOptions::default(),
+        &PresetStyle::SquareBrackets.into(),
+    )
+}
+
+/// Lists the differences between two documents with the given options, formatting paths in the style, see `diff`
+pub fn diff_with(old: &Value, new: &Value, options: &DiffOptions, style: &Style) -> Vec<Change> {
+    let mut changes = Vec::new();
+    walk(old, new, options, |segments, event| {
+        let path = style.format_segments(segments);
+        changes.push(match event {
+            // paired elements of unordered arrays are the same element wherever they are
+            Event::Moved(_) => return,
+            Event::Added(value) => Change::Added {
+                path,
+                value: value.clone(),
+            },
+            Event::Removed(value) => Change::Removed {
+                path,
+                value: value.clone(),
+            },
+            Event::Modified(old, new) => Change::Modified {
+                path,
+                old: old.clone(),
+                new: new.clone(),
+            },
+        });
+    });
+    changes
+}
+
+/// Difference reported by `walk`
+pub(crate) enum Event<'a> {
+    Added(&'a Value),
+    Removed(&'a Value),
+    Modified(&'a Value, &'a Value),
+    /// Element of an unordered array paired with the one at this index in the old array
+    Moved(usize),
+}
+
+/// Calls the sink with the segments of every difference between the documents, in document order
+///
+/// Segments lead through the new document, except that the last segment of a removal is its index in the old array.
+/// An element of an unordered array paired with one at another index reports a move before any changes inside it.
+pub(crate) fn walk<'a, F>(old: &'a Value, new: &'a Value, options: &DiffOptions, sink: F)
+where
+    F: FnMut(&[PathSegment<'a>], Event<'a>),
+{
+    Walk { options, sink }.node(old, new, &mut Vec::new());
+}
+
+struct Walk<'o, 'k, F> {
+    options: &'o DiffOptions<'k>,
+    sink: F,
+}
+
+impl<'a, 'o, 'k, F: FnMut(&[PathSegment<'a>], Event<'a>)> Walk<'o, 'k, F> {
+    fn node(&mut self, old: &'a Value, new: &'a Value, segments: &mut Vec<PathSegment<'a>>) {
+        match (old, new) {
+            (Value::Object(old_obj), Value::Object(new_obj)) => {
+                for (key, old_val) in old_obj {
+                    segments.push(PathSegment::Key(key));
+                    match new_obj.get(key) {
+                        Some(new_val) => self.node(old_val, new_val, segments),
+                        None => (self.sink)(segments, Event::Removed(old_val)),
+                    }
+                    segments.pop();
+                }
+                for (key, new_val) in new_obj {
+                    if !old_obj.contains_key(key) {
+                        segments.push(PathSegment::Key(key));
+                        (self.sink)(segments, Event::Added(new_val));
+                        segments.pop();
+                    }
+                }
+            }
+            (Value::Array(old_arr), Value::Array(new_arr)) => {
+                let unordered = self
+                    .options
+                    .unordered
+                    .iter()
+                    .find(|(glob, _)| glob.matches(segments));
+                match unordered {
+                    Some((_, key)) => self.unordered(old_arr, new_arr, *key, segments),
+                    None => self.ordered(old_arr, new_arr, segments),
+                }
+            }
+            _ if old != new => (self.sink)(segments, Event::Modified(old, new)),
+            _ => {}
+        }
+    }
+
+    fn ordered(&mut self, old: &'a [Value], new: &'a [Value], segments: &mut Vec<PathSegment<'a>>) {
+        let (mut old_start, mut new_start) = (0, 0);
+        for (old_end, new_end) in common_subsequence(old, new) {
+            self.gap(old, new, old_start..old_end, new_start..new_end, segments);
+            (old_start, new_start) = (old_end + 1, new_end + 1);
+        }
+        self.gap(
+            old,
+            new,
+            old_start..old.len(),
+            new_start..new.len(),
+            segments,
+        );
+    }
+
+    /// Reports the elements between two aligned ones, pairing them up by position as far as both sides reach
+    fn gap(
+        &mut self,
+        old: &'a [Value],
+        new: &'a [Value],
+        old_range: Range<usize>,
+        new_range: Range<usize>,
+        segments: &mut Vec<PathSegment<'a>>,
+    ) {
+        let paired = old_range.len().min(new_range.len());
+        for (old_index, new_index) in old_range.clone().zip(new_range.clone()) {
+            segments.push(PathSegment::Index(new_index));
+            self.node(&old[old_index], &new[new_index], segments);
+            segments.pop();
+        }
+        for index in old_range.skip(paired) {
+            segments.push(PathSegment::Index(index));
+            (self.sink)(segments, Event::Removed(&old[index]));
+            segments.pop();
+        }
+        for index in new_range.skip(paired) {
+            segments.push(PathSegment::Index(index));
+            (self.sink)(segments, Event::Added(&new[index]));
+            segments.pop();
+        }
+    }
+
+    fn unordered(
+        &mut self,
+        old: &'a [Value],
+        new: &'a [Value],
+        key: Option<&[PathSegment]>,
+        segments: &mut Vec<PathSegment<'a>>,
+    ) {
+        let mut paired = vec![false; old.len()];
+        for (index, new_val) in new.iter().enumerate() {
+            let new_key = key.and_then(|key| get(new_val, key));
+            let found = (0..old.len()).find(|&i| {
+                !paired[i]
+                    && match (key, new_key) {
+                        (Some(key), Some(new_key)) => get(&old[i], key) == Some(new_key),
+                        _ => old[i] == *new_val,
+                    }
+            });
+
+            segments.push(PathSegment::Index(index));
+            match found {
+                Some(i) => {
+                    paired[i] = true;
+                    if i != index {
+                        (self.sink)(segments, Event::Moved(i));
+                    }
+                    self.node(&old[i], new_val, segments);
+                }
+                None => (self.sink)(segments, Event::Added(new_val)),
+            }
+            segments.pop();
+        }
+        for (index, old_val) in old.iter().enumerate() {
+            if !paired[index] {
+                segments.push(PathSegment::Index(index));
+                (self.sink)(segments, Event::Removed(old_val));
+                segments.pop();
+            }
+        }
+    }
+}
+
+/// Largest product of the lengths of two arrays' differing middles that `common_subsequence` aligns,
+/// beyond which only their common prefix and suffix are aligned
+const MAX_ALIGNED_CELLS: usize = 1 << 22;
+
+/// Index pairs of equal elements forming a longest common subsequence of the arrays, in order
+fn common_subsequence(old: &[Value], new: &[Value]) -> Vec<(usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut aligned: Vec<_> = (0..prefix).map(|index| (index, index)).collect();
+    if old_mid.len().saturating_mul(new_mid.len()) <= MAX_ALIGNED_CELLS {
+        // lengths[i][j] is the length of the longest common subsequence of old_mid[i..] and new_mid[j..]
+        let width = new_mid.len() + 1;
+        let mut lengths = vec![0usize; (old_mid.len() + 1) * width];
+        for i in (0..old_mid.len()).rev() {
+            for j in (0..new_mid.len()).rev() {
+                lengths[i * width + j] = match old_mid[i] == new_mid[j] {
+                    true => lengths[(i + 1) * width + j + 1] + 1,
+                    false => lengths[(i + 1) * width + j].max(lengths[i * width + j + 1]),
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old_mid.len() && j < new_mid.len() {
+            if old_mid[i] == new_mid[j] {
+                aligned.push((prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+    }
+    aligned.extend(
+        (0..suffix).map(|offset| (old.len() - suffix + offset, new.len() - suffix + offset)),
+    );
+    aligned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn inserting_near_the_front_is_a_single_addition() {
+        let old = json!([1, {"a": 2}, 3, 4, 5]);
+        let new = json!([0, 1, {"a": 9}, 3, 4, 6, 7]);
+
+        assert_eq!(
+            diff_with(
+                &old,
+                &new,
+                &DiffOptions::default(),
+                &PresetStyle::CommonJs.into()
+            ),
+            vec![
+                Change::Added {
+                    path: "[0]".into(),
+                    value: json!(0)
+                },
+                Change::Modified {
+                    path: "[2].a".into(),
+                    old: json!(2),
+                    new: json!(9)
+                },
+                Change::Modified {
+                    path: "[5]".into(),
+                    old: json!(5),
+                    new: json!(6)
+                },
+                Change::Added {
+                    path: "[6]".into(),
+                    value: json!(7)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unordered_arrays_pair_duplicates_once() {
+        let options = DiffOptions::new().unordered_arrays("**".parse().unwrap(), None);
+        let changes = diff_with(
+            &json!([1, 1, 2]),
+            &json!([2, 1, 3]),
+            &options,
+            &PresetStyle::CommonJs.into(),
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                Change::Added {
+                    path: "[2]".into(),
+                    value: json!(3)
+                },
+                Change::Removed {
+                    path: "[1]".into(),
+                    value: json!(1)
+                },
+            ]
+        );
+    }
+}
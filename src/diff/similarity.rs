@@ -0,0 +1,71 @@
+use crate::iter::Iter;
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Scores how alike two documents are from `0.0` to `1.0`, comparing their `(path, value)` pairs in the default style
+///
+/// The score is the number of pairs both documents share divided by the number of distinct pairs in either,
+/// counting repeated pairs as often as they appear. Two documents without any pairs score `1.0`.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::similarity;
+///
+/// let a = json!({"name": "ada", "langs": ["en", "fr"], "age": 36});
+/// let b = json!({"name": "ada", "langs": ["en", "fr"], "age": 37});
+///
+/// assert_eq!(similarity(&a, &b), 3.0 / 5.0);
+/// assert_eq!(similarity(&a, &a), 1.0);
+/// assert_eq!(similarity(&a, &json!(null)), 0.0);
+/// ```
+pub fn similarity(a: &Value, b: &Value) -> f64 {
+    similarity_with(a, b, &PresetStyle::SquareBrackets.into())
+}
+
+/// Scores how alike two documents are, comparing `(path, value)` pairs formatted in the given style, see `similarity`
+///
+/// A style with wildcard array indices compares arrays regardless of the order of their elements.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{similarity_with, StyleBuilder};
+///
+/// let style = StyleBuilder::new().wildcard_array_keys_in_path().build();
+///
+/// assert_eq!(similarity_with(&json!(["a", "b"]), &json!(["b", "a"]), &style), 1.0);
+/// assert_eq!(similarity_with(&json!(["a", "a"]), &json!(["a"]), &style), 0.5);
+/// ```
+pub fn similarity_with(a: &Value, b: &Value, style: &Style) -> f64 {
+    let a = pair_counts(a, style);
+    let b = pair_counts(b, style);
+
+    let mut shared = 0;
+    let mut total = 0;
+    for (pair, &count) in &a {
+        let other = b.get(pair).copied().unwrap_or(0);
+        shared += count.min(other);
+        total += count.max(other);
+    }
+    total += b
+        .iter()
+        .filter(|(pair, _)| !a.contains_key(*pair))
+        .map(|(_, count)| count)
+        .sum::<usize>();
+
+    match total {
+        0 => 1.0,
+        total => shared as f64 / total as f64,
+    }
+}
+
+/// Number of times each `(path, value)` pair appears, with values written as json text
+fn pair_counts(json: &Value, style: &Style) -> HashMap<(String, String), usize> {
+    let mut counts = HashMap::new();
+    for (path, value) in Iter::new(json).use_style(style.clone()).pairs() {
+        *counts.entry((path, value.to_string())).or_insert(0) += 1;
+    }
+    counts
+}
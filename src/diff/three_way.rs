@@ -0,0 +1,220 @@
+use crate::path::PathSegment;
+use crate::style::{PresetStyle, Style};
+use serde_json::{Map, Value};
+
+/// Which side of a three-way merge a change was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// Only our document changed the value
+    Ours,
+    /// Only their document changed the value
+    Theirs,
+    /// Both documents made the same change
+    Both,
+}
+
+/// Change from the base document taken into the merged document
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeDecision {
+    /// Path of the change
+    pub path: String,
+    /// Document the change came from
+    pub side: Side,
+    /// The merged value, or `None` where the value was removed
+    pub value: Option<Value>,
+}
+
+/// Path both documents changed from the base in different ways, with each side's value or `None` where it is missing
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Path of the conflict
+    pub path: String,
+    /// The value in the base document
+    pub base: Option<Value>,
+    /// The value in our document, which the merged document keeps
+    pub ours: Option<Value>,
+    /// The value in their document
+    pub theirs: Option<Value>,
+}
+
+/// Result of a three-way merge, see `diff3`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff3 {
+    /// Both sides' changes applied to the base, keeping our value at every conflict
+    pub merged: Value,
+    /// Every change taken from either side, in document order
+    pub decisions: Vec<MergeDecision>,
+    /// Every path where the sides disagree, in document order
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl Diff3 {
+    /// Whether the documents merged without conflicts
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merges the changes two documents made to a common base, formatting paths with the default style
+///
+/// A value changed on one side only is taken from that side, and a value changed the same way on both sides is
+/// taken once. Where both sides changed a value differently, objects are merged key by key, arrays of the same
+/// length as in the base are merged index by index, and anything else is a conflict.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{diff3, MergeConflict, Side};
+///
+/// let base = json!({"hosts": ["a", "b"], "log": "info", "port": 80});
+/// let ours = json!({"hosts": ["a", "c"], "log": "debug", "port": 8080});
+/// let theirs = json!({"hosts": ["z", "b"], "log": "warn", "port": 80, "tls": true});
+/// let result = diff3(&base, &ours, &theirs);
+///
+/// assert_eq!(result.merged, json!({"hosts": ["z", "c"], "log": "debug", "port": 8080, "tls": true}));
+/// assert_eq!(result.decisions.iter().map(|d| (d.path.as_str(), d.side)).collect::<Vec<_>>(), vec![
+///     ("[\"hosts\"][0]", Side::Theirs),
+///     ("[\"hosts\"][1]", Side::Ours),
+///     ("[\"port\"]", Side::Ours),
+///     ("[\"tls\"]", Side::Theirs),
+/// ]);
+/// assert_eq!(result.conflicts, vec![MergeConflict {
+///     path: "[\"log\"]".into(),
+///     base: Some(json!("info")),
+///     ours: Some(json!("debug")),
+///     theirs: Some(json!("warn")),
+/// }]);
+/// ```
+pub fn diff3(base: &Value, ours: &Value, theirs: &Value) -> Diff3 {
+    diff3_with(base, ours, theirs, &PresetStyle::SquareBrackets.into())
+}
+
+/// Merges the changes two documents made to a common base, formatting paths in the given style, see `diff3`
+pub fn diff3_with(base: &Value, ours: &Value, theirs: &Value, style: &Style) -> Diff3 {
+    let mut merge = ThreeWay {
+        style,
+        decisions: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    let merged = merge.node(Some(base), Some(ours), Some(theirs), &mut Vec::new());
+
+    Diff3 {
+        merged: merged.unwrap_or(Value::Null),
+        decisions: merge.decisions,
+        conflicts: merge.conflicts,
+    }
+}
+
+struct ThreeWay<'s> {
+    style: &'s Style<'s>,
+    decisions: Vec<MergeDecision>,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl<'s> ThreeWay<'s> {
+    /// Merged value at the path, or `None` where it was removed
+    fn node<'a>(
+        &mut self,
+        base: Option<&'a Value>,
+        ours: Option<&'a Value>,
+        theirs: Option<&'a Value>,
+        segments: &mut Vec<PathSegment<'a>>,
+    ) -> Option<Value> {
+        if ours == theirs {
+            if ours != base {
+                self.decide(segments, Side::Both, ours);
+            }
+            return ours.cloned();
+        }
+        if ours == base {
+            self.decide(segments, Side::Theirs, theirs);
+            return theirs.cloned();
+        }
+        if theirs == base {
+            self.decide(segments, Side::Ours, ours);
+            return ours.cloned();
+        }
+
+        match (base, ours, theirs) {
+            (None, Some(Value::Object(o)), Some(Value::Object(t)))
+            | (Some(Value::Object(_)), Some(Value::Object(o)), Some(Value::Object(t))) => {
+                let b = base.and_then(Value::as_object);
+                let keys = o
+                    .keys()
+                    .chain(t.keys().filter(|key| !o.contains_key(*key)))
+                    .chain(
+                        b.into_iter()
+                            .flat_map(Map::keys)
+                            .filter(|key| !o.contains_key(*key) && !t.contains_key(*key)),
+                    );
+
+                let mut merged = Map::new();
+                for key in keys {
+                    segments.push(PathSegment::Key(key));
+                    let val =
+                        self.node(b.and_then(|b| b.get(key)), o.get(key), t.get(key), segments);
+                    segments.pop();
+                    if let Some(val) = val {
+                        merged.insert(key.clone(), val);
+                    }
+                }
+                Some(Value::Object(merged))
+            }
+            (Some(Value::Array(b)), Some(Value::Array(o)), Some(Value::Array(t)))
+                if b.len() == o.len() && b.len() == t.len() =>
+            {
+                let mut merged = Vec::with_capacity(b.len());
+                for index in 0..b.len() {
+                    segments.push(PathSegment::Index(index));
+                    let val = self.node(b.get(index), o.get(index), t.get(index), segments);
+                    segments.pop();
+                    merged.push(val.unwrap_or(Value::Null));
+                }
+                Some(Value::Array(merged))
+            }
+            _ => {
+                self.conflicts.push(MergeConflict {
+                    path: self.style.format_segments(segments),
+                    base: base.cloned(),
+                    ours: ours.cloned(),
+                    theirs: theirs.cloned(),
+                });
+                ours.cloned()
+            }
+        }
+    }
+
+    fn decide(&mut self, segments: &[PathSegment], side: Side, value: Option<&Value>) {
+        self.decisions.push(MergeDecision {
+            path: self.style.format_segments(segments),
+            side,
+            value: value.cloned(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn removals_merge_and_conflict_with_edits() {
+        let base = json!({"a": 1, "b": 2, "c": 3});
+        let ours = json!({"b": 2, "c": 4});
+        let theirs = json!({"b": 2});
+        let result = diff3(&base, &ours, &theirs);
+
+        assert_eq!(result.merged, json!({"b": 2, "c": 4}));
+        assert_eq!(
+            result.decisions,
+            vec![MergeDecision {
+                path: "[\"a\"]".into(),
+                side: Side::Both,
+                value: None,
+            }]
+        );
+        assert_eq!(result.conflicts[0].path, "[\"c\"]");
+        assert_eq!(result.conflicts[0].theirs, None);
+    }
+}
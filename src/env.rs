@@ -0,0 +1,160 @@
+use crate::iter::Iter;
+use crate::style::styles;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Error produced when a leaf has no usable environment variable name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvError {
+    /// The leaf is the root value or sits under an empty key, so its name would be empty
+    EmptyName,
+    /// The name starts with a digit, as for a root array or a key such as `1st`, which shells reject
+    LeadingDigit(String),
+    /// Two leaves map to the same name, as `a-b` and `a_b` both do to `A_B`
+    Collision(String),
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::EmptyName => write!(f, "a leaf has an empty variable name"),
+            EnvError::LeadingDigit(name) => {
+                write!(f, "variable name {} starts with a digit", name)
+            }
+            EnvError::Collision(name) => {
+                write!(f, "several leaves map to the variable name {}", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+/// Formats every leaf of a config document as a `KEY=value` line, ready to be sourced as a `.env` file
+///
+/// Names come from `PresetStyle::EnvVar` with the leading `_` dropped, so `{"db": {"port": 1}}` becomes `DB_PORT=1`.
+/// Strings are single quoted, so the shell reads every character literally, with each embedded `'` written as `'\''`.
+/// `null` is written as an empty value and other leaves as json. Empty objects and arrays produce no line.
+///
+/// Fails when a name would be empty or start with a digit, or when two leaves share a name.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::to_env_lines;
+///
+/// let config = json!({
+///     "db": {"host": "localhost", "password": "p@ss word", "port": 5432},
+///     "feature-flags": [true, null],
+///     "motd": "line 1\nline 2",
+/// });
+///
+/// assert_eq!(to_env_lines(&config).unwrap(), "\
+/// DB_HOST='localhost'
+/// DB_PASSWORD='p@ss word'
+/// DB_PORT=5432
+/// FEATURE_FLAGS_0=true
+/// FEATURE_FLAGS_1=
+/// MOTD='line 1
+/// line 2'
+/// ");
+/// assert!(to_env_lines(&json!({"a-b": 1, "a_b": 2})).is_err());
+/// ```
+pub fn to_env_lines(json: &Value) -> Result<String, EnvError> {
+    let mut out = String::new();
+    let mut names = HashSet::new();
+    for el in Iter::new(json).use_style(styles::ENV_VAR) {
+        let name = el.path.strip_prefix('_').unwrap_or(&el.path);
+        match name.bytes().next() {
+            None => return Err(EnvError::EmptyName),
+            Some(b) if b.is_ascii_digit() => return Err(EnvError::LeadingDigit(name.to_owned())),
+            Some(_) => {}
+        }
+        if !names.insert(name.to_owned()) {
+            return Err(EnvError::Collision(name.to_owned()));
+        }
+        out.push_str(name);
+        out.push('=');
+        match el.value {
+            Value::Null => {}
+            Value::String(text) => push_env_string(&mut out, text),
+            leaf => out.push_str(&leaf.to_string()),
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn push_env_string(out: &mut String, text: &str) {
+    if text.is_empty() {
+        out.push_str("''");
+        return;
+    }
+
+    out.push('\'');
+    for c in text.chars() {
+        match c {
+            '\'' => out.push_str("'\\''"),
+            _ => out.push(c),
+        }
+    }
+    out.push('\'');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn quotes_and_escapes_strings() {
+        let value = json!({"a": "", "b": "it's $HOME", "c": "C:\\dir"});
+
+        assert_eq!(
+            to_env_lines(&value).unwrap(),
+            "A=''\nB='it'\\''s $HOME'\nC='C:\\dir'\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shells_read_strings_back_literally() {
+        let texts = [
+            "a|b&c;d",
+            "<in >out",
+            "(sub) $(cmd) `cmd`",
+            "~/p *.rs ?x !1",
+            "it's \"quoted\" \\n",
+            "tab\tline\nbell\u{7}esc\u{1b}",
+            "#comment",
+        ];
+        for text in texts.iter() {
+            let lines = to_env_lines(&json!({ "v": text })).unwrap();
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{}printf %s \"$V\"", lines))
+                .output()
+                .unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout), *text);
+        }
+    }
+
+    #[test]
+    fn rejects_names_shells_cannot_use() {
+        assert_eq!(to_env_lines(&json!(1)), Err(EnvError::EmptyName));
+        assert_eq!(to_env_lines(&json!({"": 1})), Err(EnvError::EmptyName));
+        assert_eq!(
+            to_env_lines(&json!([1])),
+            Err(EnvError::LeadingDigit("0".to_owned()))
+        );
+        assert_eq!(
+            to_env_lines(&json!({"1st": 1})),
+            Err(EnvError::LeadingDigit("1ST".to_owned()))
+        );
+        assert_eq!(
+            to_env_lines(&json!({"a-b": 1, "a_b": 2})),
+            Err(EnvError::Collision("A_B".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::iter::Iter;
+use crate::node::JsonNode;
+use crate::style::Style;
+
+/// Extension trait adding keypath iteration methods directly onto json values
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{KeypathExt, PresetStyle};
+///
+/// let value = json!({"a": [1, {"b": 2}]});
+///
+/// let paths: Vec<_> = value.keypaths().map(|el| el.path).collect();
+/// assert_eq!(paths, vec!["[\"a\"][0]", "[\"a\"][1][\"b\"]"]);
+///
+/// let paths: Vec<_> = value.keypaths_with(PresetStyle::CommonJs.into()).map(|el| el.path).collect();
+/// assert_eq!(paths, vec![".a[0]", ".a[1].b"]);
+///
+/// let leaves: Vec<_> = value.leaves().collect();
+/// assert_eq!(leaves, vec![&json!(1), &json!(2)]);
+/// ```
+pub trait KeypathExt: JsonNode + Sized {
+    /// Iterates this value with the default style, see `Iterator::new`
+    fn keypaths(&self) -> Iter<'_, Self> {
+        Iter::new(self)
+    }
+
+    /// Iterates this value with a custom style, see `Iterator::use_style`
+    fn keypaths_with<'a>(&'a self, style: Style<'a>) -> Iter<'a, Self> {
+        Iter::new(self).use_style(style)
+    }
+
+    /// Iterates every leaf value, meaning anything that is neither an object nor an array, in document order
+    fn leaves(&self) -> Leaves<'_, Self> {
+        Leaves { stack: vec![self] }
+    }
+}
+
+impl<V: JsonNode> KeypathExt for V {}
+
+/// Iterator over the leaf values of a json structure, created by `KeypathExt::leaves`
+///
+/// No paths are formatted, so this is cheaper than filtering the values out of `Element`s.
+#[derive(Debug)]
+pub struct Leaves<'a, V> {
+    stack: Vec<&'a V>,
+}
+
+impl<'a, V: JsonNode> Iterator for Leaves<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(value) = self.stack.pop() {
+            if let Some(entries) = value.entries() {
+                self.stack.extend(entries.rev().map(|(_, val)| val));
+            } else if let Some(items) = value.items() {
+                self.stack.extend(items.rev());
+            } else {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn leaves_skip_empty_containers() {
+        let value = json!({"a": [], "b": {}, "c": [null, [true]]});
+        let leaves: Vec<_> = value.leaves().collect();
+
+        assert_eq!(leaves, vec![&json!(null), &json!(true)]);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::iter::Iter;
+use crate::owned::IntoIter;
+use memmap2::Mmap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+/// Files at least this large are memory-mapped rather than read into a buffer
+pub const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// Error produced while loading a json file for iteration
+#[derive(Debug)]
+pub enum FileError {
+    /// The file could not be opened, read, or mapped
+    Io(io::Error),
+    /// The file contents were not valid json
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Io(err) => write!(f, "failed to read json file: {}", err),
+            FileError::Json(err) => write!(f, "failed to parse json file: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileError::Io(err) => Some(err),
+            FileError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for FileError {
+    fn from(err: io::Error) -> Self {
+        FileError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for FileError {
+    fn from(err: serde_json::Error) -> Self {
+        FileError::Json(err)
+    }
+}
+
+impl<'a> Iter<'a> {
+    /// Parses a json file and creates an iterator that owns the result
+    ///
+    /// Files smaller than `MMAP_THRESHOLD` are read into memory in one go, while larger files are
+    /// memory-mapped and parsed straight from the mapping to avoid holding a second copy of the text.
+    /// As with any memory-mapped file, the file must not be modified while it is being parsed.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::Iterator;
+    ///
+    /// let path = std::env::temp_dir().join("json-keypath-iter-from-path.json");
+    /// std::fs::write(&path, r#"{"a": [true]}"#).unwrap();
+    ///
+    /// let items: Vec<_> = Iterator::from_path(&path).unwrap().collect();
+    /// assert_eq!(items[0].path, "[\"a\"][0]");
+    /// assert_eq!(items[0].value, json!(true));
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<IntoIter<'static>, FileError> {
+        let file = File::open(path.as_ref())?;
+        let value = if file.metadata()?.len() < MMAP_THRESHOLD {
+            serde_json::from_slice(&fs::read(path.as_ref())?)?
+        } else {
+            // safety: the mapping is only read while parsing, and callers are warned not to modify the file meanwhile
+            let mapping = unsafe { Mmap::map(&file)? };
+            serde_json::from_slice(&mapping)?
+        };
+
+        Ok(IntoIter::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_large_files() {
+        let path = std::env::temp_dir().join("json-keypath-iter-maps-large-files.json");
+        let padding = " ".repeat(MMAP_THRESHOLD as usize);
+        fs::write(&path, format!("{}[1, 2]", padding)).unwrap();
+
+        let values: Vec<_> = Iter::from_path(&path).unwrap().map(|el| el.value).collect();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(values, vec![json!(1), json!(2)]);
+    }
+
+    #[test]
+    fn reports_missing_files_and_bad_json() {
+        let path = std::env::temp_dir().join("json-keypath-iter-bad-json.json");
+        fs::write(&path, "{").unwrap();
+
+        assert!(matches!(
+            Iter::from_path(&path).unwrap_err(),
+            FileError::Json(_)
+        ));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            Iter::from_path(&path).unwrap_err(),
+            FileError::Io(_)
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::iter::Iter;
+use crate::style::{Style, StyleBuilder};
+use serde_json::{Map, Value};
+
+/// Flattens a document into a single-level json object, keyed by the styled path of each leaf
+///
+/// Only leaves are kept, whatever the style says about parents, so empty objects and arrays produce nothing.
+/// When the style formats several leaves with the same path, the one latest in document order is kept.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{to_flat_json, PresetStyle};
+///
+/// let value = json!({"host": {"name": "web-1", "tags": ["a", "b"]}, "up": true});
+/// let flat = to_flat_json(&value, &PresetStyle::CommonJs.into());
+///
+/// assert_eq!(flat, json!({
+///     ".host.name": "web-1",
+///     ".host.tags[0]": "a",
+///     ".host.tags[1]": "b",
+///     ".up": true,
+/// }));
+/// ```
+pub fn to_flat_json(json: &Value, style: &Style) -> Value {
+    let style = StyleBuilder::from(style).skip_parents().build();
+    let mut map = Map::new();
+    for el in Iter::new(json).use_style(style) {
+        map.insert(el.path, el.value.clone());
+    }
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::PresetStyle;
+    use serde_json::json;
+
+    #[test]
+    fn ignores_parents_in_style() {
+        let value = json!({"a": {"b": []}, "c": [null]});
+        let style = PresetStyle::CommonJs.builder().include_parents().build();
+
+        assert_eq!(to_flat_json(&value, &style), json!({".c[0]": null}));
+    }
+
+    #[test]
+    fn root_leaf_has_empty_path() {
+        assert_eq!(
+            to_flat_json(&json!(7), &PresetStyle::SquareBrackets.into()),
+            json!({"": 7})
+        );
+    }
+}
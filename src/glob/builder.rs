@@ -0,0 +1,201 @@
+use super::{GlobError, GlobSegment, PathGlob, Slice};
+use crate::path::PathSegment;
+use crate::style::Style;
+use std::ops::{Bound, RangeBounds};
+
+/// Typed builder for a `PathGlob`, for building selectors in code without writing pattern strings
+///
+/// Keys are stored as given, so they never need quoting, and the same path can be rendered in any `Style`.
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::{Path, PathGlob, PresetStyle};
+///
+/// let path = Path::root().key("users").any_index().key("email");
+///
+/// assert_eq!(PathGlob::from(path.clone()), "users[*].email".parse().unwrap());
+/// assert_eq!(path.render(&PresetStyle::CommonJs.into()), ".users[*].email");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<GlobSegment>,
+}
+
+impl Path {
+    /// Path to the base of the json structure, with no segments
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Adds a segment matching one object key exactly
+    pub fn key(self, key: impl Into<String>) -> Self {
+        self.push(GlobSegment::Key(key.into()))
+    }
+
+    /// Adds a segment matching one array index exactly
+    pub fn index(self, index: usize) -> Self {
+        self.push(GlobSegment::Index(index))
+    }
+
+    /// Adds a segment matching any single object key, like `*` in a pattern
+    pub fn any_key(self) -> Self {
+        self.push(GlobSegment::AnyKey(None))
+    }
+
+    /// Adds a segment matching any single object key, named for `PathGlob::captures` like `*name` in a pattern
+    pub fn any_key_as(self, name: &str) -> Self {
+        self.push(GlobSegment::AnyKey(Some(name.into())))
+    }
+
+    /// Adds a segment matching every object key the regex finds a match in, like `/regex/` in a pattern
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{Path, PathGlob, PathSegment};
+    /// use regex::Regex;
+    ///
+    /// let glob = PathGlob::from(Path::root().key_matching(Regex::new(r"^metric_\d+$").unwrap()));
+    ///
+    /// assert!(glob.matches(&[PathSegment::Key("metric_12")]));
+    /// assert!(!glob.matches(&[PathSegment::Key("metric_total")]));
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn key_matching(self, regex: regex::Regex) -> Self {
+        self.push(GlobSegment::KeyRegex(super::KeyRegex(regex)))
+    }
+
+    /// Adds a segment matching any single array index, like `[*]` in a pattern
+    pub fn any_index(self) -> Self {
+        self.push(GlobSegment::AnyIndex(None))
+    }
+
+    /// Adds a segment matching any single array index, named for `PathGlob::captures` like `[*name]` in a pattern
+    pub fn any_index_as(self, name: &str) -> Self {
+        self.push(GlobSegment::AnyIndex(Some(name.into())))
+    }
+
+    /// Adds a segment matching the array indices within the range, taking every `step`th one, like `[start:end:step]`
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{Path, PathGlob, PresetStyle};
+    ///
+    /// let path = Path::root().key("rows").slice(0..10, 2).unwrap();
+    ///
+    /// assert_eq!(PathGlob::from(path.clone()), "rows[0:10:2]".parse().unwrap());
+    /// assert_eq!(path.render(&PresetStyle::CommonJs.into()), ".rows[0:10:2]");
+    /// assert!(Path::root().slice(.., 0).is_err());
+    /// ```
+    ///
+    /// Fails if `step` is `0`, just as parsing `[::0]` does.
+    pub fn slice(self, range: impl RangeBounds<usize>, step: usize) -> Result<Self, GlobError> {
+        // bounds past `usize::MAX` are kept exact: no index follows it, and every index is at most it
+        let (start, end) = match range.start_bound() {
+            Bound::Included(&start) => (start, None),
+            Bound::Excluded(&start) => match start.checked_add(1) {
+                Some(start) => (start, None),
+                None => (start, Some(start)),
+            },
+            Bound::Unbounded => (0, None),
+        };
+        let end = end.or(match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1),
+            Bound::Excluded(&end) => Some(end),
+            Bound::Unbounded => None,
+        });
+        let slice = Slice { start, end, step };
+        if step == 0 {
+            let pattern = format!("[{}]", slice);
+            return Err(GlobError {
+                position: pattern.len() - 2,
+                pattern,
+                message: "slice step cannot be zero",
+            });
+        }
+        Ok(self.push(GlobSegment::Slice(slice)))
+    }
+
+    /// Adds a segment matching any number of keys and indices, like `**` in a pattern
+    pub fn any_depth(self) -> Self {
+        self.push(GlobSegment::AnyDepth)
+    }
+
+    /// Whether the full set of path segments is matched, see `PathGlob::matches`
+    pub fn matches(&self, path: &[PathSegment]) -> bool {
+        super::matches_from(&self.segments, path, Default::default(), None)
+    }
+
+    /// Formats the path in the style, as it would appear in an `Element`
+    ///
+    /// Wildcards are written as `*` in place of the key or index, and `any_depth` as a `**` key.
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{Path, PresetStyle};
+    ///
+    /// let path = Path::root().key("a").index(0).any_depth().key("b");
+    ///
+    /// assert_eq!(path.render(&PresetStyle::SquareBrackets.into()), "[\"a\"][0][\"**\"][\"b\"]");
+    /// assert_eq!(path.render(&PresetStyle::Slashes.into()), "/a/0/**/b");
+    /// ```
+    pub fn render(&self, style: &Style) -> String {
+        let mut path = String::new();
+        for segment in &self.segments {
+            match segment {
+                GlobSegment::Key(key) => style.push_object_segment(&mut path, key),
+                GlobSegment::Index(index) => style.push_array_segment(&mut path, *index),
+                GlobSegment::AnyKey(_) => style.push_escaped_object_segment(&mut path, "*"),
+                GlobSegment::AnyIndex(_) => style.push_array_placeholder(&mut path, "*"),
+                GlobSegment::Slice(slice) => {
+                    style.push_array_placeholder(&mut path, &slice.to_string())
+                }
+                #[cfg(feature = "regex")]
+                GlobSegment::KeyRegex(regex) => {
+                    style.push_escaped_object_segment(&mut path, &format!("/{}/", regex.0))
+                }
+                GlobSegment::AnyDepth => style.push_escaped_object_segment(&mut path, "**"),
+            }
+        }
+        style.finish_path(path)
+    }
+
+    fn push(mut self, segment: GlobSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+}
+
+impl From<Path> for PathGlob {
+    fn from(path: Path) -> Self {
+        Self::from_segments(path.segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_need_no_quoting() {
+        let glob = PathGlob::from(Path::root().key("a.b").key("[c]"));
+
+        assert_eq!(glob, PathGlob::new("[\"a.b\"][\"[c]\"]").unwrap());
+        assert!(glob.matches(&[PathSegment::Key("a.b"), PathSegment::Key("[c]")]));
+    }
+
+    #[test]
+    fn slice_bounds_at_the_edge_do_not_overflow() {
+        let max = usize::MAX;
+        let open = PathGlob::from(Path::root().slice(max - 1..=max, 1).unwrap());
+        let empty = PathGlob::from(
+            Path::root()
+                .slice((Bound::Excluded(max), Bound::Unbounded), 1)
+                .unwrap(),
+        );
+
+        assert!(open.matches(&[PathSegment::Index(max)]));
+        assert!(!open.matches(&[PathSegment::Index(max - 2)]));
+        assert!(!empty.matches(&[PathSegment::Index(max)]));
+        assert_eq!(Path::root().slice(1..3, 0).unwrap_err().position, 5);
+    }
+}
@@ -0,0 +1,97 @@
+use super::{matches_from, Captured, PathGlob};
+use crate::path::PathSegment;
+
+impl PathGlob {
+    /// Matches the path like `matches`, returning the segment bound to each wildcard, slice, and regex in the glob
+    ///
+    /// Segments matched by `**` are not captured, since it can cover any number of them.
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{PathGlob, PathSegment};
+    ///
+    /// let glob: PathGlob = "users[*user].roles[*role]".parse().unwrap();
+    /// let path = [PathSegment::Key("users"), PathSegment::Index(2), PathSegment::Key("roles"), PathSegment::Index(0)];
+    /// let captures = glob.captures(&path).unwrap();
+    ///
+    /// assert_eq!(captures.name("user"), Some(PathSegment::Index(2)));
+    /// assert_eq!(captures.get(1), Some(PathSegment::Index(0)));
+    /// assert!(glob.captures(&path[..2]).is_none());
+    /// ```
+    pub fn captures<'p>(&self, path: &[PathSegment<'p>]) -> Option<Captures<'p>> {
+        let mut captured = Vec::new();
+        let matched = self.within_depth(path)
+            && matches_from(
+                &self.segments,
+                path,
+                self.options(false),
+                Some(&mut captured),
+            );
+        match matched {
+            true => Some(Captures { captured }),
+            false => None,
+        }
+    }
+}
+
+/// Path segments bound to each wildcard, slice, and regex of a `PathGlob`, in the order they appear in the glob
+#[derive(Debug, Clone, PartialEq)]
+pub struct Captures<'p> {
+    captured: Captured<'p>,
+}
+
+impl<'p> Captures<'p> {
+    /// Segment bound to the wildcard or slice at the position, counting from `0`
+    pub fn get(&self, position: usize) -> Option<PathSegment<'p>> {
+        self.captured.get(position).map(|(_, segment)| *segment)
+    }
+
+    /// Segment bound to the wildcard with the name
+    pub fn name(&self, name: &str) -> Option<PathSegment<'p>> {
+        self.iter()
+            .find(|(captured, _)| *captured == Some(name))
+            .map(|(_, segment)| segment)
+    }
+
+    /// Number of captured segments
+    pub fn len(&self) -> usize {
+        self.captured.len()
+    }
+
+    /// Whether nothing was captured, because the glob has no wildcards or slices
+    pub fn is_empty(&self) -> bool {
+        self.captured.is_empty()
+    }
+
+    /// Name, if any, and segment of each capture in order
+    pub fn iter(&self) -> impl Iterator<Item = (Option<&str>, PathSegment<'p>)> + '_ {
+        self.captured
+            .iter()
+            .map(|(name, segment)| (name.as_deref(), *segment))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_depth_discards_captures_from_failed_attempts() {
+        let glob = PathGlob::new("**.*key[1:]").unwrap();
+        let path = [
+            PathSegment::Key("a"),
+            PathSegment::Index(0),
+            PathSegment::Key("b"),
+            PathSegment::Index(3),
+        ];
+        let captures = glob.captures(&path).unwrap();
+
+        assert_eq!(
+            captures.iter().collect::<Vec<_>>(),
+            vec![
+                (Some("key"), PathSegment::Key("b")),
+                (None, PathSegment::Index(3))
+            ]
+        );
+    }
+}
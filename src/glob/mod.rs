@@ -0,0 +1,723 @@
+mod builder;
+mod capture;
+mod predicate;
+mod search;
+mod set;
+pub use builder::Path;
+pub use capture::Captures;
+pub use predicate::ValuePredicate;
+pub use search::{count, exists};
+pub use set::PathGlobSet;
+
+use crate::node::JsonNode;
+use crate::path::PathSegment;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Pattern matched against the structure of a path rather than its styled string
+///
+/// Segments are separated by `.` for object keys and wrapped in `[...]` for array indices.
+/// A `*` matches any single object key, `[*]` matches any single array index,
+/// and either can be named for `PathGlob::captures` as `*name` or `[*name]`,
+/// `**` matches any number of keys and indices including none,
+/// `[start:end:step]` matches array indices in a slice where each part is optional, such as `[0:3]` or `[::2]`,
+/// and keys containing special characters can be quoted as `["some.key"]`.
+/// With the `regex` feature, a key written as `/regex/` matches every key the regex finds a match in anywhere,
+/// so anchor it as in `/^metric_\d+$/` to match whole keys, with `\/` for a slash inside the regex.
+/// It ignores `ignore_case`, so use `(?i)` instead. Without the feature such a key is an error,
+/// and a key that starts with a slash can be quoted as `["/key"]`.
+/// An empty pattern matches only the base of the json structure.
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::PathGlob;
+///
+/// let glob: PathGlob = "items[*].price".parse().unwrap();
+/// let quoted: PathGlob = "[\"first.name\"]".parse().unwrap();
+/// let anywhere: PathGlob = "**.email".parse().unwrap();
+///
+/// assert!("items[*".parse::<PathGlob>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathGlob {
+    segments: Vec<GlobSegment>,
+    predicates: Vec<ValuePredicate>,
+    ignore_case: bool,
+    min_depth: usize,
+    max_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum GlobSegment {
+    Key(String),
+    AnyKey(Option<Arc<str>>),
+    Index(usize),
+    AnyIndex(Option<Arc<str>>),
+    Slice(Slice),
+    #[cfg(feature = "regex")]
+    KeyRegex(KeyRegex),
+    AnyDepth,
+}
+
+/// Regex searched for within object keys, compared by its source since `regex::Regex` is not `PartialEq`
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+struct KeyRegex(regex::Regex);
+
+#[cfg(feature = "regex")]
+impl PartialEq for KeyRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// Array indices from `start` up to but not including `end`, taking every `step`th one
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Slice {
+    start: usize,
+    end: Option<usize>,
+    step: usize,
+}
+
+impl Slice {
+    fn contains(&self, index: usize) -> bool {
+        index >= self.start
+            && self.end.map_or(true, |end| index < end)
+            && (index - self.start) % self.step == 0
+    }
+}
+
+impl fmt::Display for Slice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.start)?;
+        if let Some(end) = self.end {
+            write!(f, "{}", end)?;
+        }
+        if self.step != 1 {
+            write!(f, ":{}", self.step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error describing where and why a `PathGlob` pattern could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobError {
+    /// The full pattern that failed to parse
+    pub pattern: String,
+    /// Byte offset into the pattern where parsing failed
+    pub position: usize,
+    /// Short description of the problem
+    pub message: &'static str,
+}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid path glob {:?} at position {}: {}",
+            self.pattern, self.position, self.message,
+        )
+    }
+}
+
+impl std::error::Error for GlobError {}
+
+impl PathGlob {
+    /// Parse a new glob from a pattern string
+    pub fn new(pattern: &str) -> Result<Self, GlobError> {
+        let error = |position: usize, message: &'static str| GlobError {
+            pattern: pattern.into(),
+            position,
+            message,
+        };
+
+        let bytes = pattern.as_bytes();
+        let mut segments = Vec::new();
+        let mut position = 0;
+
+        while position < bytes.len() {
+            match bytes[position] {
+                b'[' => {
+                    let close = match pattern[position..].find(']') {
+                        Some(offset) => position + offset,
+                        None => return Err(error(position, "unclosed bracket")),
+                    };
+                    let inner = &pattern[position + 1..close];
+
+                    if let Some(name) = inner.strip_prefix('*') {
+                        let name = Some(name).filter(|name| !name.is_empty());
+                        segments.push(GlobSegment::AnyIndex(name.map(Arc::from)));
+                        position = close + 1;
+                    } else if inner.starts_with('"') {
+                        let (key, end) = parse_quoted(pattern, position + 1)
+                            .ok_or_else(|| error(position, "unclosed quoted key"))?;
+                        if pattern.as_bytes().get(end) != Some(&b']') {
+                            return Err(error(end, "expected `]` after quoted key"));
+                        }
+                        segments.push(GlobSegment::Key(key));
+                        position = end + 1;
+                    } else if inner.contains(':') {
+                        segments.push(GlobSegment::Slice(parse_slice(
+                            inner,
+                            position + 1,
+                            &error,
+                        )?));
+                        position = close + 1;
+                    } else {
+                        let index = inner
+                            .parse::<usize>()
+                            .map_err(|_| error(position + 1, "expected array index"))?;
+                        segments.push(GlobSegment::Index(index));
+                        position = close + 1;
+                    }
+                }
+                b'.' if position == 0 => return Err(error(position, "unexpected `.`")),
+                b'.' => {
+                    position += 1;
+                    position = parse_key_segment(pattern, position, &mut segments, &error)?;
+                }
+                _ if position == 0 => {
+                    position = parse_key_segment(pattern, position, &mut segments, &error)?;
+                }
+                _ => return Err(error(position, "expected `.` or `[`")),
+            }
+        }
+
+        Ok(Self::from_segments(segments))
+    }
+
+    fn from_segments(segments: Vec<GlobSegment>) -> Self {
+        Self {
+            segments,
+            predicates: Vec::new(),
+            ignore_case: false,
+            min_depth: 0,
+            max_depth: None,
+        }
+    }
+
+    /// Whether the full set of path segments is matched by this glob
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{PathGlob, PathSegment};
+    ///
+    /// let glob: PathGlob = "items[*].price".parse().unwrap();
+    ///
+    /// assert!(glob.matches(&[PathSegment::Key("items"), PathSegment::Index(3), PathSegment::Key("price")]));
+    /// assert!(!glob.matches(&[PathSegment::Key("items"), PathSegment::Index(3)]));
+    /// ```
+    pub fn matches(&self, path: &[PathSegment]) -> bool {
+        self.within_depth(path) && matches_from(&self.segments, path, self.options(false), None)
+    }
+
+    /// Only matches paths whose number of segments falls within the range, where the base of the json structure is `0`
+    ///
+    /// Mostly useful alongside `*` and `**`, which would otherwise match at any depth they can reach.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathGlob};
+    ///
+    /// let value = json!({"id": 1, "x": {"id": 2, "y": {"id": 3, "z": {"id": 4}}}});
+    /// let glob = PathGlob::new("**.id").unwrap().depth(2..=3);
+    /// let ids: Vec<_> = Iterator::new(&value).find_all_paths(glob).map(|el| el.value).collect();
+    ///
+    /// assert_eq!(ids, vec![&json!(2), &json!(3)]);
+    /// ```
+    pub fn depth(mut self, range: impl RangeBounds<usize>) -> Self {
+        let min = match range.start_bound() {
+            Bound::Included(&min) => Some(min),
+            Bound::Excluded(&min) => min.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let max = match range.end_bound() {
+            Bound::Included(&max) => Some(Some(max)),
+            Bound::Excluded(&max) => max.checked_sub(1).map(Some),
+            Bound::Unbounded => Some(None),
+        };
+        // a range no depth falls in, such as `..0`, is kept as one that can never be satisfied
+        let (min, max) = match (min, max) {
+            (Some(min), Some(max)) => (min, max),
+            _ => (1, Some(0)),
+        };
+        self.min_depth = min;
+        self.max_depth = max;
+        self
+    }
+
+    /// Compares object keys case-insensitively, using Unicode lowercasing, so `userId` also matches `UserID` and `userid`
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathGlob};
+    ///
+    /// let value = json!({"Users": [{"EMAIL": "a@x"}], "users": [{"email": "b@x"}]});
+    /// let glob = PathGlob::new("users[*].email").unwrap().ignore_case();
+    /// let emails: Vec<_> = Iterator::new(&value).find_all_paths(glob).map(|el| el.value).collect();
+    ///
+    /// assert_eq!(emails, vec![&json!("a@x"), &json!("b@x")]);
+    /// ```
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+
+    /// Adds a condition the value at a matching path must also satisfy, see `ValuePredicate`
+    ///
+    /// Conditions are checked by `Iterator::find_path` and `Iterator::find_all_paths` as each value is reached,
+    /// so only elements passing every condition are yielded. `matches` and `matches_prefix` only look at paths.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathGlob, ValuePredicate};
+    ///
+    /// let value = json!({"orders": [{"id": 1, "status": "failed"}, {"id": 2, "status": "sent"}]});
+    /// let glob = PathGlob::new("orders[*].status").unwrap().where_value(ValuePredicate::Equals(json!("failed")));
+    /// let paths: Vec<_> = Iterator::new(&value).find_all_paths(glob).map(|el| el.path).collect();
+    ///
+    /// assert_eq!(paths, vec!["[\"orders\"][0][\"status\"]"]);
+    /// ```
+    pub fn where_value(mut self, predicate: ValuePredicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Whether the value satisfies every condition added with `where_value`
+    pub fn matches_value<V: JsonNode>(&self, value: &V) -> bool {
+        self.predicates
+            .iter()
+            .all(|predicate| predicate.matches(value))
+    }
+
+    /// Whether the path segments could be the start of a path matched by this glob,
+    /// meaning nothing nested below a path that fails this check can match
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{PathGlob, PathSegment};
+    ///
+    /// let glob: PathGlob = "items[*].price".parse().unwrap();
+    ///
+    /// assert!(glob.matches_prefix(&[PathSegment::Key("items"), PathSegment::Index(3)]));
+    /// assert!(!glob.matches_prefix(&[PathSegment::Key("users")]));
+    /// ```
+    pub fn matches_prefix(&self, path: &[PathSegment]) -> bool {
+        self.max_depth.map_or(true, |max| path.len() <= max)
+            && matches_from(&self.segments, path, self.options(true), None)
+    }
+
+    /// Segments of the single path this glob matches, or `None` if it has wildcards or other options
+    pub(crate) fn as_path(&self) -> Option<Vec<PathSegment<'_>>> {
+        if !self.predicates.is_empty() || self.min_depth > 0 || self.max_depth.is_some() {
+            return None;
+        }
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                GlobSegment::Key(key) if !self.ignore_case => Some(PathSegment::Key(key)),
+                GlobSegment::Index(index) => Some(PathSegment::Index(*index)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn within_depth(&self, path: &[PathSegment]) -> bool {
+        path.len() >= self.min_depth && self.max_depth.map_or(true, |max| path.len() <= max)
+    }
+
+    fn options(&self, prefix: bool) -> MatchOptions {
+        MatchOptions {
+            prefix,
+            ignore_case: self.ignore_case,
+        }
+    }
+}
+
+/// How `matches_from` compares a glob against a path
+#[derive(Debug, Clone, Copy, Default)]
+struct MatchOptions {
+    /// Running out of path segments counts as a match
+    prefix: bool,
+    /// Object keys in the glob and the path are both lowercased as they are compared
+    ignore_case: bool,
+}
+
+/// Wildcard name and the path segment it matched, see `Captures`
+type Captured<'p> = Vec<(Option<Arc<str>>, PathSegment<'p>)>;
+
+/// Matches glob segments against path segments, where `**` tries every number of levels it could cover,
+/// pushing the segment matched by each wildcard and slice onto `captures` when given
+fn matches_from<'p>(
+    globs: &[GlobSegment],
+    path: &[PathSegment<'p>],
+    options: MatchOptions,
+    captures: Option<&mut Captured<'p>>,
+) -> bool {
+    // only `**` can reach the same glob and path positions twice, so only then is a table of failures needed
+    let mut failed = match globs.contains(&GlobSegment::AnyDepth) {
+        true => vec![false; (globs.len() + 1) * (path.len() + 1)],
+        false => Vec::new(),
+    };
+    let mut state = MatchState {
+        options,
+        width: path.len() + 1,
+        failed: &mut failed,
+    };
+    state.matches(globs, path, captures)
+}
+
+/// Options for one call of `matches_from`, along with every `**` position already known not to match
+struct MatchState<'f> {
+    options: MatchOptions,
+    /// Number of path positions, including the end, in each row of `failed`
+    width: usize,
+    /// Indexed by the remaining glob and path lengths, as each recursive call works on suffixes of both
+    failed: &'f mut [bool],
+}
+
+impl MatchState<'_> {
+    fn matches<'p>(
+        &mut self,
+        globs: &[GlobSegment],
+        path: &[PathSegment<'p>],
+        mut captures: Option<&mut Captured<'p>>,
+    ) -> bool {
+        match (globs.split_first(), path.split_first()) {
+            (_, None) if self.options.prefix => true,
+            (None, None) => true,
+            (None, Some(_)) => false,
+            (Some((GlobSegment::AnyDepth, rest)), _) => {
+                let position = globs.len() * self.width + path.len();
+                if self.failed[position] {
+                    return false;
+                }
+                let captured = captures.as_ref().map_or(0, |captures| captures.len());
+                if self.matches(rest, path, captures.as_deref_mut()) {
+                    return true;
+                }
+                if let Some(captures) = captures.as_deref_mut() {
+                    captures.truncate(captured);
+                }
+                let matched = !path.is_empty() && self.matches(globs, &path[1..], captures);
+                self.failed[position] = !matched;
+                matched
+            }
+            (Some(_), None) => false,
+            (Some((glob, globs)), Some((segment, path))) => {
+                let (matched, capture) = match (glob, segment) {
+                    (GlobSegment::Key(expected), PathSegment::Key(key))
+                        if self.options.ignore_case =>
+                    {
+                        (key.to_lowercase() == expected.to_lowercase(), None)
+                    }
+                    (GlobSegment::Key(expected), PathSegment::Key(key)) => (expected == key, None),
+                    (GlobSegment::AnyKey(name), PathSegment::Key(_)) => (true, Some(name.clone())),
+                    (GlobSegment::Index(expected), PathSegment::Index(index)) => {
+                        (expected == index, None)
+                    }
+                    (GlobSegment::AnyIndex(name), PathSegment::Index(_)) => {
+                        (true, Some(name.clone()))
+                    }
+                    (GlobSegment::Slice(slice), PathSegment::Index(index)) => {
+                        (slice.contains(*index), Some(None))
+                    }
+                    #[cfg(feature = "regex")]
+                    (GlobSegment::KeyRegex(regex), PathSegment::Key(key)) => {
+                        (regex.0.is_match(key), Some(None))
+                    }
+                    _ => (false, None),
+                };
+                if let (true, Some(captures), Some(name)) =
+                    (matched, captures.as_deref_mut(), capture)
+                {
+                    captures.push((name, *segment));
+                }
+                matched && self.matches(globs, path, captures)
+            }
+        }
+    }
+}
+
+impl FromStr for PathGlob {
+    type Err = GlobError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        PathGlob::new(pattern)
+    }
+}
+
+/// Parses the object key segment starting at `start`, returning the offset after it
+fn parse_key_segment(
+    pattern: &str,
+    start: usize,
+    segments: &mut Vec<GlobSegment>,
+    error: &dyn Fn(usize, &'static str) -> GlobError,
+) -> Result<usize, GlobError> {
+    // a leading `/` always starts a regex, so a pattern never means something else without the feature
+    if pattern[start..].starts_with('/') {
+        #[cfg(feature = "regex")]
+        {
+            let (regex, end) = parse_regex(pattern, start, error)?;
+            segments.push(GlobSegment::KeyRegex(regex));
+            return Ok(end);
+        }
+        #[cfg(not(feature = "regex"))]
+        return Err(error(start, "regex keys need the `regex` feature"));
+    }
+
+    let end = pattern[start..]
+        .find(['.', '['])
+        .map_or(pattern.len(), |offset| start + offset);
+    segments.push(parse_key(&pattern[start..end], start, error)?);
+    Ok(end)
+}
+
+/// Parses a `/regex/` key starting at `start`, where `\/` stands for a `/` inside the regex,
+/// returning the compiled regex and the offset after the closing slash
+#[cfg(feature = "regex")]
+fn parse_regex(
+    pattern: &str,
+    start: usize,
+    error: &dyn Fn(usize, &'static str) -> GlobError,
+) -> Result<(KeyRegex, usize), GlobError> {
+    let mut source = String::new();
+    let mut chars = pattern[start + 1..].char_indices();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '/' => {
+                let regex =
+                    regex::Regex::new(&source).map_err(|_| error(start, "invalid regex"))?;
+                return Ok((KeyRegex(regex), start + 1 + offset + 1));
+            }
+            '\\' => match chars.next() {
+                Some((_, '/')) => source.push('/'),
+                Some((_, escaped)) => {
+                    source.push('\\');
+                    source.push(escaped);
+                }
+                None => break,
+            },
+            _ => source.push(c),
+        }
+    }
+    Err(error(start, "unclosed regex"))
+}
+
+fn parse_key(
+    key: &str,
+    position: usize,
+    error: &dyn Fn(usize, &'static str) -> GlobError,
+) -> Result<GlobSegment, GlobError> {
+    match key {
+        "" => Err(error(position, "expected object key")),
+        "*" => Ok(GlobSegment::AnyKey(None)),
+        "**" => Ok(GlobSegment::AnyDepth),
+        _ if key.starts_with('*') && !key.starts_with("**") => {
+            Ok(GlobSegment::AnyKey(Some(key[1..].into())))
+        }
+        _ => Ok(GlobSegment::Key(key.into())),
+    }
+}
+
+/// Parses the inside of a `[start:end:step]` slice, where every part is optional and the step defaults to `1`
+fn parse_slice(
+    inner: &str,
+    position: usize,
+    error: &dyn Fn(usize, &'static str) -> GlobError,
+) -> Result<Slice, GlobError> {
+    let mut bounds = [None; 3];
+    let mut offset = position;
+    for (bound, part) in bounds.iter_mut().zip(inner.splitn(3, ':')) {
+        if !part.is_empty() {
+            let parsed = part
+                .parse::<usize>()
+                .map_err(|_| error(offset, "expected non-negative slice bound"))?;
+            *bound = Some(parsed);
+        }
+        offset += part.len() + 1;
+    }
+
+    let [start, end, step] = bounds;
+    if step == Some(0) {
+        let step_position = position + inner.rfind(':').map_or(0, |colon| colon + 1);
+        return Err(error(step_position, "slice step cannot be zero"));
+    }
+    let (start, step) = (start.unwrap_or(0), step.unwrap_or(1));
+
+    Ok(Slice { start, end, step })
+}
+
+/// Parses a double quoted key starting at `start`, returning the unescaped key and the offset after the closing quote
+fn parse_quoted(pattern: &str, start: usize) -> Option<(String, usize)> {
+    let mut key = String::new();
+    let mut chars = pattern[start + 1..].char_indices();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '"' => return Some((key, start + 1 + offset + 1)),
+            '\\' => key.push(chars.next()?.1),
+            _ => key.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mixed_segments() {
+        let glob = PathGlob::new("a[0].*[*][\"b.c\"]").unwrap();
+
+        assert_eq!(
+            glob.segments,
+            vec![
+                GlobSegment::Key("a".into()),
+                GlobSegment::Index(0),
+                GlobSegment::AnyKey(None),
+                GlobSegment::AnyIndex(None),
+                GlobSegment::Key("b.c".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_glob_matches_only_base() {
+        let glob = PathGlob::new("").unwrap();
+
+        assert!(glob.matches(&[]));
+        assert!(!glob.matches(&[PathSegment::Key("a")]));
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        assert_eq!(PathGlob::new("a..b").unwrap_err().position, 2);
+        assert_eq!(PathGlob::new("a[x]").unwrap_err().position, 2);
+        assert_eq!(PathGlob::new("[\"a]").unwrap_err().position, 0);
+        assert!(PathGlob::new(".a").is_err());
+    }
+
+    #[test]
+    fn any_depth_matches_zero_or_more_levels() {
+        let glob = PathGlob::new("a.**.email").unwrap();
+
+        assert!(glob.matches(&[PathSegment::Key("a"), PathSegment::Key("email")]));
+        assert!(glob.matches(&[
+            PathSegment::Key("a"),
+            PathSegment::Index(0),
+            PathSegment::Key("b"),
+            PathSegment::Key("email"),
+        ]));
+        assert!(!glob.matches(&[PathSegment::Key("email")]));
+        assert!(glob.matches_prefix(&[PathSegment::Key("a"), PathSegment::Index(2)]));
+        assert!(!glob.matches_prefix(&[PathSegment::Key("b")]));
+    }
+
+    #[test]
+    fn repeated_any_depth_does_not_backtrack_exponentially() {
+        let pattern = format!("{}b", "**.a.".repeat(20));
+        let glob = PathGlob::new(&pattern).unwrap();
+        let path = vec![PathSegment::Key("a"); 60];
+
+        assert!(!glob.matches(&path));
+        assert!(glob.matches_prefix(&path));
+    }
+
+    #[test]
+    fn depth_range_limits_prefixes() {
+        let glob = PathGlob::new("**").unwrap().depth(1..2);
+        let (a, b) = (PathSegment::Key("a"), PathSegment::Key("b"));
+
+        assert!(!glob.matches(&[]));
+        assert!(glob.matches(&[a]));
+        assert!(!glob.matches(&[a, b]));
+        assert!(glob.matches_prefix(&[a]));
+        assert!(!glob.matches_prefix(&[a, b]));
+    }
+
+    #[test]
+    fn empty_depth_ranges_match_nothing() {
+        let a = PathSegment::Key("a");
+        let excluded = (Bound::Excluded(usize::MAX), Bound::Unbounded);
+
+        assert!(!PathGlob::new("**").unwrap().depth(..0).matches(&[]));
+        assert!(!PathGlob::new("**").unwrap().depth(excluded).matches(&[a]));
+        assert!(PathGlob::new("**")
+            .unwrap()
+            .depth(..=usize::MAX)
+            .matches(&[a]));
+    }
+
+    #[test]
+    fn slices_match_stepped_ranges() {
+        let glob = PathGlob::new("[1:6:2]").unwrap();
+        let matched: Vec<_> = (0..8)
+            .filter(|&index| glob.matches(&[PathSegment::Index(index)]))
+            .collect();
+
+        assert_eq!(matched, vec![1, 3, 5]);
+        assert!(PathGlob::new("[::2]")
+            .unwrap()
+            .matches(&[PathSegment::Index(4)]));
+        assert!(PathGlob::new("[2:]")
+            .unwrap()
+            .matches(&[PathSegment::Index(9)]));
+        assert_eq!(PathGlob::new("a[0:-1]").unwrap_err().position, 4);
+        assert_eq!(PathGlob::new("[1:2:0]").unwrap_err().position, 5);
+    }
+
+    #[cfg(not(feature = "regex"))]
+    #[test]
+    fn regex_keys_need_the_feature() {
+        assert_eq!(PathGlob::new("a./^b$/").unwrap_err().position, 2);
+        assert!(PathGlob::new("a[\"/b/\"]")
+            .unwrap()
+            .matches(&[PathSegment::Key("a"), PathSegment::Key("/b/")]));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_keys_match_whole_segments() {
+        let glob = PathGlob::new("a./^b\\/[0-9.]+$/[*]").unwrap();
+
+        assert!(glob.matches(&[
+            PathSegment::Key("a"),
+            PathSegment::Key("b/1.5"),
+            PathSegment::Index(0)
+        ]));
+        assert!(!glob.matches(&[
+            PathSegment::Key("a"),
+            PathSegment::Key("b/x"),
+            PathSegment::Index(0)
+        ]));
+        assert_eq!(PathGlob::new("a./b").unwrap_err().position, 2);
+        assert_eq!(PathGlob::new("/(/").unwrap_err().position, 0);
+    }
+
+    #[test]
+    fn ignore_case_folds_both_sides_and_keeps_the_pattern() {
+        let glob = PathGlob::new("ΣΑΣ").unwrap().ignore_case();
+
+        assert!(glob.matches(&[PathSegment::Key("σας")]));
+        assert!(glob.matches(&[PathSegment::Key("ΣΑΣ")]));
+        assert_eq!(glob.segments, vec![GlobSegment::Key("ΣΑΣ".into())]);
+        assert_ne!(glob, PathGlob::new("σας").unwrap().ignore_case());
+    }
+
+    #[test]
+    fn wildcards_do_not_cross_kinds() {
+        let glob = PathGlob::new("*").unwrap();
+
+        assert!(glob.matches(&[PathSegment::Key("a")]));
+        assert!(!glob.matches(&[PathSegment::Index(0)]));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::node::{compare_numbers, JsonNode, NodeKind};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Condition on the value found at a path, attached to a glob with `PathGlob::where_value`
+///
+/// Only `null`, booleans, numbers, and strings can be compared, since nodes only expose those as plain values.
+/// `Equals` compares numbers by their exact values and the bounds compare them as `f64`,
+/// and any comparison against a value of another kind does not hold.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{NodeKind, ValuePredicate};
+///
+/// assert!(ValuePredicate::Equals(json!(2)).matches(&json!(2.0)));
+/// assert!(ValuePredicate::Contains("ail".into()).matches(&json!("failed")));
+/// assert!(ValuePredicate::Contains("id".into()).matches(&json!({"id": 1})));
+/// assert!(ValuePredicate::GreaterThan(1.5).matches(&json!(2)));
+/// assert!(!ValuePredicate::GreaterThan(1.5).matches(&json!("2")));
+/// assert!(ValuePredicate::Kind(NodeKind::Array).matches(&json!([])));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValuePredicate {
+    /// Value equals a `null`, boolean, number, or string
+    Equals(Value),
+    /// Value does not equal a `null`, boolean, number, or string
+    NotEquals(Value),
+    /// String value containing the text, array with a string item equal to it, or object with it as a key
+    Contains(String),
+    /// Number less than the bound
+    LessThan(f64),
+    /// Number less than or equal to the bound
+    AtMost(f64),
+    /// Number greater than the bound
+    GreaterThan(f64),
+    /// Number greater than or equal to the bound
+    AtLeast(f64),
+    /// Value of the given kind
+    Kind(NodeKind),
+}
+
+impl ValuePredicate {
+    /// Whether the value satisfies this condition
+    pub fn matches<V: JsonNode>(&self, value: &V) -> bool {
+        match self {
+            ValuePredicate::Equals(expected) => equals(value, expected),
+            ValuePredicate::NotEquals(expected) => !equals(value, expected),
+            ValuePredicate::Contains(text) => match value.kind() {
+                NodeKind::String => value.as_str().is_some_and(|s| s.contains(text.as_str())),
+                NodeKind::Array => value
+                    .items()
+                    .is_some_and(|mut items| items.any(|item| item.as_str() == Some(text))),
+                NodeKind::Object => value
+                    .entries()
+                    .is_some_and(|mut entries| entries.any(|(key, _)| key == text)),
+                _ => false,
+            },
+            ValuePredicate::LessThan(bound) => value.as_f64().is_some_and(|n| n < *bound),
+            ValuePredicate::AtMost(bound) => value.as_f64().is_some_and(|n| n <= *bound),
+            ValuePredicate::GreaterThan(bound) => value.as_f64().is_some_and(|n| n > *bound),
+            ValuePredicate::AtLeast(bound) => value.as_f64().is_some_and(|n| n >= *bound),
+            ValuePredicate::Kind(kind) => value.kind() == *kind,
+        }
+    }
+}
+
+fn equals<V: JsonNode>(value: &V, expected: &Value) -> bool {
+    match expected {
+        Value::Null => value.kind() == NodeKind::Null,
+        Value::Bool(b) => value.as_bool() == Some(*b),
+        Value::Number(_) => compare_numbers(value, expected) == Some(Ordering::Equal),
+        Value::String(s) => value.as_str() == Some(s.as_str()),
+        Value::Array(_) | Value::Object(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn containers_never_equal() {
+        assert!(!ValuePredicate::Equals(json!([1])).matches(&json!([1])));
+        assert!(ValuePredicate::NotEquals(json!("a")).matches(&json!(null)));
+        assert!(!ValuePredicate::Equals(json!(null)).matches(&json!(false)));
+    }
+
+    #[test]
+    fn integers_equal_exactly() {
+        let expected = ValuePredicate::Equals(json!(9007199254740992u64));
+
+        assert!(expected.matches(&json!(9007199254740992u64)));
+        assert!(!expected.matches(&json!(9007199254740993u64)));
+        assert!(ValuePredicate::Equals(json!(-1)).matches(&json!(-1.0)));
+    }
+}
@@ -0,0 +1,81 @@
+use super::PathGlob;
+use crate::iter::Iter;
+use crate::node::JsonNode;
+use crate::style::StyleBuilder;
+
+/// Whether any value in the json structure matches the glob, including its value conditions
+///
+/// Objects and arrays are checked too, so a glob naming a container finds it even when styles would skip it.
+/// Stops at the first match, only walks subtrees the glob could match, and never formats a path.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{exists, PathGlob};
+///
+/// let value = json!({"users": [{"name": "ada"}, {"name": "bob", "email": "b@x"}]});
+///
+/// assert!(exists(&value, &"users[*].email".parse().unwrap()));
+/// assert!(!exists(&value, &"users[0].email".parse().unwrap()));
+/// ```
+pub fn exists<V: JsonNode>(json: &V, glob: &PathGlob) -> bool {
+    let mut iter = matching_walk(json, glob);
+    while let Some(value) = iter.advance() {
+        if glob.matches(iter.current_segments()) && glob.matches_value(value) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Number of values in the json structure matching the glob, including its value conditions
+///
+/// Walks like `exists` without stopping early, so no `Element` is built and no path is formatted.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{count, PathGlob};
+///
+/// let order = json!({"items": [{"sku": 1}, {"sku": 2, "parts": [{"sku": 3}]}]});
+///
+/// assert_eq!(count(&order, &"**.sku".parse().unwrap()), 3);
+/// assert_eq!(count(&order, &"items[*]".parse().unwrap()), 2);
+/// ```
+pub fn count<V: JsonNode>(json: &V, glob: &PathGlob) -> usize {
+    let mut iter = matching_walk(json, glob);
+    let mut matched = 0;
+    while let Some(value) = iter.advance() {
+        if glob.matches(iter.current_segments()) && glob.matches_value(value) {
+            matched += 1;
+        }
+    }
+    matched
+}
+
+/// Iterator over every value including objects and arrays that only keeps path segments,
+/// skipping subtrees the glob cannot match
+fn matching_walk<'a, V: JsonNode>(json: &'a V, glob: &PathGlob) -> Iter<'a, V> {
+    let mut iter = Iter::new(json)
+        .use_style(StyleBuilder::new().include_parents().build())
+        .paths_disabled()
+        .indices_disabled();
+    iter.prune_to(vec![glob.clone()].into());
+    iter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sees_skipped_parents_and_the_base() {
+        let value = json!({"a": {"b": []}});
+
+        assert!(exists(&value, &PathGlob::new("").unwrap()));
+        assert!(exists(&value, &PathGlob::new("a.b").unwrap()));
+        assert!(!exists(&value, &PathGlob::new("a.b[0]").unwrap()));
+        assert_eq!(count(&value, &PathGlob::new("**").unwrap()), 3);
+    }
+}
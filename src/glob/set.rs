@@ -0,0 +1,92 @@
+use super::{GlobError, PathGlob};
+use crate::node::JsonNode;
+use crate::path::PathSegment;
+use std::iter::FromIterator;
+
+/// Several globs matched together in a single traversal, see `Iterator::find_all_matches`
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::{PathGlobSet, PathSegment};
+///
+/// let set = PathGlobSet::new(["a.*", "**.id"]).unwrap();
+///
+/// assert_eq!(set.matching(&[PathSegment::Key("a"), PathSegment::Key("id")]), vec![0, 1]);
+/// assert_eq!(set.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathGlobSet {
+    globs: Vec<PathGlob>,
+}
+
+impl PathGlobSet {
+    /// Parse a new set from pattern strings, failing on the first pattern that cannot be parsed
+    pub fn new<I, P>(patterns: I) -> Result<Self, GlobError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        patterns
+            .into_iter()
+            .map(|pattern| PathGlob::new(pattern.as_ref()))
+            .collect::<Result<_, _>>()
+            .map(|globs| Self { globs })
+    }
+
+    /// Globs in the set, in the order their positions are reported
+    pub fn globs(&self) -> &[PathGlob] {
+        &self.globs
+    }
+
+    /// Number of globs in the set
+    pub fn len(&self) -> usize {
+        self.globs.len()
+    }
+
+    /// Whether the set has no globs, and so matches nothing
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_empty()
+    }
+
+    /// Positions of every glob matching the path, ignoring any value conditions
+    pub fn matching(&self, path: &[PathSegment]) -> Vec<usize> {
+        self.positions(|glob| glob.matches(path))
+    }
+
+    /// Positions of every glob matching both the path and the value at it
+    pub(crate) fn matching_element<V: JsonNode>(
+        &self,
+        path: &[PathSegment],
+        value: &V,
+    ) -> Vec<usize> {
+        self.positions(|glob| glob.matches(path) && glob.matches_value(value))
+    }
+
+    /// Whether any glob could match the path or something nested below it, see `PathGlob::matches_prefix`
+    pub fn matches_prefix(&self, path: &[PathSegment]) -> bool {
+        self.globs.iter().any(|glob| glob.matches_prefix(path))
+    }
+
+    fn positions(&self, mut matches: impl FnMut(&PathGlob) -> bool) -> Vec<usize> {
+        self.globs
+            .iter()
+            .enumerate()
+            .filter(|(_, glob)| matches(glob))
+            .map(|(position, _)| position)
+            .collect()
+    }
+}
+
+impl From<Vec<PathGlob>> for PathGlobSet {
+    fn from(globs: Vec<PathGlob>) -> Self {
+        Self { globs }
+    }
+}
+
+impl FromIterator<PathGlob> for PathGlobSet {
+    fn from_iter<I: IntoIterator<Item = PathGlob>>(iter: I) -> Self {
+        Self {
+            globs: iter.into_iter().collect(),
+        }
+    }
+}
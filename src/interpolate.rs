@@ -0,0 +1,283 @@
+use crate::glob::PathGlob;
+use crate::path::PathSegment;
+use crate::style::{PresetStyle, Style};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error describing why `interpolate` could not resolve a reference
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterpolateError {
+    /// A `${` is not closed, or the text inside it is not a path without wildcards
+    InvalidReference {
+        /// Path of the string holding the reference
+        path: String,
+        /// The reference as written
+        reference: String,
+    },
+    /// Nothing exists at the path a reference points to
+    Missing {
+        /// Path of the string holding the reference
+        path: String,
+        /// The reference as written
+        reference: String,
+    },
+    /// A string depends on itself through its references
+    Cycle {
+        /// Path of a string on the cycle
+        path: String,
+    },
+}
+
+impl fmt::Display for InterpolateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpolateError::InvalidReference { path, reference } => {
+                write!(f, "invalid reference {:?} at path {:?}", reference, path)
+            }
+            InterpolateError::Missing { path, reference } => write!(
+                f,
+                "reference {:?} at path {:?} points to nothing",
+                reference, path,
+            ),
+            InterpolateError::Cycle { path } => {
+                write!(f, "string at path {:?} refers back to itself", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InterpolateError {}
+
+/// Replaces `${path}` references in string leaves with the values they point to in the same document,
+/// formatting error paths with the default style
+///
+/// References are written like glob patterns without wildcards, such as `${db.hosts[0]}` or `${["a.b"]}`,
+/// and referenced strings are interpolated first. A string made of a single reference takes the referenced value
+/// as it is, whatever its kind, while references inside longer strings are replaced by the referenced string,
+/// or the json text of any other value. `$${` is written as a literal `${`. On error, the document is left unchanged.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{interpolate, InterpolateError};
+///
+/// let mut config = json!({
+///     "host": "db.local",
+///     "port": 5432,
+///     "url": "postgres://${host}:${port}/${names[0]}",
+///     "names": ["app"],
+///     "replica": {"port": "${port}", "url": "${url}"},
+///     "note": "costs $${x}",
+/// });
+/// interpolate(&mut config).unwrap();
+///
+/// assert_eq!(config["url"], "postgres://db.local:5432/app");
+/// assert_eq!(config["replica"], json!({"port": 5432, "url": "postgres://db.local:5432/app"}));
+/// assert_eq!(config["note"], "costs ${x}");
+///
+/// let mut cyclic = json!({"a": "${b}", "b": "x${a}"});
+/// assert_eq!(interpolate(&mut cyclic), Err(InterpolateError::Cycle { path: "[\"a\"]".into() }));
+/// ```
+pub fn interpolate(json: &mut Value) -> Result<(), InterpolateError> {
+    interpolate_with(json, &PresetStyle::SquareBrackets.into())
+}
+
+/// Replaces `${path}` references in string leaves, formatting error paths in the given style, see `interpolate`
+pub fn interpolate_with(json: &mut Value, style: &Style) -> Result<(), InterpolateError> {
+    let interpolated = Resolver {
+        source: &*json,
+        style,
+        resolved: HashMap::new(),
+        resolving: Vec::new(),
+    }
+    .node(json, &mut Vec::new())?;
+    *json = interpolated;
+    Ok(())
+}
+
+struct Resolver<'a, 's> {
+    source: &'a Value,
+    style: &'s Style<'s>,
+    /// Strings already interpolated, by path
+    resolved: HashMap<Vec<PathSegment<'a>>, Value>,
+    /// Paths of the strings being interpolated, innermost last
+    resolving: Vec<Vec<PathSegment<'a>>>,
+}
+
+impl<'a, 's> Resolver<'a, 's> {
+    fn node(
+        &mut self,
+        value: &'a Value,
+        segments: &mut Vec<PathSegment<'a>>,
+    ) -> Result<Value, InterpolateError> {
+        match value {
+            Value::String(s) if s.contains("${") => self.string(s, segments),
+            Value::Object(obj) => {
+                let mut interpolated = Map::new();
+                for (key, val) in obj {
+                    segments.push(PathSegment::Key(key));
+                    let val = self.node(val, segments);
+                    segments.pop();
+                    interpolated.insert(key.clone(), val?);
+                }
+                Ok(Value::Object(interpolated))
+            }
+            Value::Array(arr) => {
+                let mut interpolated = Vec::with_capacity(arr.len());
+                for (index, val) in arr.iter().enumerate() {
+                    segments.push(PathSegment::Index(index));
+                    let val = self.node(val, segments);
+                    segments.pop();
+                    interpolated.push(val?);
+                }
+                Ok(Value::Array(interpolated))
+            }
+            _ => Ok(value.clone()),
+        }
+    }
+
+    fn string(
+        &mut self,
+        s: &'a str,
+        segments: &[PathSegment<'a>],
+    ) -> Result<Value, InterpolateError> {
+        if let Some(value) = self.resolved.get(segments) {
+            return Ok(value.clone());
+        }
+        if self.resolving.iter().any(|path| path == segments) {
+            return Err(InterpolateError::Cycle {
+                path: self.style.format_segments(segments),
+            });
+        }
+
+        self.resolving.push(segments.to_vec());
+        let value = self.expand(s, segments);
+        self.resolving.pop();
+
+        let value = value?;
+        self.resolved.insert(segments.to_vec(), value.clone());
+        Ok(value)
+    }
+
+    fn expand(&mut self, s: &str, segments: &[PathSegment<'a>]) -> Result<Value, InterpolateError> {
+        let whole = s
+            .strip_prefix("${")
+            .and_then(|rest| rest.strip_suffix('}'))
+            .filter(|reference| !reference.contains('}'));
+        if let Some(reference) = whole {
+            return self.lookup(reference, segments);
+        }
+
+        let mut expanded = String::with_capacity(s.len());
+        let mut rest = s;
+        while let Some(start) = rest.find("${") {
+            if rest[..start].ends_with('$') {
+                expanded.push_str(&rest[..start - 1]);
+                expanded.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+
+            expanded.push_str(&rest[..start]);
+            let end =
+                rest[start..]
+                    .find('}')
+                    .ok_or_else(|| InterpolateError::InvalidReference {
+                        path: self.style.format_segments(segments),
+                        reference: rest[start..].into(),
+                    })?;
+            match self.lookup(&rest[start + 2..start + end], segments)? {
+                Value::String(value) => expanded.push_str(&value),
+                value => expanded.push_str(&value.to_string()),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        expanded.push_str(rest);
+        Ok(Value::String(expanded))
+    }
+
+    /// Interpolated value the reference points to, read from the string at `segments`
+    fn lookup(
+        &mut self,
+        reference: &str,
+        segments: &[PathSegment<'a>],
+    ) -> Result<Value, InterpolateError> {
+        let glob = reference.trim().parse::<PathGlob>().ok();
+        let path = glob.as_ref().and_then(PathGlob::as_path).ok_or_else(|| {
+            InterpolateError::InvalidReference {
+                path: self.style.format_segments(segments),
+                reference: format!("${{{}}}", reference),
+            }
+        })?;
+
+        let mut target = self.source;
+        let mut target_segments = Vec::with_capacity(path.len());
+        for segment in path {
+            let found = match (segment, target) {
+                (PathSegment::Key(key), Value::Object(obj)) => obj
+                    .get_key_value(key)
+                    .map(|(key, val)| (PathSegment::Key(key), val)),
+                (PathSegment::Index(index), Value::Array(arr)) => {
+                    arr.get(index).map(|val| (PathSegment::Index(index), val))
+                }
+                _ => None,
+            };
+            let (segment, val) = found.ok_or_else(|| InterpolateError::Missing {
+                path: self.style.format_segments(segments),
+                reference: format!("${{{}}}", reference),
+            })?;
+            target_segments.push(segment);
+            target = val;
+        }
+        self.node(target, &mut target_segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn errors_name_the_string_and_the_reference() {
+        let style = PresetStyle::CommonJs.into();
+        let mut value = json!({"a": {"b": "x ${a.c} y"}, "d": "${e[*]}", "f": "${g"});
+
+        assert_eq!(
+            interpolate_with(&mut value, &style),
+            Err(InterpolateError::Missing {
+                path: ".a.b".into(),
+                reference: "${a.c}".into(),
+            })
+        );
+        value["a"]["c"] = json!(1);
+        assert_eq!(
+            interpolate_with(&mut value, &style),
+            Err(InterpolateError::InvalidReference {
+                path: ".d".into(),
+                reference: "${e[*]}".into(),
+            })
+        );
+        value["d"] = json!("${a}");
+        assert_eq!(
+            interpolate_with(&mut value, &style),
+            Err(InterpolateError::InvalidReference {
+                path: ".f".into(),
+                reference: "${g".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn referencing_an_enclosing_object_is_a_cycle() {
+        let mut value = json!({"a": {"b": "${a}"}});
+
+        assert_eq!(
+            interpolate(&mut value),
+            Err(InterpolateError::Cycle {
+                path: "[\"a\"][\"b\"]".into()
+            })
+        );
+    }
+}
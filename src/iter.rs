@@ -1,27 +1,117 @@
+use crate::glob::{Captures, PathGlob, PathGlobSet};
+use crate::limits::{AmbiguousKeys, LimitExceeded, Limits, OverlongPath};
+use crate::node::JsonNode;
+use crate::path::PathSegment;
 use crate::style::{PresetStyle, Style};
 use serde_json::Value;
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::iter::FusedIterator;
 
 /// Single element struct containing the path, set of array indices, and json value
 #[derive(Debug, PartialEq)]
-pub struct Element<'a> {
+pub struct Element<'a, V = Value> {
     /// The full path from the base of a json structure to the value contained in the `Element`
     pub path: String,
     /// The full set of _array_ indices in the path, useful for grouping sets of `Element` structs to the same array element
     pub indices: Vec<usize>,
-    /// The `serde_json::Value` of described by the path
-    pub value: &'a Value,
+    /// The `serde_json::Value` (or other `JsonNode`) described by the path
+    pub value: &'a V,
 }
 
-/// Iteration strict containing a queue of elements that still need to be yielded along with a style object
-#[derive(Debug)]
-pub struct Iter<'a> {
+impl<'a> Element<'a> {
+    /// Text of the number this element holds, or `None` if it is not a number
+    ///
+    /// With the `arbitrary_precision` feature the text is exactly as it was parsed, so numbers too large or precise
+    /// for `i64`, `u64`, or `f64` keep every digit. Otherwise it is the shortest text that reads back as the same number.
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::Iterator;
+    ///
+    /// let value: serde_json::Value = serde_json::from_str(r#"{"name": "x", "price": 19.99}"#).unwrap();
+    /// let raw: Vec<_> = Iterator::new(&value).map(|el| el.raw_number().map(String::from)).collect();
+    ///
+    /// assert_eq!(raw, vec![None, Some("19.99".to_string())]);
+    /// ```
+    pub fn raw_number(&self) -> Option<Cow<'a, str>> {
+        match self.value {
+            #[cfg(feature = "arbitrary_precision")]
+            Value::Number(n) => Some(Cow::Borrowed(n.as_str())),
+            #[cfg(not(feature = "arbitrary_precision"))]
+            Value::Number(n) => Some(Cow::Owned(n.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Container whose children are still being yielded, along with the buffer lengths of its own path
+///
+/// Children are only pulled from the container, and their paths formatted, as they are reached.
+enum Frame<'a, V: JsonNode + 'a> {
+    Object {
+        entries: V::Entries<'a>,
+        path_len: usize,
+        depth: usize,
+        indices_len: usize,
+    },
+    Array {
+        items: std::iter::Enumerate<V::Items<'a>>,
+        path_len: usize,
+        depth: usize,
+        indices_len: usize,
+    },
+}
+
+/// Counters of the work done by an `Iterator`, see `Iterator::metrics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IterMetrics {
+    /// Number of elements yielded so far
+    pub elements_yielded: usize,
+    /// Number of objects and arrays walked without being yielded, because the style skips them
+    pub parents_skipped: usize,
+    /// Deepest number of path segments reached, where the base of the json structure is `0`
+    pub max_depth: usize,
+    /// Total bytes of key and index segments formatted into paths, not counting the whole-path wrapper
+    pub path_bytes_formatted: usize,
+}
+
+/// Iteration struct holding a stack of containers still being walked along with a style object
+///
+/// The path, indices, and segments of the current value are kept in buffers that are reused for every element,
+/// and only copied out when an `Element` is yielded.
+pub struct Iter<'a, V: JsonNode = Value> {
     style: Style<'a>,
-    items: VecDeque<Element<'a>>,
+    root: Option<&'a V>,
+    frames: Vec<Frame<'a, V>>,
+    path: String,
+    indices: Vec<usize>,
+    segments: Vec<PathSegment<'a>>,
+    build_paths: bool,
+    build_indices: bool,
+    remaining: Option<usize>,
+    metrics: IterMetrics,
+    prune: Option<PathGlobSet>,
+    max_path_len: Option<(usize, OverlongPath<'a>)>,
+    limits: Limits,
+    strict_keys: Option<AmbiguousKeys>,
+    ambiguous: Vec<String>,
+    exceeded: Option<LimitExceeded>,
+}
+
+impl<'a, V: JsonNode> fmt::Debug for Iter<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter")
+            .field("style", &self.style)
+            .field("depth", &self.frames.len())
+            .field("path", &self.path)
+            .finish()
+    }
 }
 
 /// Named `Iter` internally, but `Iterator` externally
-impl<'a> Iter<'a> {
+impl<'a, V: JsonNode> Iter<'a, V> {
     /// Create a new json keypath iterator
     ///
     /// Example:
@@ -36,17 +126,24 @@ impl<'a> Iter<'a> {
     /// assert_eq!(items[0], Element { path: "[\"a\"][0]".into(), indices: vec![0], value: &json!(1), });
     /// assert_eq!(items[1], Element { path: "[\"a\"][1]".into(), indices: vec![1], value: &json!(2), });
     /// ```
-    pub fn new(json: &'a Value) -> Self {
-        let mut queue = VecDeque::new();
-        queue.push_back(Element {
-            path: String::from(""),
-            indices: Vec::new(),
-            value: json,
-        });
-
+    pub fn new(json: &'a V) -> Self {
         Self {
-            items: queue,
             style: PresetStyle::SquareBrackets.into(),
+            root: Some(json),
+            frames: Vec::new(),
+            path: String::new(),
+            indices: Vec::new(),
+            segments: Vec::new(),
+            build_paths: true,
+            build_indices: true,
+            remaining: None,
+            metrics: IterMetrics::default(),
+            prune: None,
+            max_path_len: None,
+            limits: Limits::default(),
+            strict_keys: None,
+            ambiguous: Vec::new(),
+            exceeded: None,
         }
     }
 
@@ -70,52 +167,997 @@ impl<'a> Iter<'a> {
         self.style = style;
         self
     }
+
+    /// Skips formatting paths, so every yielded `Element` has an empty `path`
+    ///
+    /// The style still decides which parents are yielded, and `find_path` still matches on structure.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, Element};
+    ///
+    /// let value = json!({"a": [1, 2]});
+    /// let mut iter = Iterator::new(&value).paths_disabled();
+    ///
+    /// assert_eq!(iter.next(), Some(Element { path: "".into(), indices: vec![0], value: &json!(1), }));
+    /// ```
+    pub fn paths_disabled(mut self) -> Self {
+        self.build_paths = false;
+        self
+    }
+
+    /// Skips collecting array indices, so every yielded `Element` has empty `indices`
+    pub fn indices_disabled(mut self) -> Self {
+        self.build_indices = false;
+        self
+    }
+
+    /// Consumes the iterator, yielding only the values in document order without building paths or indices
+    ///
+    /// The style still decides whether objects and arrays are visited before their children.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, StyleBuilder};
+    ///
+    /// let value = json!({"a": [1, {"b": 2}]});
+    /// let values: Vec<_> = Iterator::new(&value).values().collect();
+    ///
+    /// assert_eq!(values, vec![&json!(1), &json!(2)]);
+    ///
+    /// let style = StyleBuilder::new().include_array_parents().build();
+    /// let values: Vec<_> = Iterator::new(&value).use_style(style).values().collect();
+    ///
+    /// assert_eq!(values, vec![&json!([1, {"b": 2}]), &json!(1), &json!(2)]);
+    /// ```
+    pub fn values(self) -> Values<'a, V> {
+        Values {
+            iter: self.paths_disabled().indices_disabled(),
+        }
+    }
+
+    /// Consumes the iterator, yielding only the formatted paths in document order without collecting indices
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PresetStyle};
+    ///
+    /// let value = json!({"a": [1, {"b": 2}]});
+    /// let paths: Vec<_> = Iterator::new(&value).use_style(PresetStyle::CommonJs.into()).paths().collect();
+    ///
+    /// assert_eq!(paths, vec![".a[0]", ".a[1].b"]);
+    /// ```
+    pub fn paths(self) -> Paths<'a, V> {
+        Paths {
+            iter: self.indices_disabled(),
+        }
+    }
+
+    /// Consumes the iterator, yielding `(path, value)` tuples without collecting indices
+    ///
+    /// The tuples can be collected straight into any map or vector of pairs.
+    ///
+    /// Example:
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PresetStyle};
+    ///
+    /// let value = json!({"a": [1, {"b": 2}]});
+    /// let map: HashMap<_, _> = Iterator::new(&value).use_style(PresetStyle::CommonJs.into()).pairs().collect();
+    ///
+    /// assert_eq!(map[".a[1].b"], &json!(2));
+    /// ```
+    pub fn pairs(self) -> Pairs<'a, V> {
+        Pairs {
+            iter: self.indices_disabled(),
+        }
+    }
+
+    /// Consumes the iterator, yielding each value with its array indices but without building any paths
+    ///
+    /// The indices are borrowed from a buffer reused for every value, so they are yielded by `Indexed::next_indexed`
+    /// rather than through `std::iter::Iterator`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::Iterator;
+    ///
+    /// let value = json!([[1, 2], [3]]);
+    /// let mut indexed = Iterator::new(&value).indexed();
+    /// let mut items = Vec::new();
+    /// while let Some((indices, value)) = indexed.next_indexed() {
+    ///     items.push((indices.to_vec(), value));
+    /// }
+    ///
+    /// assert_eq!(items, vec![(vec![0, 0], &json!(1)), (vec![0, 1], &json!(2)), (vec![1, 0], &json!(3))]);
+    /// ```
+    pub fn indexed(self) -> Indexed<'a, V> {
+        Indexed {
+            iter: self.paths_disabled(),
+        }
+    }
+
+    /// Advances the iterator to the first element whose path matches the glob, stopping as soon as it is found
+    ///
+    /// Only elements that would otherwise be yielded are considered, so parents skipped by the style never match.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, Element, PathGlob};
+    ///
+    /// let value = json!({"items": [{"price": 3}, {"price": 5}]});
+    /// let glob: PathGlob = "items[*].price".parse().unwrap();
+    /// let mut iter = Iterator::new(&value);
+    ///
+    /// assert_eq!(iter.find_path(&glob), Some(Element { path: "[\"items\"][0][\"price\"]".into(), indices: vec![0], value: &json!(3), }));
+    /// assert_eq!(iter.find_path(&glob), Some(Element { path: "[\"items\"][1][\"price\"]".into(), indices: vec![1], value: &json!(5), }));
+    /// assert_eq!(iter.find_path(&glob), None);
+    /// ```
+    pub fn find_path(&mut self, glob: &PathGlob) -> Option<Element<'a, V>> {
+        while let Some(value) = self.advance() {
+            if glob.matches(&self.segments) && glob.matches_value(value) {
+                return Some(self.element(value));
+            }
+        }
+        None
+    }
+
+    /// Consumes the iterator, yielding every element whose path matches the glob
+    ///
+    /// Objects and arrays whose path can never lead to a match are skipped without walking anything inside them,
+    /// so extracting a few values from a large document only visits the branches the glob names.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathGlob};
+    ///
+    /// let value = json!({"items": [{"price": 3, "qty": 1}, {"price": 5, "qty": 2}]});
+    /// let glob: PathGlob = "items[*].price".parse().unwrap();
+    /// let prices: Vec<_> = Iterator::new(&value).find_all_paths(glob).map(|el| el.value).collect();
+    ///
+    /// assert_eq!(prices, vec![&json!(3), &json!(5)]);
+    /// ```
+    pub fn find_all_paths(mut self, glob: PathGlob) -> PathMatches<'a, V> {
+        self.prune_to(vec![glob.clone()].into());
+        PathMatches { iter: self, glob }
+    }
+
+    /// Consumes the iterator like `find_all_paths`, also yielding the segments bound to each wildcard in the glob
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathGlob, PathSegment};
+    ///
+    /// let value = json!({"users": [{"roles": ["admin"]}, {"roles": ["dev", "ops"]}]});
+    /// let glob: PathGlob = "users[*user].roles[*role]".parse().unwrap();
+    /// let indices: Vec<_> = Iterator::new(&value)
+    ///     .find_all_captures(glob)
+    ///     .map(|(_, captures)| (captures.name("user"), captures.name("role")))
+    ///     .collect();
+    ///
+    /// assert_eq!(indices[2], (Some(PathSegment::Index(1)), Some(PathSegment::Index(1))));
+    /// ```
+    pub fn find_all_captures(mut self, glob: PathGlob) -> CaptureMatches<'a, V> {
+        self.prune_to(vec![glob.clone()].into());
+        CaptureMatches { iter: self, glob }
+    }
+
+    /// Consumes the iterator, yielding every element whose path matches at least one glob in the set,
+    /// along with the positions of all the globs it matched
+    ///
+    /// The whole set is matched in one traversal, skipping subtrees that no glob could match like `find_all_paths`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathGlobSet};
+    ///
+    /// let value = json!({"a": {"id": 1, "x": 2}, "b": [{"c": 3}]});
+    /// let set = PathGlobSet::new(["a.*", "b[0].c", "**.id"]).unwrap();
+    /// let matches: Vec<_> = Iterator::new(&value).find_all_matches(set).map(|(el, matched)| (el.value, matched)).collect();
+    ///
+    /// assert_eq!(matches, vec![(&json!(1), vec![0, 2]), (&json!(2), vec![0]), (&json!(3), vec![1])]);
+    /// ```
+    pub fn find_all_matches(mut self, set: PathGlobSet) -> SetMatches<'a, V> {
+        self.prune_to(set.clone());
+        SetMatches { iter: self, set }
+    }
+
+    /// Advances the iterator to the first element whose value satisfies the predicate, stopping as soon as it is found
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, Element};
+    ///
+    /// let value = json!({"a": [1, "two", 3]});
+    /// let mut iter = Iterator::new(&value);
+    ///
+    /// assert_eq!(iter.find_value(|v| v.is_string()), Some(Element { path: "[\"a\"][1]".into(), indices: vec![1], value: &json!("two"), }));
+    /// ```
+    pub fn find_value<P>(&mut self, mut predicate: P) -> Option<Element<'a, V>>
+    where
+        P: FnMut(&V) -> bool,
+    {
+        self.find(|el| predicate(el.value))
+    }
+
+    /// Appends up to `n` elements onto the buffer, returning how many were added
+    ///
+    /// Fewer than `n` are only added once the iterator is exhausted, so a return of `0` means it is done.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::Iterator;
+    ///
+    /// let value = json!([1, 2, 3]);
+    /// let mut iter = Iterator::new(&value);
+    /// let mut batch = Vec::new();
+    ///
+    /// assert_eq!(iter.next_batch(&mut batch, 2), 2);
+    /// batch.clear();
+    /// assert_eq!(iter.next_batch(&mut batch, 2), 1);
+    /// assert_eq!(batch[0].path, "[2]");
+    /// assert_eq!(iter.next_batch(&mut batch, 2), 0);
+    /// ```
+    pub fn next_batch(&mut self, buffer: &mut Vec<Element<'a, V>>, n: usize) -> usize {
+        buffer.reserve(n.min(self.size_hint().0));
+        let mut added = 0;
+        while added < n {
+            match self.advance() {
+                Some(value) => buffer.push(self.element(value)),
+                None => break,
+            }
+            added += 1;
+        }
+        added
+    }
+
+    /// Consumes the iterator, yielding each value with the structural segments of its path, without building any paths
+    ///
+    /// The segments are a view into a stack reused for every value, valid until the next call,
+    /// so they are yielded by `Segments::next_segments` rather than through `std::iter::Iterator`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, PathSegment};
+    ///
+    /// let value = json!({"a": [true]});
+    /// let mut segments = Iterator::new(&value).segments();
+    ///
+    /// assert_eq!(segments.next_segments(), Some((&[PathSegment::Key("a"), PathSegment::Index(0)][..], &json!(true))));
+    /// assert_eq!(segments.next_segments(), None);
+    /// ```
+    pub fn segments(self) -> Segments<'a, V> {
+        Segments {
+            iter: self.paths_disabled().indices_disabled(),
+        }
+    }
+
+    /// Consumes the iterator, collecting every path and value into a sorted map
+    ///
+    /// Styles that hide or wildcard keys or indices can format several values with the same path,
+    /// in which case the value yielded last, meaning the latest in document order, is kept.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{PresetStyle, StyleBuilder, Iterator};
+    ///
+    /// let value = json!({"b": [1, 2], "a": true});
+    /// let map = Iterator::new(&value).use_style(PresetStyle::CommonJs.into()).collect_map();
+    ///
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![
+    ///     (".a".into(), &json!(true)),
+    ///     (".b[0]".into(), &json!(1)),
+    ///     (".b[1]".into(), &json!(2)),
+    /// ]);
+    ///
+    /// let style = StyleBuilder::new().wildcard_array_keys_in_path().build();
+    /// let map = Iterator::new(&value).use_style(style).collect_map();
+    /// assert_eq!(map["[\"b\"][*]"], &json!(2));
+    /// ```
+    pub fn collect_map(self) -> BTreeMap<String, &'a V> {
+        self.pairs().collect()
+    }
+
+    /// Consumes the iterator, collecting every path and value into a hash map
+    ///
+    /// Duplicate paths are handled the same as `collect_map`, keeping the value yielded last.
+    pub fn collect_hash_map(self) -> HashMap<String, &'a V> {
+        let mut map = HashMap::with_capacity(self.size_hint().0);
+        map.extend(self.pairs());
+        map
+    }
+
+    /// Counts the elements that will be yielded with the current style, so `size_hint` is exact
+    ///
+    /// This walks the whole document once up front, so it should be called after `use_style` and before iterating.
+    /// When limits or strict keys could end iteration early, the count is only reported as an upper bound.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::Iterator;
+    ///
+    /// let value = json!({"a": [1, 2], "b": {"c": null}});
+    /// let mut iter = Iterator::new(&value).with_estimate();
+    ///
+    /// assert_eq!(iter.size_hint(), (3, Some(3)));
+    /// iter.next();
+    /// assert_eq!(iter.size_hint(), (2, Some(2)));
+    /// ```
+    pub fn with_estimate(mut self) -> Self {
+        let mut count = 0;
+        let mut stack: Vec<&V> = self.root.into_iter().collect();
+        while let Some(value) = stack.pop() {
+            if let Some(entries) = value.entries() {
+                stack.extend(entries.map(|(_, val)| val));
+                count += usize::from(!self.style.should_skip_object_parents());
+            } else if let Some(items) = value.items() {
+                stack.extend(items);
+                count += usize::from(!self.style.should_skip_array_parents());
+            } else {
+                count += 1;
+            }
+        }
+        self.remaining = Some(count);
+        self
+    }
+
+    /// Counters describing the work done so far, which keep growing until the iterator is exhausted
+    ///
+    /// Read them after a loop by iterating with `by_ref`, and after `values`, `paths`, or `pairs`
+    /// from the adapter's own `metrics`, since those consume the iterator.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, IterMetrics};
+    ///
+    /// let value = json!({"a": [1, {"b": 2}]});
+    /// let mut iter = Iterator::new(&value);
+    /// let paths: Vec<_> = iter.by_ref().map(|el| el.path).collect();
+    ///
+    /// assert_eq!(paths, vec!["[\"a\"][0]", "[\"a\"][1][\"b\"]"]);
+    /// assert_eq!(iter.metrics(), IterMetrics {
+    ///     elements_yielded: 2,
+    ///     parents_skipped: 3,
+    ///     max_depth: 3,
+    ///     path_bytes_formatted: 16,
+    /// });
+    ///
+    /// let mut values = Iterator::new(&value).values();
+    /// assert_eq!(values.by_ref().count(), 2);
+    /// assert_eq!(values.metrics().elements_yielded, 2);
+    /// ```
+    pub fn metrics(&self) -> IterMetrics {
+        self.metrics
+    }
+
+    /// Limits the length in bytes of formatted paths, including the style's whole-path wrapper
+    ///
+    /// Deeply nested documents otherwise give every element a path as long as the nesting is deep.
+    /// `OverlongPath::Truncate` cuts longer paths short at a character boundary and appends the marker,
+    /// keeping the result within the limit unless the marker itself is longer. `OverlongPath::Error` ends iteration
+    /// at the first longer path instead, leaving the error in `limit_exceeded` or yielding it from `checked`.
+    /// Nothing is checked while paths are disabled.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, LimitExceeded, OverlongPath, PresetStyle};
+    ///
+    /// let value = json!({"a": {"bb": {"ccc": 1}}, "d": 2});
+    /// let paths: Vec<_> = Iterator::new(&value)
+    ///     .use_style(PresetStyle::CommonJs.into())
+    ///     .max_path_len(6, OverlongPath::Truncate("…"))
+    ///     .paths()
+    ///     .collect();
+    ///
+    /// assert_eq!(paths, vec![".a.…", ".d"]);
+    ///
+    /// let mut iter = Iterator::new(&value).use_style(PresetStyle::CommonJs.into()).max_path_len(6, OverlongPath::Error);
+    ///
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.limit_exceeded(), Some(&LimitExceeded::PathLength { len: 9, max: 6 }));
+    /// ```
+    pub fn max_path_len(mut self, max: usize, policy: OverlongPath<'a>) -> Self {
+        self.max_path_len = Some((max, policy));
+        self
+    }
+
+    /// Ends iteration once any of the limits is exceeded, leaving the error in `limit_exceeded` or yielding it
+    /// from `checked`
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, LimitExceeded, Limits};
+    ///
+    /// let value = json!({"a": [1, 2, 3], "b": [[[[1]]]]});
+    /// let limits = Limits { max_elements: Some(2), ..Limits::default() };
+    /// let results: Vec<_> = Iterator::new(&value).with_limits(limits).checked().collect();
+    ///
+    /// assert_eq!(results.len(), 3);
+    /// assert_eq!(results[2], Err(LimitExceeded::Elements { max: 2 }));
+    ///
+    /// let limits = Limits { max_depth: Some(3), ..Limits::default() };
+    /// let mut iter = Iterator::new(&value).with_limits(limits);
+    ///
+    /// assert_eq!(iter.by_ref().count(), 3);
+    /// assert_eq!(iter.limit_exceeded(), Some(&LimitExceeded::Depth { depth: 4, max: 3 }));
+    /// ```
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Checks every object key against `Style::is_ambiguous_key`, so persisted paths are known to parse back
+    /// to the values they came from
+    ///
+    /// Keys are checked as they are walked, including those of parents the style skips, and nothing is checked
+    /// while paths are disabled.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{AmbiguousKeys, Iterator, LimitExceeded, PresetStyle};
+    ///
+    /// let value = json!({"a.b": {"c": 1}, "d": 2});
+    /// let mut iter = Iterator::new(&value).use_style(PresetStyle::CommonJs.into()).strict_keys(AmbiguousKeys::Flag);
+    ///
+    /// assert_eq!(iter.by_ref().count(), 2);
+    /// assert_eq!(iter.ambiguous_paths(), [".a.b"]);
+    ///
+    /// let mut iter = Iterator::new(&value).use_style(PresetStyle::CommonJs.into()).strict_keys(AmbiguousKeys::Error);
+    ///
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.limit_exceeded(), Some(&LimitExceeded::AmbiguousKey { path: ".a.b".into() }));
+    /// ```
+    pub fn strict_keys(mut self, mode: AmbiguousKeys) -> Self {
+        self.strict_keys = Some(mode);
+        self
+    }
+
+    /// Paths of the values under ambiguous keys walked so far, see `strict_keys`
+    pub fn ambiguous_paths(&self) -> &[String] {
+        &self.ambiguous
+    }
+
+    /// Limit that ended iteration early, if any, see `max_path_len`, `with_limits`, and `strict_keys`
+    pub fn limit_exceeded(&self) -> Option<&LimitExceeded> {
+        self.exceeded.as_ref()
+    }
+
+    /// Moves the buffers to the next value that should be yielded, keeping any estimate and metrics up to date
+    pub(crate) fn advance(&mut self) -> Option<&'a V> {
+        let value = self.step();
+        if let (Some(_), Some(err)) = (value, self.check_limits()) {
+            self.stop(err);
+            return None;
+        }
+        if value.is_some() {
+            self.metrics.elements_yielded += 1;
+        }
+        if let (Some(remaining), Some(_)) = (&mut self.remaining, value) {
+            *remaining = remaining.saturating_sub(1);
+        }
+        value
+    }
+
+    /// Ends iteration early, so `next` keeps returning `None` and `size_hint` reports nothing left
+    fn stop(&mut self, err: LimitExceeded) {
+        self.root = None;
+        self.frames.clear();
+        self.exceeded = Some(err);
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = 0;
+        }
+    }
+
+    /// Whether a limit, strict keys, or pruning could end iteration or skip values before the estimate runs out
+    fn may_stop_early(&self) -> bool {
+        self.limits != Limits::default()
+            || matches!(self.max_path_len, Some((_, OverlongPath::Error)))
+            || self.strict_keys == Some(AmbiguousKeys::Error)
+            || self.prune.is_some()
+    }
+
+    /// Limit broken by yielding the value the buffers were just moved to, if any
+    fn check_limits(&self) -> Option<LimitExceeded> {
+        if let Some(max) = self.limits.max_elements {
+            if self.metrics.elements_yielded >= max {
+                return Some(LimitExceeded::Elements { max });
+            }
+        }
+        if let Some((max, OverlongPath::Error)) = self.max_path_len {
+            let len = self.path.len() + self.style.wrapper_len();
+            if self.build_paths && len > max {
+                return Some(LimitExceeded::PathLength { len, max });
+            }
+        }
+        None
+    }
+
+    /// Limit broken by walking to the value the buffers were just moved to, yielded or not, if any
+    fn check_walk_limits(&self) -> Option<LimitExceeded> {
+        if let Some(max) = self.limits.max_depth {
+            if self.segments.len() > max {
+                let depth = self.segments.len();
+                return Some(LimitExceeded::Depth { depth, max });
+            }
+        }
+        if let Some(max) = self.limits.max_total_path_bytes {
+            if self.metrics.path_bytes_formatted > max {
+                return Some(LimitExceeded::TotalPathBytes { max });
+            }
+        }
+        None
+    }
+
+    /// Moves the buffers to the next value that should be yielded, expanding containers as they are reached
+    fn step(&mut self) -> Option<&'a V> {
+        loop {
+            let value = match self.root.take() {
+                Some(root) => root,
+                None => self.next_child()?,
+            };
+            if let Some(globs) = &self.prune {
+                if !globs.matches_prefix(&self.segments) {
+                    continue;
+                }
+            }
+            if let (Some(mode), Some(PathSegment::Key(key))) =
+                (self.strict_keys, self.segments.last())
+            {
+                if self.build_paths && self.style.is_ambiguous_key(key) {
+                    let path = self.formatted_path();
+                    match mode {
+                        AmbiguousKeys::Flag => self.ambiguous.push(path),
+                        AmbiguousKeys::Error => {
+                            self.stop(LimitExceeded::AmbiguousKey { path });
+                            return None;
+                        }
+                    }
+                }
+            }
+            if let Some(err) = self.check_walk_limits() {
+                self.stop(err);
+                return None;
+            }
+
+            let depth = self.segments.len();
+            let (path_len, indices_len) = (self.path.len(), self.indices.len());
+            self.metrics.max_depth = self.metrics.max_depth.max(depth);
+            if let Some(entries) = value.entries() {
+                self.frames.push(Frame::Object {
+                    entries,
+                    path_len,
+                    depth,
+                    indices_len,
+                });
+                if !self.style.should_skip_object_parents() {
+                    return Some(value);
+                }
+                self.metrics.parents_skipped += 1;
+            } else if let Some(items) = value.items() {
+                self.frames.push(Frame::Array {
+                    items: items.enumerate(),
+                    path_len,
+                    depth,
+                    indices_len,
+                });
+                if !self.style.should_skip_array_parents() {
+                    return Some(value);
+                }
+                self.metrics.parents_skipped += 1;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    /// Pulls the next child from the innermost unfinished container, popping finished ones
+    fn next_child(&mut self) -> Option<&'a V> {
+        loop {
+            match self.frames.last_mut()? {
+                Frame::Object {
+                    entries,
+                    path_len,
+                    depth,
+                    indices_len,
+                } => {
+                    if let Some((key, val)) = entries.next() {
+                        self.path.truncate(*path_len);
+                        self.segments.truncate(*depth);
+                        self.indices.truncate(*indices_len);
+                        if self.build_paths {
+                            self.style.push_object_segment(&mut self.path, key);
+                            self.metrics.path_bytes_formatted += self.path.len() - *path_len;
+                        }
+                        self.segments.push(PathSegment::Key(key));
+                        return Some(val);
+                    }
+                }
+                Frame::Array {
+                    items,
+                    path_len,
+                    depth,
+                    indices_len,
+                } => {
+                    if let Some((index, val)) = items.next() {
+                        self.path.truncate(*path_len);
+                        self.segments.truncate(*depth);
+                        self.indices.truncate(*indices_len);
+                        if self.build_paths {
+                            self.style.push_array_segment(&mut self.path, index);
+                            self.metrics.path_bytes_formatted += self.path.len() - *path_len;
+                        }
+                        self.segments.push(PathSegment::Index(index));
+                        if self.build_indices {
+                            self.indices.push(index);
+                        }
+                        return Some(val);
+                    }
+                }
+            }
+            self.frames.pop();
+        }
+    }
+
+    /// Skips any object or array whose path no glob in the set could match anything below
+    pub(crate) fn prune_to(&mut self, set: PathGlobSet) {
+        self.prune = Some(set);
+    }
+
+    /// Takes the base of the json structure out, if iteration has not started yet
+    pub(crate) fn take_root(&mut self) -> Option<&'a V> {
+        self.root.take()
+    }
+
+    /// Iterator over a child of the base of the json structure, with the same options,
+    /// yielding the same elements as this iterator would have for that child
+    pub(crate) fn subtree(&self, segment: PathSegment<'a>, value: &'a V) -> Self {
+        let mut path = String::new();
+        let mut indices = Vec::new();
+        match segment {
+            PathSegment::Key(key) => {
+                if self.build_paths {
+                    self.style.push_object_segment(&mut path, key);
+                }
+            }
+            PathSegment::Index(index) => {
+                if self.build_paths {
+                    self.style.push_array_segment(&mut path, index);
+                }
+                if self.build_indices {
+                    indices.push(index);
+                }
+            }
+        }
+
+        Self {
+            style: self.style.clone(),
+            root: Some(value),
+            frames: Vec::new(),
+            path,
+            indices,
+            segments: vec![segment],
+            build_paths: self.build_paths,
+            build_indices: self.build_indices,
+            remaining: None,
+            metrics: IterMetrics::default(),
+            prune: self.prune.clone(),
+            max_path_len: self.max_path_len,
+            limits: self.limits,
+            strict_keys: self.strict_keys,
+            ambiguous: Vec::new(),
+            exceeded: None,
+        }
+    }
+
+    /// Path segments of the value most recently returned by `advance`
+    pub(crate) fn current_segments(&self) -> &[PathSegment<'a>] {
+        &self.segments
+    }
+
+    /// Array indices of the value most recently returned by `advance`
+    pub(crate) fn current_indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Copies the current buffers out into an `Element`
+    pub(crate) fn element(&self, value: &'a V) -> Element<'a, V> {
+        Element {
+            path: self.formatted_path(),
+            indices: self.indices.clone(),
+            value,
+        }
+    }
+
+    /// Copies the current path buffer out, wrapped in the style's whole-path prefix and suffix
+    /// and truncated as set by `max_path_len`
+    pub(crate) fn formatted_path(&self) -> String {
+        if !self.build_paths {
+            return String::new();
+        }
+        match self.max_path_len {
+            Some((max, OverlongPath::Truncate(marker)))
+                if self.style.wrapper_len() + self.path.len() > max =>
+            {
+                // copy only what is kept, since the whole path can be as long as the document is deep
+                let mut keep = max.saturating_sub(marker.len());
+                let mut path = String::with_capacity(keep + marker.len());
+                for piece in self.style.wrapped_pieces(&self.path) {
+                    let mut end = keep.min(piece.len());
+                    while !piece.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    path.push_str(&piece[..end]);
+                    if end < piece.len() {
+                        break;
+                    }
+                    keep -= end;
+                }
+                path.push_str(marker);
+                path
+            }
+            _ => self.style.wrap_path(&self.path),
+        }
+    }
+
+    /// Path buffer of the value most recently returned by `advance`, when it is exactly the formatted path
+    #[cfg(feature = "bumpalo")]
+    pub(crate) fn unchanged_path(&self) -> Option<&str> {
+        let truncated = matches!(self.max_path_len, Some((max, OverlongPath::Truncate(_))) if self.path.len() > max);
+        match self.style.has_wrapper() || truncated {
+            true => None,
+            false => Some(&self.path),
+        }
+    }
 }
 
-impl<'a> From<&'a Value> for Iter<'a> {
-    fn from(item: &'a Value) -> Iter<'a> {
+impl<'a, V: JsonNode> From<&'a V> for Iter<'a, V> {
+    fn from(item: &'a V) -> Iter<'a, V> {
         Iter::new(item)
     }
 }
 
-impl<'a> Iterator for Iter<'a> {
-    type Item = Element<'a>;
+/// Once every container has been walked the frame stack stays empty, so `next` keeps returning `None`
+impl<'a, V: JsonNode> FusedIterator for Iter<'a, V> {}
+
+impl<'a, V: JsonNode> Iterator for Iter<'a, V> {
+    type Item = Element<'a, V>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        'items: while let Some(el) = self.items.pop_front() {
-            match el.value {
-                Value::Object(obj) => {
-                    for (key, val) in obj.iter().rev() {
-                        self.items.push_front(Element {
-                            path: self.style.object_format(&el.path, key),
-                            indices: el.indices.clone(),
-                            value: val,
-                        });
-                    }
+        self.advance().map(|value| self.element(value))
+    }
 
-                    match self.style.should_skip_object_parents() {
-                        true => continue 'items,
-                        false => return Some(el),
-                    };
-                }
-                Value::Array(arr) => {
-                    for (index, val) in arr.iter().enumerate().rev() {
-                        let mut indices_vec = el.indices.to_vec();
-                        indices_vec.push(index);
-
-                        self.items.push_front(Element {
-                            path: self.style.array_format(&el.path, index),
-                            indices: indices_vec,
-                            value: val,
-                        });
-                    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) if self.may_stop_early() => (0, Some(remaining)),
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (0, None),
+        }
+    }
+}
+
+/// Iterator yielding only the values of a json structure, in document order
+///
+/// Created by `Iterator::values`
+#[derive(Debug)]
+pub struct Values<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+}
+
+impl<'a, V: JsonNode> Values<'a, V> {
+    /// Counters describing the work done so far, see `Iterator::metrics`
+    pub fn metrics(&self) -> IterMetrics {
+        self.iter.metrics()
+    }
+}
+
+impl<'a, V: JsonNode> FusedIterator for Values<'a, V> {}
+
+impl<'a, V: JsonNode> Iterator for Values<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.advance()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator yielding only the formatted paths of a json structure, in document order
+///
+/// Created by `Iterator::paths`
+#[derive(Debug)]
+pub struct Paths<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+}
+
+impl<'a, V: JsonNode> Paths<'a, V> {
+    /// Counters describing the work done so far, see `Iterator::metrics`
+    pub fn metrics(&self) -> IterMetrics {
+        self.iter.metrics()
+    }
+}
+
+impl<'a, V: JsonNode> FusedIterator for Paths<'a, V> {}
+
+impl<'a, V: JsonNode> Iterator for Paths<'a, V> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.advance()?;
+        Some(self.iter.formatted_path())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator yielding the formatted path and value of each element as a tuple, in document order
+///
+/// Created by `Iterator::pairs`
+#[derive(Debug)]
+pub struct Pairs<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+}
+
+impl<'a, V: JsonNode> Pairs<'a, V> {
+    /// Counters describing the work done so far, see `Iterator::metrics`
+    pub fn metrics(&self) -> IterMetrics {
+        self.iter.metrics()
+    }
+}
+
+impl<'a, V: JsonNode> FusedIterator for Pairs<'a, V> {}
+
+impl<'a, V: JsonNode> Iterator for Pairs<'a, V> {
+    type Item = (String, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.iter.advance()?;
+        Some((self.iter.formatted_path(), value))
+    }
 
-                    match self.style.should_skip_array_parents() {
-                        true => continue 'items,
-                        false => return Some(el),
-                    };
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Walker yielding each value along with its array indices, without building paths
+///
+/// Created by `Iterator::indexed`
+#[derive(Debug)]
+pub struct Indexed<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+}
+
+impl<'a, V: JsonNode> Indexed<'a, V> {
+    /// Advances to the next value, returning it along with its array indices
+    pub fn next_indexed(&mut self) -> Option<(&[usize], &'a V)> {
+        let value = self.iter.advance()?;
+        Some((self.iter.current_indices(), value))
+    }
+}
+
+/// Walker yielding each value along with the structural segments of its path, without allocating per value
+///
+/// Created by `Iterator::segments`
+#[derive(Debug)]
+pub struct Segments<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+}
+
+impl<'a, V: JsonNode> Segments<'a, V> {
+    /// Advances to the next value, returning it along with the segments of its path
+    pub fn next_segments(&mut self) -> Option<(&[PathSegment<'a>], &'a V)> {
+        let value = self.iter.advance()?;
+        Some((self.iter.current_segments(), value))
+    }
+}
+
+/// Iterator adapter yielding only the elements whose path matches a `PathGlob`
+///
+/// Created by `Iterator::find_all_paths`
+pub struct PathMatches<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+    glob: PathGlob,
+}
+
+impl<'a, V: JsonNode> fmt::Debug for PathMatches<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PathMatches")
+            .field("iter", &self.iter)
+            .field("glob", &self.glob)
+            .finish()
+    }
+}
+
+impl<'a, V: JsonNode> Iterator for PathMatches<'a, V> {
+    type Item = Element<'a, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find_path(&self.glob)
+    }
+}
+
+/// Iterator adapter yielding the elements whose path matches a `PathGlob`, with the segments bound to its wildcards
+///
+/// Created by `Iterator::find_all_captures`
+pub struct CaptureMatches<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+    glob: PathGlob,
+}
+
+impl<'a, V: JsonNode> fmt::Debug for CaptureMatches<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CaptureMatches")
+            .field("iter", &self.iter)
+            .field("glob", &self.glob)
+            .finish()
+    }
+}
+
+impl<'a, V: JsonNode> Iterator for CaptureMatches<'a, V> {
+    type Item = (Element<'a, V>, Captures<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(value) = self.iter.advance() {
+            if let Some(captures) = self.glob.captures(self.iter.current_segments()) {
+                if self.glob.matches_value(value) {
+                    return Some((self.iter.element(value), captures));
                 }
-                _ => return Some(el),
+            }
+        }
+        None
+    }
+}
+
+/// Iterator adapter yielding the elements whose path matches any glob in a `PathGlobSet`, with the positions matched
+///
+/// Created by `Iterator::find_all_matches`
+pub struct SetMatches<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+    set: PathGlobSet,
+}
+
+impl<'a, V: JsonNode> fmt::Debug for SetMatches<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetMatches")
+            .field("iter", &self.iter)
+            .field("set", &self.set)
+            .finish()
+    }
+}
+
+impl<'a, V: JsonNode> Iterator for SetMatches<'a, V> {
+    type Item = (Element<'a, V>, Vec<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(value) = self.iter.advance() {
+            let matched = self
+                .set
+                .matching_element(self.iter.current_segments(), value);
+            if !matched.is_empty() {
+                return Some((self.iter.element(value), matched));
             }
         }
         None
@@ -128,6 +1170,148 @@ mod tests {
     use crate::style::StyleBuilder;
     use serde_json::json;
 
+    #[test]
+    fn huge_batches_reserve_only_what_remains() {
+        let value = json!([1, 2, 3]);
+        let mut iter = Iter::new(&value);
+        let mut batch = Vec::new();
+
+        assert_eq!(iter.next_batch(&mut batch, usize::MAX), 3);
+        assert!(batch.capacity() < 64);
+    }
+
+    #[test]
+    fn buffers_unwind_after_deep_siblings() {
+        let value = json!({"a": {"b": [{"c": 1}]}, "d": [2]});
+        let items: Vec<_> = Iter::new(&value)
+            .use_style(PresetStyle::CommonJs.into())
+            .map(|el| (el.path, el.indices))
+            .collect();
+
+        assert_eq!(
+            items,
+            vec![(".a.b[0].c".into(), vec![0]), (".d[0]".into(), vec![0])]
+        );
+    }
+
+    #[test]
+    fn expands_children_lazily() {
+        let value = Value::Array(vec![json!(1); 100_000]);
+        let mut iter = Iter::new(&value);
+        iter.nth(4);
+
+        assert_eq!(iter.frames.len(), 1);
+        assert_eq!(iter.path, "[4]");
+    }
+
+    #[test]
+    fn find_path_ignores_disabled_paths() {
+        let value = json!({"a": [{"b": 1}, {"b": 2}]});
+        let glob: PathGlob = "a[1].b".parse().unwrap();
+        let mut iter = Iter::new(&value).paths_disabled().indices_disabled();
+
+        assert_eq!(
+            iter.find_path(&glob),
+            Some(Element {
+                path: String::new(),
+                indices: Vec::new(),
+                value: &json!(2),
+            })
+        );
+    }
+
+    #[test]
+    fn find_all_paths_skips_subtrees_that_cannot_match() {
+        let value = json!({"a": {"b": {"c": [1]}}, "d": [{"e": 2}, {"e": 3}]});
+        let mut matches = Iter::new(&value).find_all_paths("d[*].e".parse().unwrap());
+        let values: Vec<_> = matches.by_ref().map(|el| el.value).collect();
+
+        assert_eq!(values, vec![&json!(2), &json!(3)]);
+        assert_eq!(matches.iter.metrics().max_depth, 3);
+        assert_eq!(matches.iter.metrics().parents_skipped, 4);
+    }
+
+    #[test]
+    fn find_all_matches_skips_subtrees_no_glob_can_match() {
+        let value = json!({"a": {"b": [1, 2]}, "c": {"d": 3}, "e": 4});
+        let set = PathGlobSet::new(["c.d", "e"]).unwrap();
+        let mut matches = Iter::new(&value).find_all_matches(set);
+        let found: Vec<_> = matches
+            .by_ref()
+            .map(|(el, matched)| (el.value, matched))
+            .collect();
+
+        assert_eq!(found, vec![(&json!(3), vec![0]), (&json!(4), vec![1])]);
+        assert_eq!(matches.iter.metrics().parents_skipped, 2);
+    }
+
+    #[test]
+    fn limits_bound_documents_of_only_containers() {
+        let mut value = json!({});
+        for _ in 0..50 {
+            value = json!({ "a": value });
+        }
+        let limits = Limits {
+            max_depth: Some(3),
+            ..Limits::default()
+        };
+        let mut iter = Iter::new(&value).with_limits(limits);
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(
+            iter.limit_exceeded(),
+            Some(&LimitExceeded::Depth { depth: 4, max: 3 })
+        );
+        assert_eq!(iter.metrics().max_depth, 3);
+
+        let limits = Limits {
+            max_total_path_bytes: Some(20),
+            ..Limits::default()
+        };
+        let mut iter = Iter::new(&value).with_limits(limits);
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(
+            iter.limit_exceeded(),
+            Some(&LimitExceeded::TotalPathBytes { max: 20 })
+        );
+    }
+
+    #[test]
+    fn estimate_is_an_upper_bound_under_limits() {
+        let value = json!([1, 2, 3, 4, 5]);
+        let limits = Limits {
+            max_elements: Some(2),
+            ..Limits::default()
+        };
+        let mut iter = Iter::new(&value).with_limits(limits).with_estimate();
+
+        assert_eq!(iter.size_hint(), (0, Some(5)));
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn estimate_respects_style_and_preallocates() {
+        let value = json!({"a": [1, {"b": 2}], "c": []});
+        let style = StyleBuilder::new().include_array_parents().build();
+        let items: Vec<_> = Iter::new(&value).use_style(style).with_estimate().collect();
+
+        assert_eq!(items.len(), 4);
+        assert_eq!(items.capacity(), 4);
+    }
+
+    #[test]
+    fn collect_hash_map_keeps_last_duplicate() {
+        let value = json!({"a": [1, 2], "b": [3]});
+        let style = StyleBuilder::new().hide_object_keys_in_path().build();
+        let map = Iter::new(&value).use_style(style).collect_hash_map();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["[\"\"][0]"], &json!(3));
+        assert_eq!(map["[\"\"][1]"], &json!(2));
+    }
+
     #[test]
     fn null_to_iter() {
         let value = json!(null);
@@ -230,8 +1414,8 @@ mod tests {
     fn can_skip_parents() {
         let value = json!({
             "first": [1, 2, 3],
-            "middle": true,
             "last": ["a", "b", "c"],
+            "middle": true,
         });
         let style = StyleBuilder::new()
             .skip_object_parents()
@@ -290,8 +1474,8 @@ mod tests {
     fn complex_format_on_iter() {
         let value = json!({
             "first": [1, 2, 3],
-            "middle": true,
             "last": ["a", "b", "c"],
+            "middle": true,
         });
         let style = StyleBuilder::new()
             .include_object_parents()
@@ -325,7 +1509,7 @@ mod tests {
             }
         );
 
-        // interesting note that "middle" is sorted alphabetically to the last object entry by json!()
+        // entries are written in sorted order, so they come out the same with or without `preserve_order`
         assert_eq!(
             items[9],
             Element {
@@ -340,8 +1524,8 @@ mod tests {
     fn in_a_for_loop() {
         let value = json!({
             "first": [1, 2, 3],
-            "middle": true,
             "last": ["a", "b", "c"],
+            "middle": true,
         });
 
         let mut collection = Vec::new();
@@ -379,7 +1563,7 @@ mod tests {
             }
         );
 
-        // interesting note that "middle" is sorted alphabetically to the last object entry by json!()
+        // entries are written in sorted order, so they come out the same with or without `preserve_order`
         assert_eq!(
             collection[9],
             Element {
@@ -389,4 +1573,55 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn truncated_paths_stop_at_a_char_boundary() {
+        let value = json!({"ééé": 1});
+        let style = PresetStyle::CommonJs.into();
+        let paths: Vec<_> = Iter::new(&value)
+            .use_style(style)
+            .max_path_len(5, OverlongPath::Truncate("~"))
+            .paths()
+            .collect();
+
+        assert_eq!(paths, vec![".é~"]);
+    }
+
+    #[test]
+    fn truncated_paths_copy_only_what_they_keep() {
+        let mut value = json!(1);
+        for _ in 0..500 {
+            value = json!({ "ab": value });
+        }
+        let style = StyleBuilder::new()
+            .object_key_prefix(".")
+            .object_key_suffix("")
+            .path_prefix("$")
+            .path_suffix("!")
+            .build();
+        let paths: Vec<_> = Iter::new(&value)
+            .use_style(style)
+            .max_path_len(8, OverlongPath::Truncate("~"))
+            .paths()
+            .collect();
+
+        assert_eq!(paths, vec!["$.ab.ab~"]);
+        assert_eq!(paths[0].capacity(), 8);
+    }
+
+    #[test]
+    fn total_path_bytes_limit_counts_skipped_parents() {
+        let value = json!({"abc": {"def": 1}});
+        let limits = Limits {
+            max_total_path_bytes: Some(13),
+            ..Limits::default()
+        };
+        let mut iter = Iter::new(&value).with_limits(limits);
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(
+            iter.limit_exceeded(),
+            Some(&LimitExceeded::TotalPathBytes { max: 13 })
+        );
+    }
 }
@@ -0,0 +1,127 @@
+use crate::iter::{Element, Iter};
+use crate::node::{JsonNode, NodeKind};
+use ijson::{IValue, ValueType};
+
+/// Single element struct referencing an `ijson::IValue`
+pub type IElement<'a> = Element<'a, IValue>;
+
+/// Iteration struct traversing an `ijson::IValue` directly, without converting to `serde_json::Value`
+///
+/// Example:
+/// ```rust
+/// use ijson::ijson;
+/// use json_keypath_iter::{IElement, IValueIter};
+///
+/// let value = ijson!({"a": [1, 2]});
+/// let items: Vec<_> = IValueIter::new(&value).collect();
+///
+/// assert_eq!(items[0], IElement { path: "[\"a\"][0]".into(), indices: vec![0], value: &ijson!(1), });
+/// assert_eq!(items[1], IElement { path: "[\"a\"][1]".into(), indices: vec![1], value: &ijson!(2), });
+/// ```
+pub type IValueIter<'a> = Iter<'a, IValue>;
+
+impl<'a> Iter<'a> {
+    /// Create a json keypath iterator over an `ijson::IValue`
+    pub fn from_ivalue(json: &'a IValue) -> IValueIter<'a> {
+        Iter::new(json)
+    }
+}
+
+impl JsonNode for IValue {
+    // ijson object iteration is not double ended, so entries are gathered up front
+    type Entries<'a> = std::vec::IntoIter<(&'a str, &'a IValue)>;
+    type Items<'a> = std::slice::Iter<'a, IValue>;
+
+    fn kind(&self) -> NodeKind {
+        match self.type_() {
+            ValueType::Null => NodeKind::Null,
+            ValueType::Bool => NodeKind::Bool,
+            ValueType::Number => NodeKind::Number,
+            ValueType::String => NodeKind::String,
+            ValueType::Array => NodeKind::Array,
+            ValueType::Object => NodeKind::Object,
+        }
+    }
+
+    fn entries(&self) -> Option<Self::Entries<'_>> {
+        self.as_object().map(|obj| {
+            obj.iter()
+                .map(|(key, val)| (key.as_str(), val))
+                .collect::<Vec<_>>()
+                .into_iter()
+        })
+    }
+
+    fn items(&self) -> Option<Self::Items<'_>> {
+        self.as_array().map(|arr| arr.iter())
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        self.to_bool()
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        self.to_f64()
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        self.to_i64()
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        self.to_u64()
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        self.as_string().map(|s| s.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::StyleBuilder;
+    use ijson::ijson;
+    use serde_json::json;
+
+    #[test]
+    fn matches_serde_json_iteration() {
+        let value = json!({
+            "first": [1, 2, 3],
+            "middle": {"inner": true},
+            "last": ["a", "b", "c"],
+        });
+        let ivalue: IValue = serde_json::from_value(value.clone()).unwrap();
+        let style = || StyleBuilder::new().include_array_parents().build();
+
+        let expected: Vec<_> = Iter::new(&value)
+            .use_style(style())
+            .map(|el| (el.path, el.indices))
+            .collect();
+        let mut actual: Vec<_> = IValueIter::new(&ivalue)
+            .use_style(style())
+            .map(|el| (el.path, el.indices))
+            .collect();
+
+        // ijson keeps insertion order where serde_json sorts keys
+        actual.sort();
+        let mut sorted = expected.clone();
+        sorted.sort();
+        assert_eq!(actual, sorted);
+    }
+
+    #[test]
+    fn scalars_yield_themselves() {
+        let value = ijson!("hi");
+        let items: Vec<_> = IValueIter::new(&value).collect();
+
+        assert_eq!(
+            items,
+            vec![IElement {
+                path: String::from(""),
+                indices: Vec::new(),
+                value: &ijson!("hi"),
+            }]
+        );
+    }
+}
@@ -1,5 +1,180 @@
 mod style;
-pub use style::{PresetStyle, Style, StyleBuilder};
+pub use style::{
+    styles, KeyEscape, PresetStyle, Style, StyleBuilder, StyleError, UnknownPresetError,
+};
+
+mod path;
+pub use path::{PathParseError, PathSegment};
+
+mod glob;
+pub use glob::{count, exists, Captures, GlobError, Path, PathGlob, PathGlobSet, ValuePredicate};
+
+mod query;
+pub use query::{query, query_with, JsonPath, JsonPathError};
+
+mod node;
+pub use node::{JsonNode, NodeKind};
 
 mod iter;
-pub use iter::{Element, Iter as Iterator};
+pub use iter::{
+    CaptureMatches, Element, Indexed, Iter, Iter as Iterator, IterMetrics, Pairs, PathMatches,
+    Paths, Segments, SetMatches, Values,
+};
+
+mod limits;
+pub use limits::{AmbiguousKeys, Checked, LimitExceeded, Limits, OverlongPath};
+
+mod chunk;
+pub use chunk::TopLevelChunks;
+
+mod dedup;
+pub use dedup::DedupPairs;
+
+mod column;
+pub use column::{collect_column, ColumnError};
+
+mod table;
+pub use table::{
+    reshape, reshape_with, to_table, to_table_with, ArrayPolicy, MissingPolicy, Shape, Table,
+    TableError, TableOptions,
+};
+
+mod owned;
+pub use owned::{IntoIter, OwnedElement};
+
+#[cfg(feature = "yaml")]
+mod yaml;
+#[cfg(feature = "yaml")]
+pub use yaml::{YamlError, YamlKeyPolicy};
+
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "cbor")]
+pub use cbor::{BytesEncoding, CborOptions, TagPolicy};
+
+#[cfg(feature = "ijson")]
+mod ivalue;
+#[cfg(feature = "ijson")]
+pub use ivalue::{IElement, IValueIter};
+
+#[cfg(feature = "raw_value")]
+mod raw;
+#[cfg(feature = "raw_value")]
+pub use raw::{RawElement, RawIter};
+
+mod stream;
+pub use stream::{DocumentElement, DuplicateKeyPolicy, StreamIter};
+
+#[cfg(feature = "mmap")]
+mod file;
+#[cfg(feature = "mmap")]
+pub use file::{FileError, MMAP_THRESHOLD};
+
+mod order;
+pub use order::{key_order, KeyOrder};
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
+mod ext;
+pub use ext::{KeypathExt, Leaves};
+
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "arrow")]
+pub use arrow::{to_record_batches, ArrowConvertError, ArrowOptions};
+
+mod ser;
+pub use ser::{to_flat_pairs, FlatCompound, FlatSerializer};
+
+mod de;
+pub use de::{from_flat_pairs, unflatten, FlatDeserializer, UnflattenError};
+
+mod flat;
+pub use flat::to_flat_json;
+
+mod env;
+pub use env::{to_env_lines, EnvError};
+
+#[cfg(feature = "tracing")]
+mod trace;
+#[cfg(feature = "tracing")]
+pub use trace::{record_json, LeafEvents, RecordLimits, RecordTarget};
+
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "csv")]
+pub use crate::csv::{write_csv, CsvError, CsvLayout, CsvOptions};
+
+mod sql;
+pub use sql::{postgres_conditions, postgres_path, SqlFragment};
+
+mod tree;
+pub use tree::{render_tree, TreeOptions};
+
+#[cfg(feature = "prometheus")]
+mod prometheus;
+#[cfg(feature = "prometheus")]
+pub use prometheus::{to_prometheus, MetricNameCollision};
+
+mod secret;
+pub use secret::{redact, SecretKeys, SkipSecrets, DEFAULT_SECRET_KEYS, REDACTED};
+
+mod compare;
+pub use compare::{compare_paths, PathsDiff, TypeMismatch};
+
+mod report;
+pub use report::{render_table, report, report_with, RenderOptions, ReportOptions, TableFormat};
+
+mod map;
+pub use map::{map_values, map_values_with, truncate_depth};
+
+mod mutate;
+pub use mutate::{
+    copy_path, for_each_mut, for_each_mut_with, move_path, rename_keys, MovePathError,
+};
+
+mod defaults;
+pub use defaults::{fill_defaults, fill_defaults_with};
+
+mod coerce;
+pub use coerce::{coerce, coerce_with, Coercion, CoercionFailure};
+
+mod nulls;
+pub use nulls::{strip_nulls, strip_nulls_with};
+
+mod sort;
+pub use sort::{dedup_arrays, dedup_arrays_with, sort_arrays, MissingKeys, SortOrder};
+
+mod merge;
+pub use merge::{merge, merge_with, Resolution};
+
+mod normalize;
+pub use normalize::{normalize, normalize_with, NormalizeOptions};
+
+mod patch;
+pub use patch::{PatchError, PatchSet};
+
+mod interpolate;
+pub use interpolate::{interpolate, interpolate_with, InterpolateError};
+
+mod diff;
+pub use diff::{
+    diff, diff3, diff3_with, diff_delta, diff_delta_with, diff_with, similarity, similarity_with,
+    Change, Diff3, DiffOptions, MergeConflict, MergeDecision, Side,
+};
+
+mod shared;
+pub use shared::{SharedElement, SharedIter};
+
+mod arc;
+pub use arc::{ArcElement, ArcIter};
+
+#[cfg(feature = "bumpalo")]
+mod bump;
+#[cfg(feature = "bumpalo")]
+pub use bump::{BumpElement, BumpIter};
+
+/// Helpers for checking that a custom `Style` round-trips, generating values with `arbitrary`
+#[cfg(feature = "testing")]
+pub mod testing;
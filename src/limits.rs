@@ -0,0 +1,152 @@
+use crate::iter::{Element, Iter};
+use crate::node::JsonNode;
+use serde_json::Value;
+use std::fmt;
+use std::iter::FusedIterator;
+
+/// What an `Iterator` does with a path longer than `Iterator::max_path_len` allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlongPath<'a> {
+    /// Cuts the path short and appends the marker
+    Truncate(&'a str),
+    /// Ends iteration with `LimitExceeded::PathLength`
+    Error,
+}
+
+/// What an `Iterator` does with an object key the style cannot format unambiguously, see `Style::is_ambiguous_key`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmbiguousKeys {
+    /// Keeps iterating, collecting the path of every value under such a key in `Iterator::ambiguous_paths`
+    Flag,
+    /// Ends iteration with `LimitExceeded::AmbiguousKey`
+    Error,
+}
+
+/// Bounds on the work an `Iterator` does, for walking untrusted documents, see `Iterator::with_limits`
+///
+/// Every limit is off by default. Depth and path bytes are checked for every value walked, including objects and
+/// arrays the style skips, so a document holding nothing but nested containers is still bounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Most elements yielded
+    pub max_elements: Option<usize>,
+    /// Most path segments of any value walked, where the base of the json structure is `0`
+    pub max_depth: Option<usize>,
+    /// Most bytes of key and index segments formatted into paths in total, as counted by `IterMetrics::path_bytes_formatted`
+    pub max_total_path_bytes: Option<usize>,
+}
+
+/// Limit that ended iteration early
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitExceeded {
+    /// A formatted path was longer than `Iterator::max_path_len` allows
+    PathLength {
+        /// Length in bytes of the formatted path
+        len: usize,
+        /// The limit
+        max: usize,
+    },
+    /// More elements were about to be yielded than `Limits::max_elements` allows
+    Elements {
+        /// The limit
+        max: usize,
+    },
+    /// A value was nested deeper than `Limits::max_depth` allows
+    Depth {
+        /// Number of path segments of the value
+        depth: usize,
+        /// The limit
+        max: usize,
+    },
+    /// More bytes were formatted into paths than `Limits::max_total_path_bytes` allows
+    TotalPathBytes {
+        /// The limit
+        max: usize,
+    },
+    /// An object key could not be formatted unambiguously, see `Iterator::strict_keys`
+    AmbiguousKey {
+        /// Formatted path of the value under the key
+        path: String,
+    },
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitExceeded::PathLength { len, max } => write!(
+                f,
+                "formatted path of {} bytes is longer than the limit of {}",
+                len, max,
+            ),
+            LimitExceeded::Elements { max } => write!(f, "more than {} elements", max),
+            LimitExceeded::Depth { depth, max } => write!(
+                f,
+                "value nested {} levels deep is deeper than the limit of {}",
+                depth, max,
+            ),
+            LimitExceeded::TotalPathBytes { max } => {
+                write!(f, "more than {} bytes formatted into paths", max)
+            }
+            LimitExceeded::AmbiguousKey { path } => {
+                write!(
+                    f,
+                    "path {:?} has a key containing the style's delimiters",
+                    path
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl<'a, V: JsonNode> Iter<'a, V> {
+    /// Consumes the iterator, yielding `Ok` elements until a limit is exceeded and then that limit as an `Err`
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, LimitExceeded, OverlongPath};
+    ///
+    /// let value = json!({"a": 1, "bbbbbb": 2, "c": 3});
+    /// let results: Vec<_> = Iterator::new(&value).max_path_len(8, OverlongPath::Error).checked().collect();
+    ///
+    /// assert_eq!(results.len(), 2);
+    /// assert_eq!(results[0].as_ref().unwrap().path, "[\"a\"]");
+    /// assert_eq!(results[1], Err(LimitExceeded::PathLength { len: 10, max: 8 }));
+    /// ```
+    pub fn checked(self) -> Checked<'a, V> {
+        Checked {
+            iter: self,
+            done: false,
+        }
+    }
+}
+
+/// Iterator yielding elements as `Ok`, followed by an `Err` if a limit ends iteration early
+///
+/// Created by `Iterator::checked`
+#[derive(Debug)]
+pub struct Checked<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+    done: bool,
+}
+
+impl<'a, V: JsonNode> FusedIterator for Checked<'a, V> {}
+
+impl<'a, V: JsonNode> Iterator for Checked<'a, V> {
+    type Item = Result<Element<'a, V>, LimitExceeded>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.iter.advance() {
+            Some(value) => Some(Ok(self.iter.element(value))),
+            None => {
+                self.done = true;
+                self.iter.limit_exceeded().cloned().map(Err)
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::iter::Iter;
+use crate::path::get_mut;
+use crate::style::{PresetStyle, Style, StyleBuilder};
+use serde_json::Value;
+
+/// Builds a copy of a document where each leaf can be replaced, formatting paths with the default style
+///
+/// The callback sees the path and value of every leaf, meaning every value that is not an object or array,
+/// and returns `Some` with a replacement or `None` to keep the leaf as it is.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::map_values;
+///
+/// let value = json!({"price": 10, "tags": ["a", "b"], "meta": {}});
+/// let mapped = map_values(&value, |path, v| match v.as_i64() {
+///     Some(n) => Some(json!(n * 2)),
+///     None if path.ends_with("[1]") => Some(json!("z")),
+///     None => None,
+/// });
+///
+/// assert_eq!(mapped, json!({"price": 20, "tags": ["a", "z"], "meta": {}}));
+/// ```
+pub fn map_values<F>(json: &Value, f: F) -> Value
+where
+    F: FnMut(&str, &Value) -> Option<Value>,
+{
+    map_values_with(json, &PresetStyle::SquareBrackets.into(), f)
+}
+
+/// Builds a copy of a document where each leaf can be replaced, formatting paths with the given style
+///
+/// Parents are never passed to the callback, whatever the style says about them.
+pub fn map_values_with<F>(json: &Value, style: &Style, mut f: F) -> Value
+where
+    F: FnMut(&str, &Value) -> Option<Value>,
+{
+    let mut mapped = json.clone();
+    let mut iter = Iter::new(json).use_style(StyleBuilder::from(style).skip_parents().build());
+
+    while let Some(value) = iter.advance() {
+        if value.is_object() || value.is_array() {
+            continue;
+        }
+        if let Some(replacement) = f(&iter.element(value).path, value) {
+            if let Some(slot) = get_mut(&mut mapped, iter.current_segments()) {
+                *slot = replacement;
+            }
+        }
+    }
+    mapped
+}
+
+/// Builds a copy of a document where every non-empty object or array nested `max_depth` levels down is replaced
+/// by the placeholder, so deep payloads can be logged safely
+///
+/// The root is at depth zero, so a `max_depth` of zero replaces the whole document unless it is a leaf.
+/// Leaves and empty containers are always copied as they are.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::truncate_depth;
+///
+/// let value = json!({"a": {"b": {"c": 1}, "d": 2, "e": []}, "f": [[1], 3]});
+///
+/// assert_eq!(
+///     truncate_depth(&value, 2, &json!("…")),
+///     json!({"a": {"b": "…", "d": 2, "e": []}, "f": ["…", 3]}),
+/// );
+/// assert_eq!(truncate_depth(&value, 0, &json!({"_truncated": true})), json!({"_truncated": true}));
+/// ```
+pub fn truncate_depth(json: &Value, max_depth: usize, placeholder: &Value) -> Value {
+    match json {
+        Value::Object(obj) if !obj.is_empty() && max_depth == 0 => placeholder.clone(),
+        Value::Array(arr) if !arr.is_empty() && max_depth == 0 => placeholder.clone(),
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(key, val)| (key.clone(), truncate_depth(val, max_depth - 1, placeholder)))
+                .collect(),
+        ),
+        Value::Array(arr) => Value::Array(
+            arr.iter()
+                .map(|val| truncate_depth(val, max_depth - 1, placeholder))
+                .collect(),
+        ),
+        _ => json.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn replaces_a_root_leaf_and_skips_empty_containers() {
+        let style = PresetStyle::CommonJs.builder().include_parents().build();
+        let mut seen = Vec::new();
+        let mapped = map_values_with(&json!({"a": [], "b": {}}), &style, |path, _| {
+            seen.push(path.to_string());
+            None
+        });
+
+        assert!(seen.is_empty());
+        assert_eq!(mapped, json!({"a": [], "b": {}}));
+        assert_eq!(
+            map_values(&json!(1), |path, _| Some(json!(path))),
+            json!("")
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::path::remove_key;
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+
+/// How `merge` settles a path where both documents hold different values
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// Keep the value already in the left document
+    Left,
+    /// Take a copy of the value from the right document
+    Right,
+    /// Store this value instead of either side
+    Replace(Value),
+    /// Remove the entry from the left document
+    Remove,
+}
+
+/// Merges the right document into the left one, formatting the paths passed to the callback with the default style
+///
+/// Objects on both sides are merged key by key, and keys only on the right are copied over. Anywhere else that
+/// both sides hold values which are not equal, including two arrays, the callback is given the path and both values
+/// and decides what the left document keeps. `Resolution::Remove` at the root leaves `null` behind.
+///
+/// Example:
+/// ```rust
+/// use serde_json::{json, Value};
+/// use json_keypath_iter::{merge, Resolution};
+///
+/// let mut config = json!({"log": {"file": "a.log", "level": "info"}, "name": "app", "ports": [80]});
+/// let overrides = json!({"log": {"color": true, "level": "debug"}, "ports": [443]});
+/// let mut conflicts = Vec::new();
+///
+/// merge(&mut config, &overrides, |path, left, right| {
+///     conflicts.push(path.to_string());
+///     match (left, right) {
+///         (Value::Array(l), Value::Array(r)) => Resolution::Replace(l.iter().chain(r).cloned().collect()),
+///         _ => Resolution::Right,
+///     }
+/// });
+///
+/// assert_eq!(config, json!({"log": {"color": true, "file": "a.log", "level": "debug"}, "name": "app", "ports": [80, 443]}));
+/// assert_eq!(conflicts, vec!["[\"log\"][\"level\"]", "[\"ports\"]"]);
+/// ```
+pub fn merge<F>(left: &mut Value, right: &Value, f: F)
+where
+    F: FnMut(&str, &Value, &Value) -> Resolution,
+{
+    merge_with(left, right, &PresetStyle::SquareBrackets.into(), f)
+}
+
+/// Merges the right document into the left one, formatting the paths passed to the callback with the given style,
+/// see `merge`
+pub fn merge_with<F>(left: &mut Value, right: &Value, style: &Style, mut f: F)
+where
+    F: FnMut(&str, &Value, &Value) -> Resolution,
+{
+    if let Some(resolution) = merge_node(left, right, style, &mut String::new(), &mut f) {
+        *left = match resolution {
+            Resolution::Remove => Value::Null,
+            resolution => resolve(resolution, left, right),
+        };
+    }
+}
+
+/// Merges the nodes, returning the resolution to apply when they conflict
+fn merge_node<F>(
+    left: &mut Value,
+    right: &Value,
+    style: &Style,
+    path: &mut String,
+    f: &mut F,
+) -> Option<Resolution>
+where
+    F: FnMut(&str, &Value, &Value) -> Resolution,
+{
+    let (obj, other) = match (left, right) {
+        (Value::Object(obj), Value::Object(other)) => (obj, other),
+        (left, right) if left == right => return None,
+        (left, right) => return Some(f(&style.wrap_path(path), left, right)),
+    };
+
+    let path_len = path.len();
+    for (key, val) in other {
+        style.push_object_segment(path, key);
+        match obj.get_mut(key) {
+            Some(existing) => match merge_node(existing, val, style, path, f) {
+                Some(Resolution::Remove) => {
+                    remove_key(obj, key);
+                }
+                Some(resolution) => *existing = resolve(resolution, existing, val),
+                None => {}
+            },
+            None => {
+                obj.insert(key.clone(), val.clone());
+            }
+        }
+        path.truncate(path_len);
+    }
+    None
+}
+
+fn resolve(resolution: Resolution, left: &mut Value, right: &Value) -> Value {
+    match resolution {
+        Resolution::Left => left.take(),
+        Resolution::Right => right.clone(),
+        Resolution::Replace(value) => value,
+        Resolution::Remove => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn equal_values_skip_the_callback_and_removals_apply() {
+        let mut value = json!({"same": [1], "drop": 1, "keep": {"a": 1}});
+        merge(
+            &mut value,
+            &json!({"same": [1], "drop": 2, "keep": 3}),
+            |path, _, _| {
+                if path.contains("drop") {
+                    Resolution::Remove
+                } else {
+                    Resolution::Left
+                }
+            },
+        );
+
+        assert_eq!(value, json!({"same": [1], "keep": {"a": 1}}));
+    }
+}
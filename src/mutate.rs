@@ -0,0 +1,401 @@
+use crate::de::{insert, insertable};
+use crate::glob::PathGlob;
+use crate::path::{get_mut, remove_key, PathParseError, PathSegment};
+use crate::style::{PresetStyle, Style};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Calls the callback with the path and a mutable reference of every value matching the glob,
+/// formatting paths with the default style
+///
+/// Matching objects and arrays are passed to the callback before anything nested within them,
+/// and whatever the callback leaves behind is what gets walked next. Subtrees the glob cannot match are skipped.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{for_each_mut, PathGlob};
+///
+/// let mut value = json!({"users": [{"email": "Ada@X.org"}, {"email": "BOB@x.org"}], "total": 1.239});
+///
+/// for_each_mut(&mut value, &"users[*].email".parse().unwrap(), |_, v| {
+///     *v = v.as_str().map(str::to_lowercase).into();
+/// });
+/// for_each_mut(&mut value, &"total".parse().unwrap(), |_, v| {
+///     *v = v.as_f64().map(|n| (n * 100.0).round() / 100.0).into();
+/// });
+///
+/// assert_eq!(value, json!({"users": [{"email": "ada@x.org"}, {"email": "bob@x.org"}], "total": 1.24}));
+/// ```
+pub fn for_each_mut<F>(json: &mut Value, glob: &PathGlob, f: F)
+where
+    F: FnMut(&str, &mut Value),
+{
+    for_each_mut_with(json, glob, &PresetStyle::SquareBrackets.into(), f)
+}
+
+/// Calls the callback with the path and a mutable reference of every value matching the glob,
+/// formatting paths with the given style, see `for_each_mut`
+///
+/// Parents are passed to the callback when they match, whatever the style says about them.
+pub fn for_each_mut_with<F>(json: &mut Value, glob: &PathGlob, style: &Style, mut f: F)
+where
+    F: FnMut(&str, &mut Value),
+{
+    for_each_mut_unwrapped(json, glob, style, |path, value| {
+        f(&style.wrap_path(path), value)
+    });
+}
+
+/// Calls the callback like `for_each_mut_with`, passing paths before the style's wrapper is added so segments can be pushed onto them
+pub(crate) fn for_each_mut_unwrapped<F>(json: &mut Value, glob: &PathGlob, style: &Style, mut f: F)
+where
+    F: FnMut(&mut String, &mut Value),
+{
+    let mut walk = MutWalk {
+        glob,
+        style,
+        path: String::new(),
+        f: &mut f,
+    };
+    walk.visit(json, &mut Vec::new());
+}
+
+struct MutWalk<'g, F> {
+    glob: &'g PathGlob,
+    style: &'g Style<'g>,
+    path: String,
+    f: &'g mut F,
+}
+
+impl<'g, F: FnMut(&mut String, &mut Value)> MutWalk<'g, F> {
+    fn visit<'v>(&mut self, node: &'v mut Value, segments: &mut Vec<PathSegment<'v>>) {
+        if !self.glob.matches_prefix(segments) {
+            return;
+        }
+        if self.glob.matches(segments) && self.glob.matches_value(&*node) {
+            let path_len = self.path.len();
+            (self.f)(&mut self.path, node);
+            self.path.truncate(path_len);
+        }
+
+        let path_len = self.path.len();
+        match node {
+            Value::Object(obj) => {
+                for (key, val) in obj.iter_mut() {
+                    self.style.push_object_segment(&mut self.path, key);
+                    segments.push(PathSegment::Key(key));
+                    self.visit(val, segments);
+                    segments.pop();
+                    self.path.truncate(path_len);
+                }
+            }
+            Value::Array(arr) => {
+                for (index, val) in arr.iter_mut().enumerate() {
+                    self.style.push_array_segment(&mut self.path, index);
+                    segments.push(PathSegment::Index(index));
+                    self.visit(val, segments);
+                    segments.pop();
+                    self.path.truncate(path_len);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Renames every object key whose path matches the glob, at any depth, to whatever the callback returns for it
+///
+/// Paths are matched as they were before any renaming, and a renamed key replaces any entry already using the new name.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{rename_keys, PathGlob};
+///
+/// let mut value = json!({"userId": 1, "homeAddress": {"zipCode": "x"}, "tags": [{"tagName": "a"}]});
+/// let snake_case = |key: &str| {
+///     key.chars()
+///         .flat_map(|c| match c.is_uppercase() {
+///             true => vec!['_', c.to_ascii_lowercase()],
+///             false => vec![c],
+///         })
+///         .collect()
+/// };
+/// rename_keys(&mut value, &"**".parse().unwrap(), snake_case);
+///
+/// assert_eq!(value, json!({"user_id": 1, "home_address": {"zip_code": "x"}, "tags": [{"tag_name": "a"}]}));
+/// ```
+pub fn rename_keys<F>(json: &mut Value, glob: &PathGlob, mut f: F)
+where
+    F: FnMut(&str) -> String,
+{
+    rename_below(json, &[], glob, &mut f);
+}
+
+fn rename_below<F>(node: &mut Value, segments: &[PathSegment], glob: &PathGlob, f: &mut F)
+where
+    F: FnMut(&str) -> String,
+{
+    match node {
+        Value::Object(obj) => {
+            let mut renames = Vec::new();
+            let mut child = segments.to_vec();
+            for (key, val) in obj.iter_mut() {
+                child.push(PathSegment::Key(key));
+                if glob.matches(&child) {
+                    let new = f(key);
+                    if new != *key {
+                        renames.push((key.clone(), new));
+                    }
+                }
+                if glob.matches_prefix(&child) {
+                    rename_below(val, &child, glob, f);
+                }
+                child.pop();
+            }
+
+            if !renames.is_empty() {
+                let mut renames = renames.into_iter().peekable();
+                let entries = std::mem::replace(obj, Map::new());
+                for (key, val) in entries {
+                    match renames.next_if(|(old, _)| *old == key) {
+                        Some((_, new)) => {
+                            obj.insert(new, val);
+                        }
+                        None => {
+                            obj.entry(key).or_insert(val);
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(arr) => {
+            let mut child = segments.to_vec();
+            for (index, val) in arr.iter_mut().enumerate() {
+                child.push(PathSegment::Index(index));
+                if glob.matches_prefix(&child) {
+                    rename_below(val, &child, glob, f);
+                }
+                child.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Error produced when a value cannot be moved or copied between two paths
+#[derive(Debug)]
+pub enum MovePathError {
+    /// A path could not be parsed with the style
+    Parse(PathParseError),
+    /// Nothing exists at the source path
+    Missing {
+        /// The source path
+        path: String,
+    },
+    /// The destination path runs through a leaf, or names a non-empty object or array, as with `unflatten`
+    Conflict {
+        /// The destination path
+        path: String,
+    },
+    /// The destination path is nested inside the source path
+    IntoItself {
+        /// The destination path
+        path: String,
+    },
+}
+
+impl fmt::Display for MovePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovePathError::Parse(err) => err.fmt(f),
+            MovePathError::Missing { path } => write!(f, "nothing exists at path {:?}", path),
+            MovePathError::Conflict { path } => {
+                write!(f, "path {:?} conflicts with an existing value", path)
+            }
+            MovePathError::IntoItself { path } => {
+                write!(f, "path {:?} is inside the value being moved", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MovePathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MovePathError::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PathParseError> for MovePathError {
+    fn from(err: PathParseError) -> Self {
+        MovePathError::Parse(err)
+    }
+}
+
+/// Moves the value at one styled path to another, removing it from its old place
+///
+/// The destination is created like `unflatten` would: missing objects and arrays are added along the way,
+/// a leaf or an empty object or array is replaced, and anything else is a conflict.
+/// An array index may be at most the array's length, which appends, as arrays are never padded.
+/// Removing an array item shifts the items after it down, and the destination path is read after that shift.
+/// On error, the document is left unchanged.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{move_path, PresetStyle};
+///
+/// let mut value = json!({"meta": {"tags": ["a"], "id": 1}});
+/// move_path(&mut value, ".meta.tags", ".tags", &PresetStyle::CommonJs.into()).unwrap();
+///
+/// assert_eq!(value, json!({"meta": {"id": 1}, "tags": ["a"]}));
+/// ```
+pub fn move_path(
+    json: &mut Value,
+    from: &str,
+    to: &str,
+    style: &Style,
+) -> Result<(), MovePathError> {
+    let source = style.parse_path(from)?;
+    let destination = style.parse_path(to)?;
+    if get_mut(json, &source).is_none() {
+        return Err(MovePathError::Missing { path: from.into() });
+    }
+    if destination == source {
+        return Ok(());
+    }
+    if destination.starts_with(&source) {
+        return Err(MovePathError::IntoItself { path: to.into() });
+    }
+
+    // read the destination as it would be before the source is removed, to check it up front
+    let mut before_removal = destination.clone();
+    if let Some((PathSegment::Index(removed), parent)) = source.split_last() {
+        if destination.starts_with(parent) {
+            if let Some(PathSegment::Index(index)) = before_removal.get_mut(parent.len()) {
+                if *index >= *removed {
+                    *index += 1;
+                }
+            }
+        }
+    }
+    if !insertable(json, &before_removal) {
+        return Err(MovePathError::Conflict { path: to.into() });
+    }
+
+    let value = take(json, &source).ok_or_else(|| MovePathError::Missing { path: from.into() })?;
+    insert(json, &destination, value, &mut 0)
+        .ok_or_else(|| MovePathError::Conflict { path: to.into() })
+}
+
+/// Copies the value at one styled path to another, creating the destination like `move_path`
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{copy_path, PresetStyle};
+///
+/// let mut value = json!({"defaults": {"port": 80}, "servers": [{}]});
+/// copy_path(&mut value, ".defaults.port", ".servers[0].port", &PresetStyle::CommonJs.into()).unwrap();
+///
+/// assert_eq!(value, json!({"defaults": {"port": 80}, "servers": [{"port": 80}]}));
+/// ```
+pub fn copy_path(
+    json: &mut Value,
+    from: &str,
+    to: &str,
+    style: &Style,
+) -> Result<(), MovePathError> {
+    let source = style.parse_path(from)?;
+    let destination = style.parse_path(to)?;
+    let value = get_mut(json, &source)
+        .map(|value| value.clone())
+        .ok_or_else(|| MovePathError::Missing { path: from.into() })?;
+
+    insert(json, &destination, value, &mut 0)
+        .ok_or_else(|| MovePathError::Conflict { path: to.into() })
+}
+
+/// Removes the value at the segments, leaving `null` when it is the base of the json structure,
+/// or `None` if nothing exists there
+fn take(json: &mut Value, segments: &[PathSegment]) -> Option<Value> {
+    let parent = match segments.split_last() {
+        Some((_, parent)) => get_mut(json, parent),
+        None => return Some(std::mem::take(json)),
+    };
+    match (parent, segments.last()) {
+        (Some(Value::Object(obj)), Some(PathSegment::Key(key))) => remove_key(obj, key),
+        (Some(Value::Array(arr)), Some(PathSegment::Index(index))) if *index < arr.len() => {
+            Some(arr.remove(*index))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renamed_keys_replace_existing_entries() {
+        let mut value = json!({"aB": 1, "a_b": 2, "c": {"aB": 3}});
+        rename_keys(&mut value, &"*".parse().unwrap(), |key| {
+            key.replace("B", "_b")
+        });
+
+        assert_eq!(value, json!({"a_b": 1, "c": {"aB": 3}}));
+    }
+
+    #[test]
+    fn moves_within_an_array_after_removal() {
+        let style = PresetStyle::CommonJs.into();
+        let mut value = json!({"a": [1, 2, 3]});
+
+        move_path(&mut value, ".a[0]", ".a[1]", &style).unwrap();
+        assert_eq!(value, json!({"a": [2, 1]}));
+
+        let mut value = json!({"a": [1, [], {"x": 1}]});
+        assert!(matches!(
+            move_path(&mut value, ".a[0]", ".a[1]", &style),
+            Err(MovePathError::Conflict { .. })
+        ));
+        assert!(matches!(
+            move_path(&mut value, ".a", ".a[2].y", &style),
+            Err(MovePathError::IntoItself { .. })
+        ));
+        assert!(matches!(
+            move_path(&mut value, ".a[0]", ".b[1]", &style),
+            Err(MovePathError::Conflict { .. })
+        ));
+        assert!(matches!(
+            copy_path(&mut value, ".a[0]", ".a[18446744073709551615]", &style),
+            Err(MovePathError::Conflict { .. })
+        ));
+        assert_eq!(value, json!({"a": [1, [], {"x": 1}]}));
+
+        move_path(&mut value, ".a[0]", ".a[2]", &style).unwrap();
+        assert_eq!(value, json!({"a": [[], {"x": 1}, 1]}));
+    }
+
+    #[test]
+    fn walks_what_the_callback_leaves_behind() {
+        let mut value = json!({"a": 1});
+        let mut paths = Vec::new();
+        let style = PresetStyle::CommonJs.into();
+
+        for_each_mut_with(&mut value, &"**".parse().unwrap(), &style, |path, v| {
+            paths.push(path.to_string());
+            if v == &json!(1) {
+                *v = json!([true]);
+            }
+        });
+
+        assert_eq!(paths, vec!["", ".a", ".a[0]"]);
+        assert_eq!(value, json!({"a": [true]}));
+    }
+}
@@ -0,0 +1,202 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Classification of a single json node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// A `null` value
+    Null,
+    /// A `true` or `false` value
+    Bool,
+    /// Any numeric value
+    Number,
+    /// A string value
+    String,
+    /// An array of nodes
+    Array,
+    /// An object of keyed nodes
+    Object,
+}
+
+/// Minimal view of a json-like value, allowing any value type to be traversed by `Iterator`
+///
+/// Implemented for `serde_json::Value`, and for other value types behind their crate features.
+/// Third-party value types only need to classify themselves and expose their object entries and array items.
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::{Iterator, JsonNode, NodeKind};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Tree {
+///     Leaf(u32),
+///     Branch(Vec<Tree>),
+/// }
+///
+/// impl JsonNode for Tree {
+///     type Entries<'a> = std::iter::Empty<(&'a str, &'a Tree)>;
+///     type Items<'a> = std::slice::Iter<'a, Tree>;
+///
+///     fn kind(&self) -> NodeKind {
+///         match self {
+///             Tree::Leaf(_) => NodeKind::Number,
+///             Tree::Branch(_) => NodeKind::Array,
+///         }
+///     }
+///     fn entries(&self) -> Option<Self::Entries<'_>> {
+///         None
+///     }
+///     fn items(&self) -> Option<Self::Items<'_>> {
+///         match self {
+///             Tree::Leaf(_) => None,
+///             Tree::Branch(items) => Some(items.iter()),
+///         }
+///     }
+/// }
+///
+/// let tree = Tree::Branch(vec![Tree::Leaf(1), Tree::Branch(vec![Tree::Leaf(2)])]);
+/// let items: Vec<_> = Iterator::new(&tree).map(|el| (el.path, el.value)).collect();
+///
+/// assert_eq!(items, vec![("[0]".into(), &Tree::Leaf(1)), ("[1][0]".into(), &Tree::Leaf(2))]);
+/// ```
+pub trait JsonNode {
+    /// Iterator over the key and node of each object entry, in the order they should be yielded
+    type Entries<'a>: DoubleEndedIterator<Item = (&'a str, &'a Self)>
+    where
+        Self: 'a;
+    /// Iterator over each array item, in index order
+    type Items<'a>: DoubleEndedIterator<Item = &'a Self> + ExactSizeIterator
+    where
+        Self: 'a;
+
+    /// Classifies this node
+    fn kind(&self) -> NodeKind;
+    /// Entries of this node when it is an object, otherwise `None`
+    fn entries(&self) -> Option<Self::Entries<'_>>;
+    /// Items of this node when it is an array, otherwise `None`
+    fn items(&self) -> Option<Self::Items<'_>>;
+
+    /// Value of this node when it is a boolean, used by `ValuePredicate`
+    fn as_bool(&self) -> Option<bool> {
+        None
+    }
+    /// Value of this node when it is a number, used by `ValuePredicate`
+    fn as_f64(&self) -> Option<f64> {
+        None
+    }
+    /// Value of this node when it is an integer that fits in an `i64`, used by `ValuePredicate` to compare exactly
+    fn as_i64(&self) -> Option<i64> {
+        None
+    }
+    /// Value of this node when it is an integer that fits in a `u64`, used by `ValuePredicate` to compare exactly
+    fn as_u64(&self) -> Option<u64> {
+        None
+    }
+    /// Value of this node when it is a string, used by `ValuePredicate`
+    fn as_str(&self) -> Option<&str> {
+        None
+    }
+}
+
+type ValueEntry<'a> = (&'a String, &'a Value);
+
+impl JsonNode for Value {
+    type Entries<'a> =
+        std::iter::Map<serde_json::map::Iter<'a>, fn(ValueEntry<'a>) -> (&'a str, &'a Value)>;
+    type Items<'a> = std::slice::Iter<'a, Value>;
+
+    fn kind(&self) -> NodeKind {
+        match self {
+            Value::Null => NodeKind::Null,
+            Value::Bool(_) => NodeKind::Bool,
+            Value::Number(_) => NodeKind::Number,
+            Value::String(_) => NodeKind::String,
+            Value::Array(_) => NodeKind::Array,
+            Value::Object(_) => NodeKind::Object,
+        }
+    }
+
+    fn entries(&self) -> Option<Self::Entries<'_>> {
+        fn entry<'a>((key, val): ValueEntry<'a>) -> (&'a str, &'a Value) {
+            (key.as_str(), val)
+        }
+
+        match self {
+            Value::Object(obj) => Some(obj.iter().map(entry as fn(_) -> _)),
+            _ => None,
+        }
+    }
+
+    fn items(&self) -> Option<Self::Items<'_>> {
+        match self {
+            Value::Array(arr) => Some(arr.iter()),
+            _ => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        Value::as_bool(self)
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        Value::as_f64(self)
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        Value::as_i64(self)
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        Value::as_u64(self)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Value::as_str(self)
+    }
+}
+
+/// Compares two numeric nodes by their exact values, since large integers lose precision as `f64`
+///
+/// Integers are never converted to `f64`, so the comparison is transitive across integers and floats.
+pub(crate) fn compare_numbers<L: JsonNode, R: JsonNode>(left: &L, right: &R) -> Option<Ordering> {
+    fn integer<V: JsonNode>(n: &V) -> Option<i128> {
+        n.as_i64()
+            .map(i128::from)
+            .or_else(|| n.as_u64().map(i128::from))
+    }
+
+    match (integer(left), integer(right)) {
+        (Some(l), Some(r)) => Some(l.cmp(&r)),
+        (Some(l), None) => right.as_f64().and_then(|r| compare_integer_to_float(l, r)),
+        (None, Some(r)) => left
+            .as_f64()
+            .and_then(|l| compare_integer_to_float(r, l))
+            .map(Ordering::reverse),
+        (None, None) => left
+            .as_f64()
+            .zip(right.as_f64())
+            .and_then(|(l, r)| l.partial_cmp(&r)),
+    }
+}
+
+/// Compares an integer with a float without rounding either, or `None` when the float is NaN
+fn compare_integer_to_float(integer: i128, float: f64) -> Option<Ordering> {
+    // 2^127, the first float past the range of `i128`
+    const LIMIT: f64 = i128::MAX as f64;
+
+    if float.is_nan() {
+        None
+    } else if float >= LIMIT {
+        Some(Ordering::Less)
+    } else if float < -LIMIT {
+        Some(Ordering::Greater)
+    } else {
+        // the float is now finite and its whole part fits, so only the fraction can break a tie
+        let whole = float.trunc() as i128;
+        Some(
+            integer
+                .cmp(&whole)
+                .then_with(|| 0.0.partial_cmp(&float.fract()).unwrap_or(Ordering::Equal)),
+        )
+    }
+}
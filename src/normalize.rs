@@ -0,0 +1,167 @@
+use crate::glob::{Path, PathGlob};
+use crate::mutate::for_each_mut_unwrapped;
+use crate::style::{PresetStyle, Style};
+use serde_json::{Map, Number, Value};
+use std::collections::HashSet;
+
+/// Changes `normalize` makes across a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    /// Removes leading and trailing whitespace from strings
+    pub trim: bool,
+    /// Replaces every run of whitespace within strings by a single space
+    pub collapse_whitespace: bool,
+    /// Stores floats with no fractional part, such as `2.0` or `-0.0`, as integers
+    pub normalize_numbers: bool,
+    /// Lowercases object keys, with a lowercased key replacing any entry already using that name
+    pub lowercase_keys: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        NormalizeOptions {
+            trim: true,
+            collapse_whitespace: true,
+            normalize_numbers: true,
+            lowercase_keys: false,
+        }
+    }
+}
+
+/// Normalizes every value in a document, returning the paths it changed formatted with the default style
+///
+/// Changed strings and numbers are reported at their path, and renamed keys at their new path, each path once.
+/// The keys renamed in an object are reported together, before anything nested within it.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{normalize, NormalizeOptions};
+///
+/// let mut value = json!({"Name": "  Ada   Lovelace ", "score": 3.0, "tags": ["ok", " x "]});
+/// let options = NormalizeOptions { lowercase_keys: true, ..NormalizeOptions::default() };
+/// let changed = normalize(&mut value, &options);
+///
+/// assert_eq!(value, json!({"name": "Ada Lovelace", "score": 3, "tags": ["ok", "x"]}));
+/// assert_eq!(changed, vec!["[\"name\"]", "[\"score\"]", "[\"tags\"][1]"]);
+/// ```
+pub fn normalize(json: &mut Value, options: &NormalizeOptions) -> Vec<String> {
+    normalize_with(json, options, &PresetStyle::SquareBrackets.into())
+}
+
+/// Normalizes every value in a document, returning the paths it changed in the given style, see `normalize`
+pub fn normalize_with(json: &mut Value, options: &NormalizeOptions, style: &Style) -> Vec<String> {
+    let everything = PathGlob::from(Path::root().any_depth());
+    let mut changed = Vec::new();
+    let mut renamed = HashSet::new();
+
+    for_each_mut_unwrapped(json, &everything, style, |path, value| match value {
+        Value::Object(obj) if options.lowercase_keys => {
+            let path_len = path.len();
+            for key in lowercase_keys(obj) {
+                style.push_object_segment(path, &key);
+                changed.push(style.wrap_path(path));
+                renamed.insert(path.clone());
+                path.truncate(path_len);
+            }
+        }
+        Value::String(s) => {
+            if let Some(normalized) = normalize_string(s, options) {
+                *s = normalized;
+                if !renamed.contains(path) {
+                    changed.push(style.wrap_path(path));
+                }
+            }
+        }
+        Value::Number(n) if options.normalize_numbers => {
+            if let Some(normalized) = normalize_number(n) {
+                *n = normalized;
+                if !renamed.contains(path) {
+                    changed.push(style.wrap_path(path));
+                }
+            }
+        }
+        _ => {}
+    });
+
+    changed
+}
+
+/// Lowercases the keys of the object, returning the new keys of entries that were renamed in document order
+fn lowercase_keys(obj: &mut Map<String, Value>) -> Vec<String> {
+    if !obj.keys().any(|key| key.chars().any(char::is_uppercase)) {
+        return Vec::new();
+    }
+
+    let mut renamed = Vec::new();
+    let mut lowercased = Map::new();
+    for (key, val) in std::mem::take(obj) {
+        let lower = key.to_lowercase();
+        if lower == key {
+            lowercased.entry(key).or_insert(val);
+        } else {
+            renamed.push(lower.clone());
+            lowercased.insert(lower, val);
+        }
+    }
+    *obj = lowercased;
+
+    let mut keys: Vec<String> = obj
+        .keys()
+        .filter(|key| renamed.contains(key))
+        .cloned()
+        .collect();
+    keys.dedup();
+    keys
+}
+
+fn normalize_string(s: &str, options: &NormalizeOptions) -> Option<String> {
+    let mut normalized = match options.trim {
+        true => s.trim(),
+        false => s,
+    }
+    .to_string();
+
+    if options.collapse_whitespace {
+        let mut collapsed = String::with_capacity(normalized.len());
+        let mut in_whitespace = false;
+        for c in normalized.chars() {
+            match c.is_whitespace() {
+                true if in_whitespace => {}
+                true => collapsed.push(' '),
+                false => collapsed.push(c),
+            }
+            in_whitespace = c.is_whitespace();
+        }
+        normalized = collapsed;
+    }
+
+    (normalized != s).then_some(normalized)
+}
+
+fn normalize_number(n: &Number) -> Option<Number> {
+    let float = n.as_f64().filter(|_| n.is_f64())?;
+    let in_range = float >= i64::MIN as f64 && float < i64::MAX as f64;
+    (float.fract() == 0.0 && in_range).then(|| Number::from(float as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn collapses_without_trimming_and_lowercased_keys_replace_existing() {
+        let options = NormalizeOptions {
+            trim: false,
+            normalize_numbers: false,
+            lowercase_keys: true,
+            ..NormalizeOptions::default()
+        };
+        let mut value = json!({"a": 1, "A": {"B": "\t x \n\n y "}, "n": -0.0});
+        let changed = normalize_with(&mut value, &options, &PresetStyle::CommonJs.into());
+
+        assert_eq!(value, json!({"a": {"b": " x y "}, "n": -0.0}));
+        assert_eq!(changed, vec![".a", ".a.b"]);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+
+/// Removes every object entry whose value is `null`, at any depth, returning the paths removed
+/// formatted with the default style
+///
+/// Array entries are kept so indices stay stable, see `strip_nulls_with` to remove them as well.
+/// Objects and arrays left empty are kept.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::strip_nulls;
+///
+/// let mut value = json!({"a": null, "b": {"c": null, "d": 1}, "e": [null, {"f": null}]});
+/// let removed = strip_nulls(&mut value);
+///
+/// assert_eq!(value, json!({"b": {"d": 1}, "e": [null, {}]}));
+/// assert_eq!(removed, vec!["[\"a\"]", "[\"b\"][\"c\"]", "[\"e\"][1][\"f\"]"]);
+/// ```
+pub fn strip_nulls(json: &mut Value) -> Vec<String> {
+    strip_nulls_with(json, &PresetStyle::SquareBrackets.into(), false)
+}
+
+/// Removes every `null` object entry, and every `null` array entry when `array_entries` is set,
+/// returning the paths removed in the given style, see `strip_nulls`
+///
+/// Removed array entries are reported at their index before anything was removed.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{strip_nulls_with, PresetStyle};
+///
+/// let mut value = json!({"tags": [null, "a", null, "b"]});
+/// let removed = strip_nulls_with(&mut value, &PresetStyle::CommonJs.into(), true);
+///
+/// assert_eq!(value, json!({"tags": ["a", "b"]}));
+/// assert_eq!(removed, vec![".tags[0]", ".tags[2]"]);
+/// ```
+pub fn strip_nulls_with(json: &mut Value, style: &Style, array_entries: bool) -> Vec<String> {
+    let mut removed = Vec::new();
+    strip(json, style, array_entries, &mut String::new(), &mut removed);
+    removed
+}
+
+fn strip(
+    node: &mut Value,
+    style: &Style,
+    array_entries: bool,
+    path: &mut String,
+    removed: &mut Vec<String>,
+) {
+    let path_len = path.len();
+    match node {
+        Value::Object(obj) => {
+            obj.retain(|key, val| {
+                style.push_object_segment(path, key);
+                let keep = !val.is_null();
+                if keep {
+                    strip(val, style, array_entries, path, removed);
+                } else {
+                    removed.push(style.wrap_path(path));
+                }
+                path.truncate(path_len);
+                keep
+            });
+        }
+        Value::Array(arr) => {
+            let mut index = 0;
+            arr.retain_mut(|val| {
+                style.push_array_segment(path, index);
+                index += 1;
+                let keep = !(array_entries && val.is_null());
+                if keep {
+                    strip(val, style, array_entries, path, removed);
+                } else {
+                    removed.push(style.wrap_path(path));
+                }
+                path.truncate(path_len);
+                keep
+            });
+        }
+        _ => {}
+    }
+}
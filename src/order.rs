@@ -0,0 +1,76 @@
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+/// Order in which object entries are yielded by the iterators in this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Keys are yielded in sorted order, the default behaviour of `serde_json::Map`
+    Sorted,
+    /// Keys are yielded in the order they appeared in the source document
+    Insertion,
+}
+
+/// Reports which key order is in effect for `serde_json::Map`, and so for every iterator in this crate
+///
+/// Insertion order is enabled by this crate's `preserve_order` feature, which forwards to the serde_json feature
+/// of the same name. Because cargo unifies features, another crate in the dependency graph may enable it too,
+/// so this checks the behaviour of `serde_json::Map` directly rather than trusting this crate's own feature flags.
+/// The check runs once and is cached for the life of the process.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{key_order, Iterator, KeyOrder};
+///
+/// let value = json!({"b": 1, "a": 2});
+/// let paths: Vec<_> = Iterator::new(&value).map(|el| el.path).collect();
+///
+/// match key_order() {
+///     KeyOrder::Sorted => assert_eq!(paths, vec!["[\"a\"]", "[\"b\"]"]),
+///     KeyOrder::Insertion => assert_eq!(paths, vec!["[\"b\"]", "[\"a\"]"]),
+/// }
+/// ```
+pub fn key_order() -> KeyOrder {
+    static ORDER: OnceLock<KeyOrder> = OnceLock::new();
+
+    *ORDER.get_or_init(|| {
+        let mut probe = Map::new();
+        probe.insert("b".into(), Value::Null);
+        probe.insert("a".into(), Value::Null);
+
+        match probe.keys().next().map(String::as_str) {
+            Some("b") => KeyOrder::Insertion,
+            _ => KeyOrder::Sorted,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::Iter;
+
+    #[cfg(feature = "preserve_order")]
+    #[test]
+    fn preserves_document_order() {
+        let value: Value =
+            serde_json::from_str(r#"{"z": 1, "m": {"y": 2, "b": 3}, "a": 4}"#).unwrap();
+        let paths: Vec<_> = Iter::new(&value).map(|el| el.path).collect();
+
+        assert_eq!(key_order(), KeyOrder::Insertion);
+        assert_eq!(
+            paths,
+            vec!["[\"z\"]", "[\"m\"][\"y\"]", "[\"m\"][\"b\"]", "[\"a\"]"]
+        );
+    }
+
+    #[cfg(not(feature = "preserve_order"))]
+    #[test]
+    fn sorts_keys_by_default() {
+        let value: Value = serde_json::from_str(r#"{"z": 1, "a": 2}"#).unwrap();
+        let paths: Vec<_> = Iter::new(&value).map(|el| el.path).collect();
+
+        assert_eq!(key_order(), KeyOrder::Sorted);
+        assert_eq!(paths, vec!["[\"a\"]", "[\"z\"]"]);
+    }
+}
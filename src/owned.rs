@@ -0,0 +1,208 @@
+use crate::iter::Iter;
+use crate::style::{PresetStyle, Style};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// Single element struct like `Element`, but owning its json value
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedElement {
+    /// The full path from the base of a json structure to the value contained in the `OwnedElement`
+    pub path: String,
+    /// The full set of _array_ indices in the path, useful for grouping sets of `OwnedElement` structs to the same array element
+    pub indices: Vec<usize>,
+    /// The `serde_json::Value` described by the path
+    pub value: Value,
+}
+
+/// Iteration struct that takes ownership of a json value, moving each child value out as it is yielded
+#[derive(Debug)]
+pub struct IntoIter<'s> {
+    style: Style<'s>,
+    items: VecDeque<OwnedElement>,
+}
+
+impl<'s> IntoIter<'s> {
+    /// Create a new json keypath iterator that owns the json value
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{IntoIter, OwnedElement};
+    ///
+    /// let items: Vec<_> = IntoIter::new(json!({"a": [1, 2]})).collect();
+    ///
+    /// assert_eq!(items[0], OwnedElement { path: "[\"a\"][0]".into(), indices: vec![0], value: json!(1), });
+    /// assert_eq!(items[1], OwnedElement { path: "[\"a\"][1]".into(), indices: vec![1], value: json!(2), });
+    /// ```
+    pub fn new(json: Value) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back(OwnedElement {
+            path: String::from(""),
+            indices: Vec::new(),
+            value: json,
+        });
+
+        Self {
+            items: queue,
+            style: PresetStyle::SquareBrackets.into(),
+        }
+    }
+
+    /// Optionally used to set a custom style for the path in elements
+    pub fn use_style(mut self, style: Style<'s>) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl<'a> Iter<'a> {
+    /// Serializes any value into json and creates an iterator that owns the result
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, OwnedElement};
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Request {
+    ///     id: u32,
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// let request = Request { id: 7, tags: vec!["fast"] };
+    /// let items: Vec<_> = Iterator::from_serialize(&request).unwrap().collect();
+    ///
+    /// assert_eq!(items[0], OwnedElement { path: "[\"id\"]".into(), indices: vec![], value: json!(7), });
+    /// assert_eq!(items[1], OwnedElement { path: "[\"tags\"][0]".into(), indices: vec![0], value: json!("fast"), });
+    /// ```
+    pub fn from_serialize<T>(value: &T) -> Result<IntoIter<'static>, serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        serde_json::to_value(value).map(IntoIter::new)
+    }
+}
+
+impl<'s> IntoIter<'s> {
+    /// Queues another value to walk once everything before it has been yielded, with a path not yet wrapped
+    pub(crate) fn push_back(&mut self, el: OwnedElement) {
+        self.items.push_back(el);
+    }
+
+    /// Applies the style's whole-path wrapper to an element about to be yielded
+    fn finish(&self, mut el: OwnedElement) -> OwnedElement {
+        el.path = self.style.finish_path(el.path);
+        el
+    }
+}
+
+impl<'s> From<Value> for IntoIter<'s> {
+    fn from(item: Value) -> IntoIter<'s> {
+        IntoIter::new(item)
+    }
+}
+
+impl<'s> Iterator for IntoIter<'s> {
+    type Item = OwnedElement;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        'items: while let Some(mut el) = self.items.pop_front() {
+            match el.value {
+                Value::Object(_) => {
+                    // parents are only cloned when they will actually be yielded
+                    let obj = match self.style.should_skip_object_parents() {
+                        true => el.value.take(),
+                        false => el.value.clone(),
+                    };
+                    if let Value::Object(obj) = obj {
+                        for (key, val) in obj.into_iter().rev() {
+                            self.items.push_front(OwnedElement {
+                                path: self.style.object_format(&el.path, &key),
+                                indices: el.indices.clone(),
+                                value: val,
+                            });
+                        }
+                    }
+
+                    match self.style.should_skip_object_parents() {
+                        true => continue 'items,
+                        false => return Some(self.finish(el)),
+                    };
+                }
+                Value::Array(_) => {
+                    let arr = match self.style.should_skip_array_parents() {
+                        true => el.value.take(),
+                        false => el.value.clone(),
+                    };
+                    if let Value::Array(arr) = arr {
+                        for (index, val) in arr.into_iter().enumerate().rev() {
+                            let mut indices_vec = el.indices.to_vec();
+                            indices_vec.push(index);
+
+                            self.items.push_front(OwnedElement {
+                                path: self.style.array_format(&el.path, index),
+                                indices: indices_vec,
+                                value: val,
+                            });
+                        }
+                    }
+
+                    match self.style.should_skip_array_parents() {
+                        true => continue 'items,
+                        false => return Some(self.finish(el)),
+                    };
+                }
+                _ => return Some(self.finish(el)),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::StyleBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn matches_borrowed_iteration() {
+        let value = json!({
+            "first": [1, 2, 3],
+            "middle": {"inner": true},
+            "last": ["a", "b", "c"],
+        });
+        let style = || {
+            StyleBuilder::new()
+                .include_object_parents()
+                .include_array_parents()
+                .build()
+        };
+        let borrowed: Vec<_> = Iter::new(&value).use_style(style()).collect();
+        let owned: Vec<_> = IntoIter::new(value.clone()).use_style(style()).collect();
+
+        assert_eq!(borrowed.len(), owned.len());
+        for (borrowed, owned) in borrowed.into_iter().zip(owned) {
+            assert_eq!(borrowed.path, owned.path);
+            assert_eq!(borrowed.indices, owned.indices);
+            assert_eq!(borrowed.value, &owned.value);
+        }
+    }
+
+    #[test]
+    fn serializes_maps() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("b", vec![true]);
+        let items: Vec<_> = Iter::from_serialize(&map).unwrap().collect();
+
+        assert_eq!(
+            items,
+            vec![OwnedElement {
+                path: String::from("[\"b\"][0]"),
+                indices: vec![0],
+                value: Value::Bool(true),
+            }]
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use crate::de::insert;
+use crate::path::{get_mut, remove_key, PathParseError, PathSegment};
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+use std::fmt;
+
+/// Error for one operation of a `PatchSet` that could not be applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// A path could not be parsed with the style
+    Parse(PathParseError),
+    /// Nothing exists at the path being deleted or renamed
+    Missing {
+        /// The path of the operation
+        path: String,
+    },
+    /// The path runs through a leaf, its index would pad arrays with more `null`s than there are operations,
+    /// or the renamed entry is an array item or would replace another entry
+    Conflict {
+        /// The path of the operation
+        path: String,
+    },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::Parse(err) => err.fmt(f),
+            PatchError::Missing { path } => write!(f, "nothing exists at path {:?}", path),
+            PatchError::Conflict { path } => {
+                write!(f, "path {:?} conflicts with an existing value", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PatchError::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PathParseError> for PatchError {
+    fn from(err: PathParseError) -> Self {
+        PatchError::Parse(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operation {
+    Set(String, Value),
+    Delete(String),
+    Rename(String, String),
+}
+
+/// Batch of changes addressed by styled paths, applied to a document all at once or not at all
+///
+/// Operations run in the order they were recorded, so later ones see the results of earlier ones.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{PatchError, PatchSet, PresetStyle};
+///
+/// let mut value = json!({"user": {"name": "ada", "tmp": 1}, "tags": ["a"]});
+/// let mut patch = PatchSet::new();
+/// patch
+///     .set(".user.email", json!("ada@example.org"))
+///     .delete(".user.tmp")
+///     .rename(".user.name", "login")
+///     .set(".tags[1]", json!("b"));
+///
+/// patch.apply_with(&mut value, &PresetStyle::CommonJs.into()).unwrap();
+/// assert_eq!(value, json!({"user": {"login": "ada", "email": "ada@example.org"}, "tags": ["a", "b"]}));
+///
+/// let mut failing = PatchSet::new();
+/// failing.set(".user.email", json!(null)).delete(".missing").set(".tags[0].x", json!(1));
+/// let errors = failing.apply_with(&mut value, &PresetStyle::CommonJs.into()).unwrap_err();
+///
+/// assert_eq!(errors, vec![
+///     PatchError::Missing { path: ".missing".into() },
+///     PatchError::Conflict { path: ".tags[0].x".into() },
+/// ]);
+/// assert_eq!(value["user"]["email"], "ada@example.org");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatchSet {
+    operations: Vec<Operation>,
+}
+
+impl PatchSet {
+    /// Starts an empty set of operations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records storing the value at the path, replacing whatever is there and creating missing objects
+    /// and arrays along the way like `unflatten`, with at most one `null` of padding per operation in the set
+    pub fn set(&mut self, path: impl Into<String>, value: Value) -> &mut Self {
+        self.operations.push(Operation::Set(path.into(), value));
+        self
+    }
+
+    /// Records removing the object entry or array item at the path, which must exist
+    pub fn delete(&mut self, path: impl Into<String>) -> &mut Self {
+        self.operations.push(Operation::Delete(path.into()));
+        self
+    }
+
+    /// Records renaming the object entry at the path to the key, which must not already be used by another entry
+    pub fn rename(&mut self, path: impl Into<String>, key: impl Into<String>) -> &mut Self {
+        self.operations
+            .push(Operation::Rename(path.into(), key.into()));
+        self
+    }
+
+    /// Number of operations recorded
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Whether no operations were recorded
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Applies every operation, reading paths with the default style, see `apply_with`
+    pub fn apply(&self, json: &mut Value) -> Result<(), Vec<PatchError>> {
+        self.apply_with(json, &PresetStyle::SquareBrackets.into())
+    }
+
+    /// Applies every operation, reading paths with the given style
+    ///
+    /// Every path is parsed before anything is applied, and the operations are then run against a copy
+    /// of the document that only replaces it when all of them succeed. Otherwise the document is left unchanged
+    /// and the error of every failing operation is returned, in order.
+    pub fn apply_with(&self, json: &mut Value, style: &Style) -> Result<(), Vec<PatchError>> {
+        let mut errors = Vec::new();
+        let mut parsed = Vec::with_capacity(self.operations.len());
+        for operation in &self.operations {
+            let path = match operation {
+                Operation::Set(path, _) | Operation::Delete(path) | Operation::Rename(path, _) => {
+                    path
+                }
+            };
+            match style.parse_path(path) {
+                Ok(segments) => parsed.push((operation, path, segments)),
+                Err(err) => errors.push(err.into()),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut patched = json.clone();
+        let mut padding = self.operations.len();
+        for (operation, path, segments) in parsed {
+            let applied = match operation {
+                Operation::Set(_, value) => {
+                    set(&mut patched, &segments, value.clone(), &mut padding)
+                }
+                Operation::Delete(_) => delete(&mut patched, &segments),
+                Operation::Rename(_, key) => rename(&mut patched, &segments, key),
+            };
+            if let Err(err) = applied {
+                errors.push(err(path.clone()));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        *json = patched;
+        Ok(())
+    }
+}
+
+type Failure = fn(String) -> PatchError;
+
+fn missing(path: String) -> PatchError {
+    PatchError::Missing { path }
+}
+
+fn conflict(path: String) -> PatchError {
+    PatchError::Conflict { path }
+}
+
+fn set(
+    json: &mut Value,
+    segments: &[PathSegment],
+    value: Value,
+    padding: &mut usize,
+) -> Result<(), Failure> {
+    match get_mut(json, segments) {
+        Some(existing) => *existing = value,
+        None => insert(json, segments, value, padding).ok_or(conflict as Failure)?,
+    }
+    Ok(())
+}
+
+fn delete(json: &mut Value, segments: &[PathSegment]) -> Result<(), Failure> {
+    let (last, parent) = segments.split_last().ok_or(conflict as Failure)?;
+    match (get_mut(json, parent), last) {
+        (Some(Value::Object(obj)), PathSegment::Key(key)) => remove_key(obj, key).map(drop),
+        (Some(Value::Array(arr)), PathSegment::Index(index)) if *index < arr.len() => {
+            arr.remove(*index);
+            Some(())
+        }
+        _ => None,
+    }
+    .ok_or(missing as Failure)
+}
+
+fn rename(json: &mut Value, segments: &[PathSegment], key: &str) -> Result<(), Failure> {
+    let (last, parent) = segments.split_last().ok_or(conflict as Failure)?;
+    let (obj, old) = match (get_mut(json, parent), last) {
+        (Some(Value::Object(obj)), PathSegment::Key(old)) if obj.contains_key(*old) => (obj, *old),
+        (Some(Value::Array(arr)), PathSegment::Index(index)) if *index < arr.len() => {
+            return Err(conflict)
+        }
+        _ => return Err(missing),
+    };
+    if old == key {
+        return Ok(());
+    }
+    if obj.contains_key(key) {
+        return Err(conflict);
+    }
+
+    // rebuilt rather than removed and inserted, so that the entry keeps its place under `preserve_order`
+    *obj = std::mem::take(obj)
+        .into_iter()
+        .map(|(k, v)| match k == old {
+            true => (key.to_owned(), v),
+            false => (k, v),
+        })
+        .collect();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renames_and_deletes_keep_the_order_of_other_entries() {
+        let mut value = json!({"a": 1, "b": 2, "c": 3, "d": 4});
+        let mut patch = PatchSet::new();
+        patch.rename("[\"a\"]", "z").delete("[\"b\"]");
+        patch.apply(&mut value).unwrap();
+
+        let keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        match cfg!(feature = "preserve_order") {
+            true => assert_eq!(keys, vec!["z", "c", "d"]),
+            false => assert_eq!(keys, vec!["c", "d", "z"]),
+        }
+    }
+
+    #[test]
+    fn later_operations_see_earlier_ones_and_parse_errors_stop_everything() {
+        let mut value = json!({"a": 1});
+        let mut patch = PatchSet::new();
+        patch
+            .rename("[\"a\"]", "b")
+            .set("[\"b\"]", json!(2))
+            .delete("[\"a\"]");
+
+        assert_eq!(
+            patch.apply(&mut value),
+            Err(vec![PatchError::Missing {
+                path: "[\"a\"]".into()
+            }])
+        );
+        patch.operations.pop();
+        patch.apply(&mut value).unwrap();
+        assert_eq!(value, json!({"b": 2}));
+
+        patch.set("[\"c\"", json!(3)).set("oops", json!(4));
+        match patch.apply(&mut value) {
+            Err(errors) => assert!(errors.iter().all(|e| matches!(e, PatchError::Parse(_)))),
+            other => panic!("expected parse errors, got {:?}", other),
+        }
+        assert_eq!(value, json!({"b": 2}));
+
+        let mut huge = PatchSet::new();
+        huge.set("[\"t\"][100000000000]", json!(1));
+        assert_eq!(
+            huge.apply(&mut value),
+            Err(vec![PatchError::Conflict {
+                path: "[\"t\"][100000000000]".into()
+            }])
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use serde_json::{Map, Value};
+
+/// Single step in the path from the base of a json structure to a nested value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment<'a> {
+    /// Key of an entry within an object
+    Key(&'a str),
+    /// Index of an entry within an array
+    Index(usize),
+}
+
+/// Error describing where a styled path could not be parsed back into segments
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathParseError {
+    /// The full path that failed to parse
+    pub path: String,
+    /// Byte offset of the furthest point the parser reached
+    pub position: usize,
+    /// Short description of the problem
+    pub message: &'static str,
+}
+
+impl std::fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cannot parse path {:?} at position {}: {}",
+            self.path, self.position, self.message,
+        )
+    }
+}
+
+impl std::error::Error for PathParseError {}
+
+/// Value reached by following the segments down from a node, if every one of them exists
+pub(crate) fn get<'v>(node: &'v Value, segments: &[PathSegment]) -> Option<&'v Value> {
+    segments
+        .iter()
+        .try_fold(node, |node, segment| match (segment, node) {
+            (PathSegment::Key(key), Value::Object(obj)) => obj.get(*key),
+            (PathSegment::Index(index), Value::Array(arr)) => arr.get(*index),
+            _ => None,
+        })
+}
+
+/// Removes an object entry, shifting the entries after it so that `preserve_order` keeps their order
+#[cfg(feature = "preserve_order")]
+pub(crate) fn remove_key(obj: &mut Map<String, Value>, key: &str) -> Option<Value> {
+    obj.shift_remove(key)
+}
+
+/// Removes an object entry, shifting the entries after it so that `preserve_order` keeps their order
+#[cfg(not(feature = "preserve_order"))]
+pub(crate) fn remove_key(obj: &mut Map<String, Value>, key: &str) -> Option<Value> {
+    obj.remove(key)
+}
+
+/// Value reached by following the segments down from a node, if every one of them exists
+pub(crate) fn get_mut<'v>(node: &'v mut Value, segments: &[PathSegment]) -> Option<&'v mut Value> {
+    segments
+        .iter()
+        .try_fold(node, |node, segment| match (segment, node) {
+            (PathSegment::Key(key), Value::Object(obj)) => obj.get_mut(*key),
+            (PathSegment::Index(index), Value::Array(arr)) => arr.get_mut(*index),
+            _ => None,
+        })
+}
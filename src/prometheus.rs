@@ -0,0 +1,168 @@
+use crate::iter::Iter;
+use crate::path::PathSegment;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+/// Error returned when leaves under different keys sanitize to the same metric name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricNameCollision {
+    /// The metric name shared by the leaves
+    pub name: String,
+}
+
+impl fmt::Display for MetricNameCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "leaves under different keys share the metric name {}",
+            self.name
+        )
+    }
+}
+
+impl std::error::Error for MetricNameCollision {}
+
+/// Formats every numeric leaf as a gauge in the Prometheus text exposition format
+///
+/// Metric names are the namespace followed by each object key along the path, joined with `_`,
+/// with anything other than ascii letters, digits, `_`, and `:` replaced by `_`.
+/// Array indices become `index_0`, `index_1`, … labels from the outermost array inwards,
+/// so every entry of an array shares one metric. Leaves that are not numbers are left out.
+/// Fails when leaves under different keys end up with the same name, as `a-b` and `a_b` both would.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::to_prometheus;
+///
+/// let status = json!({"uptime": 3600, "queues": [{"depth": 4}, {"depth": 0}], "version": "1.2"});
+///
+/// assert_eq!(to_prometheus(&status, "app").unwrap(), "\
+/// ## TYPE app_queues_depth gauge
+/// app_queues_depth{index_0=\"0\"} 4
+/// app_queues_depth{index_0=\"1\"} 0
+/// ## TYPE app_uptime gauge
+/// app_uptime 3600
+/// ");
+/// assert!(to_prometheus(&json!({"a-b": 1, "a_b": 2}), "app").is_err());
+/// ```
+pub fn to_prometheus(json: &Value, namespace: &str) -> Result<String, MetricNameCollision> {
+    let mut metrics: BTreeMap<String, Metric> = BTreeMap::new();
+    let mut segments = Iter::new(json).segments();
+    while let Some((path, value)) = segments.next_segments() {
+        if !value.is_number() {
+            continue;
+        }
+        let mut keys = Vec::new();
+        let mut indices = Vec::new();
+        for segment in path {
+            match segment {
+                PathSegment::Key(key) => keys.push(*key),
+                PathSegment::Index(index) => indices.push(*index),
+            }
+        }
+
+        let name = metric_name(namespace, &keys);
+        let metric = metrics.entry(name).or_insert_with(|| Metric {
+            keys: keys.clone(),
+            samples: Vec::new(),
+        });
+        if metric.keys != keys {
+            return Err(MetricNameCollision {
+                name: metric_name(namespace, &keys),
+            });
+        }
+        metric.samples.push((indices, value));
+    }
+
+    let mut out = String::new();
+    for (name, metric) in metrics {
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        for (indices, value) in metric.samples {
+            out.push_str(&name);
+            if !indices.is_empty() {
+                out.push('{');
+                for (depth, index) in indices.iter().enumerate() {
+                    if depth > 0 {
+                        out.push(',');
+                    }
+                    let _ = write!(out, "index_{}=\"{}\"", depth, index);
+                }
+                out.push('}');
+            }
+            let _ = writeln!(out, " {}", value);
+        }
+    }
+    Ok(out)
+}
+
+/// Samples gathered under one metric name, with the object keys that name was built from
+struct Metric<'a> {
+    keys: Vec<&'a str>,
+    samples: Vec<(Vec<usize>, &'a Value)>,
+}
+
+/// Joins the namespace and keys into a metric name, prefixing `_` when it would be empty or start with a digit
+fn metric_name(namespace: &str, keys: &[&str]) -> String {
+    let mut name = metric_name_part(namespace).into_owned();
+    for key in keys {
+        if !name.is_empty() {
+            name.push('_');
+        }
+        name.push_str(&metric_name_part(key));
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// Replaces anything that is not allowed in a metric name with `_`
+fn metric_name_part(key: &str) -> Cow<'_, str> {
+    let allowed = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == ':';
+    match key.chars().all(allowed) {
+        true => Cow::Borrowed(key),
+        false => Cow::Owned(
+            key.chars()
+                .map(|c| if allowed(c) { c } else { '_' })
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sanitizes_names_without_namespace() {
+        let value = json!({"2xx rate": 0.5, "db": {"pool-size": [[3]]}});
+
+        assert_eq!(
+            to_prometheus(&value, "").unwrap(),
+            "# TYPE _2xx_rate gauge\n_2xx_rate 0.5\n\
+             # TYPE db_pool_size gauge\ndb_pool_size{index_0=\"0\",index_1=\"0\"} 3\n"
+        );
+    }
+
+    #[test]
+    fn root_number_uses_namespace() {
+        assert_eq!(
+            to_prometheus(&json!(1), "up").unwrap(),
+            "# TYPE up gauge\nup 1\n"
+        );
+    }
+
+    #[test]
+    fn rejects_keys_sharing_a_name() {
+        let collision = |value| to_prometheus(&value, "app").unwrap_err().name;
+
+        assert_eq!(collision(json!({"a-b": 1, "a_b": 2})), "app_a_b");
+        assert_eq!(collision(json!({"a": {"b": 1}, "a_b": 2})), "app_a_b");
+        assert_eq!(collision(json!({"x": [{"y": 1}], "x_y": 2})), "app_x_y");
+        assert!(to_prometheus(&json!({"a": [1, [2]]}), "app").is_ok());
+    }
+}
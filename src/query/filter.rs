@@ -0,0 +1,104 @@
+use crate::node::compare_numbers;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Expression inside `[?(...)]`, evaluated against each child with the child as `@`
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Exists(Operand),
+    Compare(Operand, Comparison, Operand),
+}
+
+/// Side of a comparison, either a value below `@` or a literal
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Operand {
+    Current(Vec<RelativeStep>),
+    Literal(Value),
+}
+
+/// Step of a path below `@`
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum RelativeStep {
+    Key(String),
+    Index(isize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Filter {
+    /// Whether the expression holds with `current` as `@`
+    pub(super) fn matches(&self, current: &Value) -> bool {
+        match self {
+            Filter::Or(left, right) => left.matches(current) || right.matches(current),
+            Filter::And(left, right) => left.matches(current) && right.matches(current),
+            Filter::Not(inner) => !inner.matches(current),
+            Filter::Exists(operand) => match operand.resolve(current) {
+                Some(Value::Bool(b)) if matches!(operand, Operand::Literal(_)) => *b,
+                Some(_) => matches!(operand, Operand::Current(_)),
+                None => false,
+            },
+            Filter::Compare(left, comparison, right) => {
+                match (left.resolve(current), right.resolve(current)) {
+                    (Some(left), Some(right)) => comparison.holds(left, right),
+                    // a missing value only differs from everything
+                    (None, _) | (_, None) => *comparison == Comparison::NotEqual,
+                }
+            }
+        }
+    }
+}
+
+impl Operand {
+    fn resolve<'v>(&'v self, current: &'v Value) -> Option<&'v Value> {
+        match self {
+            Operand::Literal(value) => Some(value),
+            Operand::Current(steps) => steps.iter().try_fold(current, |value, step| match step {
+                RelativeStep::Key(key) => value.as_object()?.get(key),
+                RelativeStep::Index(index) => {
+                    let arr = value.as_array()?;
+                    arr.get(super::resolve_index(*index, arr.len())?)
+                }
+            }),
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(self, left: &Value, right: &Value) -> bool {
+        let ordering = match (left, right) {
+            (Value::Number(_), Value::Number(_)) => compare_numbers(left, right),
+            (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+            _ if left == right => Some(Ordering::Equal),
+            _ => None,
+        };
+
+        match self {
+            Comparison::Equal => ordering == Some(Ordering::Equal),
+            Comparison::NotEqual => ordering != Some(Ordering::Equal),
+            _ if !matches!(
+                (left, right),
+                (Value::Number(_), Value::Number(_)) | (Value::String(_), Value::String(_))
+            ) =>
+            {
+                false
+            }
+            Comparison::Less => ordering == Some(Ordering::Less),
+            Comparison::LessOrEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Comparison::Greater => ordering == Some(Ordering::Greater),
+            Comparison::GreaterOrEqual => {
+                matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+            }
+        }
+    }
+}
@@ -0,0 +1,395 @@
+mod filter;
+mod parse;
+
+use crate::iter::Element;
+use crate::path::PathSegment;
+use crate::style::{PresetStyle, Style};
+use filter::Filter;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// Compiled JSONPath query, covering a pragmatic subset of the syntax
+///
+/// Queries start at `$` and are followed by any number of steps:
+/// `.key` or `['key']` for an object key, `[0]` for an array index, `.*` or `[*]` for every child,
+/// `[-1]` for an array index counted back from the end, where `-1` is the last item and indices past the start match nothing,
+/// `[start:end:step]` for a slice of array items, where every part is optional and may be negative as in Python,
+/// `..` before any of those to search every descendant instead of only children,
+/// and `[?(expression)]` to keep only children for which the filter expression holds.
+///
+/// Filter expressions compare `@`, or a path below it such as `@.price` or `@['a b'][0]`,
+/// against numbers, `'strings'`, `true`, `false`, and `null` with `==`, `!=`, `<`, `<=`, `>`, and `>=`.
+/// A path on its own checks that it exists, and comparisons can be combined with `&&`, `||`, `!`, and parentheses.
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::JsonPath;
+///
+/// let path: JsonPath = "$..books[?(@.price < 10 && @.tags)].title".parse().unwrap();
+///
+/// assert!("$.books[?(@.price <)]".parse::<JsonPath>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    descendants: bool,
+    selector: Selector,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Key(String),
+    Index(isize),
+    Slice {
+        start: Option<isize>,
+        end: Option<isize>,
+        step: isize,
+    },
+    Wildcard,
+    Filter(Filter),
+}
+
+/// Error describing where and why a `JsonPath` query could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPathError {
+    /// The full query that failed to parse
+    pub query: String,
+    /// Byte offset into the query where parsing failed
+    pub position: usize,
+    /// Short description of the problem
+    pub message: &'static str,
+}
+
+impl fmt::Display for JsonPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid json path {:?} at position {}: {}",
+            self.query, self.position, self.message,
+        )
+    }
+}
+
+impl std::error::Error for JsonPathError {}
+
+impl FromStr for JsonPath {
+    type Err = JsonPathError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        JsonPath::new(query)
+    }
+}
+
+/// Value reached by a query, along with the segments of its path
+type Node<'a> = (Vec<PathSegment<'a>>, &'a Value);
+
+impl JsonPath {
+    /// Parse a new query from a JSONPath string
+    pub fn new(query: &str) -> Result<Self, JsonPathError> {
+        parse::parse(query)
+    }
+
+    /// Evaluates the query, returning every matching value as an `Element` with its path formatted in the style
+    ///
+    /// Values are returned in the order they are found, where a descendant search applies its selector to a value
+    /// before the values nested within it. The style's parent options do not apply, since objects and arrays are only
+    /// returned when the query selects them.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{JsonPath, PresetStyle, Element};
+    ///
+    /// let value = json!({"store": {"books": [{"title": "a", "price": 8}, {"title": "b", "price": 12}]}});
+    /// let path: JsonPath = "$..books[?(@.price < 10)].title".parse().unwrap();
+    ///
+    /// assert_eq!(path.evaluate(&value, &PresetStyle::CommonJs.into()), vec![
+    ///     Element { path: ".store.books[0].title".into(), indices: vec![0], value: &json!("a") },
+    /// ]);
+    /// ```
+    pub fn evaluate<'a>(&self, json: &'a Value, style: &Style) -> Vec<Element<'a>> {
+        let mut nodes: Vec<Node<'a>> = vec![(Vec::new(), json)];
+        for step in &self.steps {
+            let mut next = Vec::new();
+            for (segments, value) in nodes {
+                match step.descendants {
+                    true => descend(&step.selector, segments, value, &mut next),
+                    false => select(&step.selector, &segments, value, &mut next),
+                }
+            }
+            nodes = next;
+        }
+
+        nodes
+            .into_iter()
+            .map(|(segments, value)| element(&segments, value, style))
+            .collect()
+    }
+}
+
+/// Parses and evaluates a JSONPath query, formatting paths with the default style
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::query;
+///
+/// let value = json!({"users": [{"name": "ada", "admin": true}, {"name": "bob"}]});
+/// let names: Vec<_> = query(&value, "$.users[?(@.admin == true)].name")
+///     .unwrap()
+///     .into_iter()
+///     .map(|el| (el.path, el.value))
+///     .collect();
+///
+/// assert_eq!(names, vec![("[\"users\"][0][\"name\"]".into(), &json!("ada"))]);
+/// ```
+pub fn query<'a>(json: &'a Value, query: &str) -> Result<Vec<Element<'a>>, JsonPathError> {
+    query_with(json, query, &PresetStyle::SquareBrackets.into())
+}
+
+/// Parses and evaluates a JSONPath query, formatting paths with the given style
+pub fn query_with<'a>(
+    json: &'a Value,
+    query: &str,
+    style: &Style,
+) -> Result<Vec<Element<'a>>, JsonPathError> {
+    Ok(JsonPath::new(query)?.evaluate(json, style))
+}
+
+/// Applies the selector to the children of a value
+fn select<'a>(
+    selector: &Selector,
+    segments: &[PathSegment<'a>],
+    value: &'a Value,
+    out: &mut Vec<Node<'a>>,
+) {
+    let child = |segment: PathSegment<'a>| {
+        let mut path = segments.to_vec();
+        path.push(segment);
+        path
+    };
+
+    match (selector, value) {
+        (Selector::Key(key), Value::Object(obj)) => {
+            if let Some((key, val)) = obj.get_key_value(key.as_str()) {
+                out.push((child(PathSegment::Key(key)), val));
+            }
+        }
+        (Selector::Index(index), Value::Array(arr)) => {
+            if let Some(index) = resolve_index(*index, arr.len()) {
+                out.push((child(PathSegment::Index(index)), &arr[index]));
+            }
+        }
+        (Selector::Slice { start, end, step }, Value::Array(arr)) => {
+            for index in slice_indices(*start, *end, *step, arr.len()) {
+                out.push((child(PathSegment::Index(index)), &arr[index]));
+            }
+        }
+        (Selector::Wildcard, _) | (Selector::Filter(_), _) => {
+            let keep = |val: &Value| match selector {
+                Selector::Filter(filter) => filter.matches(val),
+                _ => true,
+            };
+            match value {
+                Value::Object(obj) => {
+                    for (key, val) in obj.iter().filter(|(_, val)| keep(val)) {
+                        out.push((child(PathSegment::Key(key)), val));
+                    }
+                }
+                Value::Array(arr) => {
+                    for (index, val) in arr.iter().enumerate().filter(|(_, val)| keep(val)) {
+                        out.push((child(PathSegment::Index(index)), val));
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Applies the selector to the children of a value and of every value nested within it, in document order
+fn descend<'a>(
+    selector: &Selector,
+    segments: Vec<PathSegment<'a>>,
+    value: &'a Value,
+    out: &mut Vec<Node<'a>>,
+) {
+    let mut stack = vec![(segments, value)];
+    while let Some((segments, value)) = stack.pop() {
+        select(selector, &segments, value, out);
+
+        let start = stack.len();
+        match value {
+            Value::Object(obj) => {
+                for (key, val) in obj {
+                    let mut path = segments.clone();
+                    path.push(PathSegment::Key(key));
+                    stack.push((path, val));
+                }
+            }
+            Value::Array(arr) => {
+                for (index, val) in arr.iter().enumerate() {
+                    let mut path = segments.clone();
+                    path.push(PathSegment::Index(index));
+                    stack.push((path, val));
+                }
+            }
+            _ => {}
+        }
+        stack[start..].reverse();
+    }
+}
+
+/// Position of an index within an array of the given length, counting back from the end when negative
+fn resolve_index(index: isize, len: usize) -> Option<usize> {
+    let index = match index < 0 {
+        true => len.checked_sub(index.unsigned_abs())?,
+        false => index as usize,
+    };
+    (index < len).then_some(index)
+}
+
+/// Indices selected by a slice from an array of the given length, in the order the step walks them
+fn slice_indices(
+    start: Option<isize>,
+    end: Option<isize>,
+    step: isize,
+    len: usize,
+) -> impl Iterator<Item = usize> {
+    let len = len as isize;
+    let clamp = |bound: isize, min: isize, max: isize| {
+        let bound = if bound < 0 { bound + len } else { bound };
+        bound.clamp(min, max)
+    };
+
+    let (first, stop) = match step > 0 {
+        true => (
+            clamp(start.unwrap_or(0), 0, len),
+            clamp(end.unwrap_or(len), 0, len),
+        ),
+        false => (
+            start.map_or(len - 1, |start| clamp(start, -1, len - 1)),
+            end.map_or(-1, |end| clamp(end, -1, len - 1)),
+        ),
+    };
+
+    std::iter::successors(Some(first), move |index| index.checked_add(step))
+        .take_while(move |&index| match step > 0 {
+            true => index < stop,
+            false => index > stop,
+        })
+        .map(|index| index as usize)
+}
+
+/// Formats the segments of a matched value into an `Element`
+fn element<'a>(segments: &[PathSegment], value: &'a Value, style: &Style) -> Element<'a> {
+    let mut path = String::new();
+    let mut indices = Vec::new();
+    for segment in segments {
+        match segment {
+            PathSegment::Key(key) => style.push_object_segment(&mut path, key),
+            PathSegment::Index(index) => {
+                style.push_array_segment(&mut path, *index);
+                indices.push(*index);
+            }
+        }
+    }
+    Element {
+        path: style.finish_path(path),
+        indices,
+        value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(value: &Value, path: &str) -> Vec<String> {
+        query_with(value, path, &PresetStyle::CommonJs.into())
+            .unwrap()
+            .into_iter()
+            .map(|el| el.path)
+            .collect()
+    }
+
+    #[test]
+    fn descendants_select_outer_values_first() {
+        let value = json!({"a": {"b": [{"id": 2}], "id": 1}, "id": 3});
+
+        assert_eq!(paths(&value, "$..id"), vec![".id", ".a.id", ".a.b[0].id"]);
+        assert_eq!(
+            paths(&value, "$..*"),
+            vec![".a", ".id", ".a.b", ".a.id", ".a.b[0]", ".a.b[0].id"]
+        );
+    }
+
+    #[test]
+    fn root_query_selects_the_base() {
+        let value = json!([1]);
+
+        assert_eq!(paths(&value, "$"), vec![""]);
+        assert_eq!(paths(&value, "$[0]"), vec!["[0]"]);
+        assert!(paths(&value, "$[1]").is_empty());
+    }
+
+    #[test]
+    fn negative_indices_count_from_the_end() {
+        let value = json!({"a": [{"b": 1}, {"b": 2}, {"b": 3}]});
+
+        assert_eq!(paths(&value, "$.a[-1].b"), vec![".a[2].b"]);
+        assert_eq!(paths(&value, "$.a[-3]"), vec![".a[0]"]);
+        assert!(paths(&value, "$.a[-4]").is_empty());
+        assert_eq!(paths(&value, "$.a[?(@.b == 2)]"), vec![".a[1]"]);
+        assert_eq!(paths(&json!([[1, 5], [2]]), "$[?(@[-1] > 2)]"), vec!["[0]"]);
+    }
+
+    #[test]
+    fn slices_follow_python_semantics() {
+        let value = json!([0, 1, 2, 3, 4]);
+        let indices = |path| -> Vec<_> {
+            query(&value, path)
+                .unwrap()
+                .into_iter()
+                .map(|el| el.value.as_u64().unwrap())
+                .collect()
+        };
+
+        assert_eq!(indices("$[1:3]"), vec![1, 2]);
+        assert_eq!(indices("$[::2]"), vec![0, 2, 4]);
+        assert_eq!(indices("$[-2:]"), vec![3, 4]);
+        assert_eq!(indices("$[::-1]"), vec![4, 3, 2, 1, 0]);
+        assert_eq!(indices("$[3:0:-2]"), vec![3, 1]);
+        assert_eq!(indices("$[10:]"), Vec::<u64>::new());
+        assert_eq!(indices("$[0::9223372036854775807]"), vec![0]);
+        assert_eq!(indices("$[4::-9223372036854775808]"), vec![4]);
+    }
+
+    #[test]
+    fn filters_apply_to_object_members() {
+        let value = json!({"x": {"on": true}, "y": {"on": false}, "z": 3});
+
+        assert_eq!(paths(&value, "$[?(@.on == true)]"), vec![".x"]);
+        assert_eq!(
+            paths(&value, "$[?(@ > 2 || @.on == false)]"),
+            vec![".y", ".z"]
+        );
+        assert_eq!(paths(&value, "$[?(!@.on)]"), vec![".z"]);
+    }
+
+    #[test]
+    fn filters_compare_large_integers_exactly() {
+        let value = json!({"a": 9007199254740993u64, "b": -9007199254740993i64});
+
+        assert_eq!(paths(&value, "$[?(@ == 9007199254740993)]"), vec![".a"]);
+        assert_eq!(paths(&value, "$[?(@ > 9007199254740992)]"), vec![".a"]);
+        assert_eq!(paths(&value, "$[?(@ < -9007199254740992)]"), vec![".b"]);
+    }
+}
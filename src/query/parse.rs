@@ -0,0 +1,393 @@
+use super::filter::{Comparison, Filter, Operand, RelativeStep};
+use super::{JsonPath, JsonPathError, Selector, Step};
+use serde_json::Value;
+
+/// Deepest nesting of `||`, `&&`, `!`, and parentheses in a filter, which is parsed, evaluated, and dropped recursively
+const MAX_FILTER_DEPTH: usize = 128;
+
+/// Parses a full query starting with `$`
+pub(super) fn parse(query: &str) -> Result<JsonPath, JsonPathError> {
+    let mut parser = Parser {
+        query,
+        position: 0,
+        depth: 0,
+    };
+    if !parser.eat("$") {
+        return Err(parser.error("expected `$`"));
+    }
+
+    let mut steps = Vec::new();
+    while parser.position < query.len() {
+        let descendants = parser.eat("..");
+        let selector = if descendants && !parser.peek("[") {
+            parser.member()?
+        } else if descendants || parser.eat("[") {
+            if descendants {
+                parser.eat("[");
+            }
+            parser.bracket()?
+        } else if parser.eat(".") {
+            parser.member()?
+        } else {
+            return Err(parser.error("expected `.`, `..`, or `[`"));
+        };
+        steps.push(Step {
+            descendants,
+            selector,
+        });
+    }
+
+    Ok(JsonPath { steps })
+}
+
+struct Parser<'q> {
+    query: &'q str,
+    position: usize,
+    /// Operators the filter being parsed is nested within, see `MAX_FILTER_DEPTH`
+    depth: usize,
+}
+
+impl<'q> Parser<'q> {
+    fn error(&self, message: &'static str) -> JsonPathError {
+        JsonPathError {
+            query: self.query.into(),
+            position: self.position,
+            message,
+        }
+    }
+
+    /// Enters one more level of a filter, failing once it is nested deeper than `MAX_FILTER_DEPTH`
+    fn descend(&mut self) -> Result<(), JsonPathError> {
+        self.depth += 1;
+        match self.depth > MAX_FILTER_DEPTH {
+            true => Err(self.error("filter is nested too deeply")),
+            false => Ok(()),
+        }
+    }
+
+    fn rest(&self) -> &'q str {
+        &self.query[self.position..]
+    }
+
+    fn peek(&self, token: &str) -> bool {
+        self.rest().starts_with(token)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.peek(token);
+        if found {
+            self.position += token.len();
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Key or `*` after a `.` or `..`
+    fn member(&mut self) -> Result<Selector, JsonPathError> {
+        if self.eat("*") {
+            return Ok(Selector::Wildcard);
+        }
+        let name = self.name();
+        match name.is_empty() {
+            true => Err(self.error("expected object key or `*`")),
+            false => Ok(Selector::Key(name.into())),
+        }
+    }
+
+    /// Unquoted key, running up to the next `.`, `[`, or anything that cannot appear in one
+    fn name(&mut self) -> &'q str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '$'))
+            .unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    /// Contents of `[...]` after the opening bracket, including the closing bracket
+    fn bracket(&mut self) -> Result<Selector, JsonPathError> {
+        self.skip_whitespace();
+        let selector = if self.eat("*") {
+            Selector::Wildcard
+        } else if self.eat("?(") {
+            let filter = self.or()?;
+            self.skip_whitespace();
+            if !self.eat(")") {
+                return Err(self.error("expected `)` to close the filter"));
+            }
+            Selector::Filter(filter)
+        } else if self.peek("'") || self.peek("\"") {
+            Selector::Key(self.quoted()?)
+        } else {
+            self.index_or_slice()?
+        };
+
+        self.skip_whitespace();
+        match self.eat("]") {
+            true => Ok(selector),
+            false => Err(self.error("expected `]`")),
+        }
+    }
+
+    /// Either `index` or `start:end:step`, where every part of a slice is optional
+    fn index_or_slice(&mut self) -> Result<Selector, JsonPathError> {
+        let bound = |parser: &mut Self| match parser.peek(":") || parser.peek("]") {
+            true => Ok(None),
+            false => parser.index().map(Some),
+        };
+
+        let start = bound(self)?;
+        if !self.eat(":") {
+            return start
+                .map(Selector::Index)
+                .ok_or_else(|| self.error("expected array index"));
+        }
+        let end = bound(self)?;
+        let step = match self.eat(":") {
+            true => bound(self)?.unwrap_or(1),
+            false => 1,
+        };
+        if step == 0 {
+            return Err(self.error("slice step cannot be zero"));
+        }
+
+        Ok(Selector::Slice { start, end, step })
+    }
+
+    /// Array index, where a leading `-` counts back from the end
+    fn index(&mut self) -> Result<isize, JsonPathError> {
+        let sign = usize::from(self.peek("-"));
+        let digits = sign
+            + self.rest()[sign..]
+                .bytes()
+                .take_while(u8::is_ascii_digit)
+                .count();
+        let index = self.rest()[..digits]
+            .parse()
+            .map_err(|_| self.error("expected array index"))?;
+        self.position += digits;
+        Ok(index)
+    }
+
+    /// Single or double quoted string, with `\` escaping the next character
+    fn quoted(&mut self) -> Result<String, JsonPathError> {
+        let start = self.position;
+        let quote = self.rest().chars().next();
+        let mut text = String::new();
+        let mut chars = self.rest().char_indices().skip(1);
+
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => text.push(escaped),
+                    None => break,
+                },
+                _ if Some(c) == quote => {
+                    self.position += offset + c.len_utf8();
+                    return Ok(text);
+                }
+                _ => text.push(c),
+            }
+        }
+
+        self.position = start;
+        Err(self.error("unclosed string"))
+    }
+
+    fn or(&mut self) -> Result<Filter, JsonPathError> {
+        let depth = self.depth;
+        let mut filter = self.and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat("||") {
+                self.depth = depth;
+                return Ok(filter);
+            }
+            self.descend()?;
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> Result<Filter, JsonPathError> {
+        let depth = self.depth;
+        let mut filter = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat("&&") {
+                self.depth = depth;
+                return Ok(filter);
+            }
+            self.descend()?;
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Filter, JsonPathError> {
+        self.skip_whitespace();
+        if self.peek("!") && !self.peek("!=") {
+            self.descend()?;
+            self.position += 1;
+            let inner = self.unary()?;
+            self.depth -= 1;
+            return Ok(Filter::Not(Box::new(inner)));
+        }
+        if self.eat("(") {
+            self.descend()?;
+            let filter = self.or()?;
+            self.depth -= 1;
+            self.skip_whitespace();
+            return match self.eat(")") {
+                true => Ok(filter),
+                false => Err(self.error("expected `)`")),
+            };
+        }
+
+        let left = self.operand()?;
+        self.skip_whitespace();
+        let comparison = match self.comparison() {
+            Some(comparison) => comparison,
+            None => return Ok(Filter::Exists(left)),
+        };
+        self.skip_whitespace();
+        Ok(Filter::Compare(left, comparison, self.operand()?))
+    }
+
+    fn comparison(&mut self) -> Option<Comparison> {
+        let comparisons = [
+            ("==", Comparison::Equal),
+            ("!=", Comparison::NotEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+        ];
+        comparisons
+            .iter()
+            .find(|(token, _)| self.eat(token))
+            .map(|(_, comparison)| *comparison)
+    }
+
+    fn operand(&mut self) -> Result<Operand, JsonPathError> {
+        self.skip_whitespace();
+        if self.eat("@") {
+            let mut steps = Vec::new();
+            loop {
+                if self.eat(".") {
+                    let name = self.name();
+                    if name.is_empty() {
+                        return Err(self.error("expected object key"));
+                    }
+                    steps.push(RelativeStep::Key(name.into()));
+                } else if self.eat("[") {
+                    self.skip_whitespace();
+                    let step = match self.peek("'") || self.peek("\"") {
+                        true => RelativeStep::Key(self.quoted()?),
+                        false => RelativeStep::Index(self.index()?),
+                    };
+                    self.skip_whitespace();
+                    if !self.eat("]") {
+                        return Err(self.error("expected `]`"));
+                    }
+                    steps.push(step);
+                } else {
+                    return Ok(Operand::Current(steps));
+                }
+            }
+        }
+
+        if self.peek("'") || self.peek("\"") {
+            return Ok(Operand::Literal(Value::String(self.quoted()?)));
+        }
+        for (word, value) in [
+            ("true", Value::Bool(true)),
+            ("false", Value::Bool(false)),
+            ("null", Value::Null),
+        ] {
+            if self.eat(word) {
+                return Ok(Operand::Literal(value));
+            }
+        }
+
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(self.rest().len());
+        match serde_json::from_str::<serde_json::Number>(&self.rest()[..len]) {
+            Ok(number) if len > 0 => {
+                self.position += len;
+                Ok(Operand::Literal(Value::Number(number)))
+            }
+            _ => Err(self.error("expected `@`, a string, a number, `true`, `false`, or `null`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_steps() {
+        let path = parse("$.a..b[0]['c d'][*]..[1]").unwrap();
+        let selectors: Vec<_> = path
+            .steps
+            .iter()
+            .map(|step| (step.descendants, step.selector.clone()))
+            .collect();
+
+        assert_eq!(
+            selectors,
+            vec![
+                (false, Selector::Key("a".into())),
+                (true, Selector::Key("b".into())),
+                (false, Selector::Index(0)),
+                (false, Selector::Key("c d".into())),
+                (false, Selector::Wildcard),
+                (true, Selector::Index(1)),
+            ]
+        );
+        assert_eq!(
+            parse("$[-1]").unwrap().steps[0].selector,
+            Selector::Index(-1)
+        );
+        assert_eq!(
+            parse("$[::-2]").unwrap().steps[0].selector,
+            Selector::Slice {
+                start: None,
+                end: None,
+                step: -2
+            }
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        assert_eq!(parse("a").unwrap_err().position, 0);
+        assert_eq!(parse("$.").unwrap_err().position, 2);
+        assert_eq!(parse("$[0").unwrap_err().position, 3);
+        assert_eq!(parse("$[-]").unwrap_err().position, 2);
+        assert_eq!(parse("$[1:2:0]").unwrap_err().position, 7);
+        assert_eq!(parse("$['a]").unwrap_err().position, 2);
+        assert_eq!(parse("$[?(@.a == )]").unwrap_err().position, 11);
+    }
+
+    #[test]
+    fn rejects_deeply_nested_filters() {
+        let nested = |open: &str, close: &str, times: usize| {
+            format!("$[?({}@.a{})]", open.repeat(times), close.repeat(times))
+        };
+
+        assert!(parse(&nested("!", "", MAX_FILTER_DEPTH)).is_ok());
+        assert!(parse(&nested("(", ")", MAX_FILTER_DEPTH)).is_ok());
+        assert_eq!(
+            parse(&nested("!", "", 100_000)).unwrap_err().message,
+            "filter is nested too deeply"
+        );
+        assert!(parse(&nested("(", ")", 100_000)).is_err());
+        assert!(parse(&format!("$[?(@.a{})]", " && @.a".repeat(100_000))).is_err());
+    }
+}
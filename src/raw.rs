@@ -0,0 +1,273 @@
+use crate::iter::Iter;
+use crate::order::{key_order, KeyOrder};
+use crate::style::{PresetStyle, Style};
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::value::RawValue;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Single element struct like `Element`, but referencing unparsed json text
+#[derive(Debug)]
+pub struct RawElement<'a> {
+    /// The full path from the base of a json structure to the value contained in the `RawElement`
+    pub path: String,
+    /// The full set of _array_ indices in the path, useful for grouping sets of `RawElement` structs to the same array element
+    pub indices: Vec<usize>,
+    /// The unparsed json text described by the path
+    pub value: &'a RawValue,
+}
+
+/// Iteration struct over unparsed json that only splits containers once they are reached
+///
+/// Leaves are never parsed, and containers deeper than `max_depth` are yielded whole,
+/// so large embedded segments that are not descended into cost no more than a scan for their end.
+#[derive(Debug)]
+pub struct RawIter<'a> {
+    style: Style<'a>,
+    max_depth: Option<usize>,
+    items: VecDeque<(usize, RawElement<'a>)>,
+}
+
+impl<'a> RawIter<'a> {
+    /// Create a new json keypath iterator over unparsed json
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::value::RawValue;
+    /// use json_keypath_iter::RawIter;
+    ///
+    /// let raw = RawValue::from_string(r#"{"a": [1, {"b": null}]}"#.into()).unwrap();
+    /// let items: Vec<_> = RawIter::new(&raw).collect::<Result<_, _>>().unwrap();
+    ///
+    /// assert_eq!(items[0].path, "[\"a\"][0]");
+    /// assert_eq!(items[0].value.get(), "1");
+    /// assert_eq!(items[1].path, "[\"a\"][1][\"b\"]");
+    /// assert_eq!(items[1].value.get(), "null");
+    /// ```
+    pub fn new(json: &'a RawValue) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back((
+            0,
+            RawElement {
+                path: String::from(""),
+                indices: Vec::new(),
+                value: json,
+            },
+        ));
+
+        Self {
+            items: queue,
+            max_depth: None,
+            style: PresetStyle::SquareBrackets.into(),
+        }
+    }
+
+    /// Optionally used to set a custom style for the path in elements
+    pub fn use_style(mut self, style: Style<'a>) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Stops splitting containers nested deeper than the given depth, yielding them as unparsed leaves instead
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::value::RawValue;
+    /// use json_keypath_iter::RawIter;
+    ///
+    /// let raw = RawValue::from_string(r#"{"blob": {"huge": [1, 2, 3]}, "id": 1}"#.into()).unwrap();
+    /// let items: Vec<_> = RawIter::new(&raw).max_depth(1).collect::<Result<_, _>>().unwrap();
+    ///
+    /// assert_eq!(items[0].path, "[\"blob\"]");
+    /// assert_eq!(items[0].value.get(), r#"{"huge": [1, 2, 3]}"#);
+    /// ```
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+}
+
+impl<'a> Iter<'a> {
+    /// Create a json keypath iterator over unparsed json, see `RawIter::new`
+    pub fn from_raw(json: &'a RawValue) -> RawIter<'a> {
+        RawIter::new(json)
+    }
+}
+
+impl<'a> RawIter<'a> {
+    /// Applies the style's whole-path wrapper to an element about to be yielded
+    fn finish(&self, mut el: RawElement<'a>) -> RawElement<'a> {
+        el.path = self.style.finish_path(el.path);
+        el
+    }
+}
+
+impl<'a> From<&'a RawValue> for RawIter<'a> {
+    fn from(item: &'a RawValue) -> RawIter<'a> {
+        RawIter::new(item)
+    }
+}
+
+impl<'a> Iterator for RawIter<'a> {
+    type Item = Result<RawElement<'a>, serde_json::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        'items: while let Some((depth, el)) = self.items.pop_front() {
+            if self.max_depth.is_some_and(|max| depth >= max) {
+                return Some(Ok(self.finish(el)));
+            }
+
+            match el.value.get().trim_start().as_bytes().first() {
+                Some(b'{') => {
+                    let obj: RawEntries<'a> = match serde_json::from_str(el.value.get()) {
+                        Ok(obj) => obj,
+                        Err(err) => return Some(Err(err)),
+                    };
+                    for (key, val) in obj.0.into_iter().rev() {
+                        self.items.push_front((
+                            depth + 1,
+                            RawElement {
+                                path: self.style.object_format(&el.path, &key),
+                                indices: el.indices.clone(),
+                                value: val,
+                            },
+                        ));
+                    }
+
+                    match self.style.should_skip_object_parents() {
+                        true => continue 'items,
+                        false => return Some(Ok(self.finish(el))),
+                    };
+                }
+                Some(b'[') => {
+                    let arr: Vec<&'a RawValue> = match serde_json::from_str(el.value.get()) {
+                        Ok(arr) => arr,
+                        Err(err) => return Some(Err(err)),
+                    };
+                    for (index, val) in arr.into_iter().enumerate().rev() {
+                        let mut indices_vec = el.indices.to_vec();
+                        indices_vec.push(index);
+
+                        self.items.push_front((
+                            depth + 1,
+                            RawElement {
+                                path: self.style.array_format(&el.path, index),
+                                indices: indices_vec,
+                                value: val,
+                            },
+                        ));
+                    }
+
+                    match self.style.should_skip_array_parents() {
+                        true => continue 'items,
+                        false => return Some(Ok(self.finish(el))),
+                    };
+                }
+                _ => return Some(Ok(self.finish(el))),
+            }
+        }
+        None
+    }
+}
+
+/// Object entries in the same order, and with the same last-wins handling of duplicates, as `serde_json::Map`
+struct RawEntries<'a>(Vec<(String, &'a RawValue)>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawEntries<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = RawEntries<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a json object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries: Vec<(String, &'de RawValue)> = Vec::new();
+                let mut positions: HashMap<String, usize> = HashMap::new();
+                while let Some((key, val)) = map.next_entry::<String, &'de RawValue>()? {
+                    match positions.get(&key) {
+                        Some(&position) => entries[position].1 = val,
+                        None => {
+                            positions.insert(key.clone(), entries.len());
+                            entries.push((key, val));
+                        }
+                    }
+                }
+                if key_order() == KeyOrder::Sorted {
+                    entries.sort_by(|a, b| a.0.cmp(&b.0));
+                }
+                Ok(RawEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::StyleBuilder;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn matches_parsed_iteration() {
+        let value = json!({
+            "first": [1, 2, 3],
+            "middle": {"inner": true, "escaped \"key\"": "x"},
+            "last": ["a", "b", "c"],
+        });
+        let text = serde_json::to_string_pretty(&value).unwrap();
+        let raw = RawValue::from_string(text).unwrap();
+        let style = || StyleBuilder::new().include_object_parents().build();
+
+        let expected: Vec<_> = Iter::new(&value)
+            .use_style(style())
+            .map(|el| (el.path, el.indices, el.value.clone()))
+            .collect();
+        let actual: Vec<_> = RawIter::new(&raw)
+            .use_style(style())
+            .map(|el| {
+                let el = el.unwrap();
+                let value: Value = serde_json::from_str(el.value.get()).unwrap();
+                (el.path, el.indices, value)
+            })
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn stops_early_without_splitting_later_siblings() {
+        let raw = RawValue::from_string(r#"[1, [2, 3]]"#.into()).unwrap();
+        let mut iter = RawIter::new(&raw);
+
+        assert_eq!(iter.next().unwrap().unwrap().value.get(), "1");
+        // the nested array is still queued whole until it is reached
+        assert_eq!(iter.items.len(), 1);
+        assert_eq!(iter.items[0].1.value.get(), "[2, 3]");
+    }
+
+    #[test]
+    fn keeps_the_last_duplicate_key_like_parsed_values() {
+        let text = r#"{"a": 1, "b": 2, "a": 3}"#;
+        let value: Value = serde_json::from_str(text).unwrap();
+        let raw = RawValue::from_string(text.into()).unwrap();
+
+        let expected: Vec<_> = Iter::new(&value)
+            .map(|el| (el.path, el.value.clone()))
+            .collect();
+        let actual: Vec<_> = RawIter::new(&raw)
+            .map(|el| {
+                let el = el.unwrap();
+                (el.path, serde_json::from_str(el.value.get()).unwrap())
+            })
+            .collect();
+
+        assert_eq!(actual, expected);
+        assert_eq!(actual.len(), 2);
+    }
+}
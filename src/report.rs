@@ -0,0 +1,266 @@
+use crate::iter::{Element, Iter};
+use crate::style::Style;
+use crate::tree::truncate;
+use serde_json::{Map, Value};
+
+/// Markup produced by `render_table`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableFormat {
+    /// A GitHub flavoured Markdown table
+    Markdown,
+    /// A plain html `<table>`
+    Html,
+}
+
+/// Options controlling how `render_table` draws elements
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Markup to render the table as
+    pub format: TableFormat,
+    /// Rendered values longer than this many characters are cut short with `…`
+    pub max_value_len: usize,
+    /// Paths longer than this many characters are cut short with `…`, or `None` to keep them whole
+    pub max_path_len: Option<usize>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            format: TableFormat::Markdown,
+            max_value_len: 40,
+            max_path_len: None,
+        }
+    }
+}
+
+/// Renders elements as a table with one row per element and columns for the path, type, and value
+///
+/// Values are shown as json, and every cell is escaped for the chosen markup.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{render_table, Iterator, PresetStyle, RenderOptions, TableFormat};
+///
+/// let value = json!({"ids": [7], "name": "a|b"});
+/// let elements = Iterator::new(&value).use_style(PresetStyle::CommonJs.into());
+///
+/// assert_eq!(render_table(elements, &RenderOptions::default()), "\
+/// | path | type | value |
+/// | --- | --- | --- |
+/// | .ids[0] | number | 7 |
+/// | .name | string | \"a\\|b\" |
+/// ");
+///
+/// let options = RenderOptions { format: TableFormat::Html, ..RenderOptions::default() };
+/// let elements = Iterator::new(&value).use_style(PresetStyle::CommonJs.into()).take(1);
+/// assert_eq!(render_table(elements, &options), "\
+/// <table>
+/// <tr><th>path</th><th>type</th><th>value</th></tr>
+/// <tr><td>.ids[0]</td><td>number</td><td>7</td></tr>
+/// </table>
+/// ");
+/// ```
+pub fn render_table<'a, I>(elements: I, options: &RenderOptions) -> String
+where
+    I: IntoIterator<Item = Element<'a>>,
+{
+    let mut out = String::new();
+    match options.format {
+        TableFormat::Markdown => out.push_str("| path | type | value |\n| --- | --- | --- |\n"),
+        TableFormat::Html => {
+            out.push_str("<table>\n<tr><th>path</th><th>type</th><th>value</th></tr>\n")
+        }
+    }
+
+    for el in elements {
+        let path = match options.max_path_len {
+            Some(max_len) => truncate(el.path, max_len),
+            None => el.path,
+        };
+        let value = truncate(el.value.to_string(), options.max_value_len);
+        let cells = [path.as_str(), type_name(el.value), value.as_str()];
+
+        match options.format {
+            TableFormat::Markdown => {
+                out.push('|');
+                for cell in cells {
+                    out.push(' ');
+                    out.push_str(&escape_markdown(cell));
+                    out.push_str(" |");
+                }
+            }
+            TableFormat::Html => {
+                out.push_str("<tr>");
+                for cell in cells {
+                    out.push_str("<td>");
+                    out.push_str(&escape_html(cell));
+                    out.push_str("</td>");
+                }
+                out.push_str("</tr>");
+            }
+        }
+        out.push('\n');
+    }
+
+    if options.format == TableFormat::Html {
+        out.push_str("</table>\n");
+    }
+    out
+}
+
+/// Options controlling which fields `report_with` writes for each element
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportOptions {
+    /// Whether each entry has a `value` field, turn off to keep values out of audit logs
+    pub include_values: bool,
+    /// String values longer than this many characters are cut short with `…`, or `None` to keep them whole
+    pub max_string_len: Option<usize>,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        ReportOptions {
+            include_values: true,
+            max_string_len: None,
+        }
+    }
+}
+
+/// Describes every element yielded with the style as a json array of `path`, `indices`, `type`, and `value` entries
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{report, PresetStyle};
+///
+/// let value = json!({"user": {"name": "ada", "roles": ["admin"]}});
+///
+/// assert_eq!(report(&value, &PresetStyle::CommonJs.into()), json!([
+///     {"path": ".user.name", "indices": [], "type": "string", "value": "ada"},
+///     {"path": ".user.roles[0]", "indices": [0], "type": "string", "value": "admin"},
+/// ]));
+/// ```
+pub fn report(json: &Value, style: &Style) -> Value {
+    report_with(json, style, &ReportOptions::default())
+}
+
+/// Describes every element yielded with the style like `report`, leaving out or shortening values
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{report_with, PresetStyle, ReportOptions};
+///
+/// let value = json!({"id": 7, "note": "a long note"});
+/// let options = ReportOptions { max_string_len: Some(6), ..ReportOptions::default() };
+///
+/// assert_eq!(report_with(&value, &PresetStyle::CommonJs.into(), &options), json!([
+///     {"path": ".id", "indices": [], "type": "number", "value": 7},
+///     {"path": ".note", "indices": [], "type": "string", "value": "a long…"},
+/// ]));
+/// ```
+pub fn report_with(json: &Value, style: &Style, options: &ReportOptions) -> Value {
+    let entries = Iter::new(json)
+        .use_style(style.clone())
+        .map(|el| {
+            let mut entry = Map::new();
+            entry.insert("path".into(), Value::String(el.path));
+            entry.insert(
+                "indices".into(),
+                el.indices.into_iter().map(Value::from).collect(),
+            );
+            entry.insert("type".into(), type_name(el.value).into());
+            if options.include_values {
+                let value = match (el.value, options.max_string_len) {
+                    (Value::String(text), Some(max_len)) => {
+                        Value::String(truncate(text.clone(), max_len))
+                    }
+                    (value, _) => value.clone(),
+                };
+                entry.insert("value".into(), value);
+            }
+            Value::Object(entry)
+        })
+        .collect();
+    Value::Array(entries)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escapes pipes so they do not split the cell, and line breaks so they do not end the row
+fn escape_markdown(cell: &str) -> String {
+    cell.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+fn escape_html(cell: &str) -> String {
+    let mut escaped = String::with_capacity(cell.len());
+    for c in cell.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::Iter;
+    use serde_json::json;
+
+    #[test]
+    fn escapes_html_cells() {
+        let value = json!({"<b>": "x & y"});
+        let options = RenderOptions {
+            format: TableFormat::Html,
+            ..RenderOptions::default()
+        };
+
+        assert!(render_table(Iter::new(&value), &options).contains(
+            "<td>[&quot;&lt;b&gt;&quot;]</td><td>string</td><td>&quot;x &amp; y&quot;</td>"
+        ));
+    }
+
+    #[test]
+    fn report_can_omit_values() {
+        let options = ReportOptions {
+            include_values: false,
+            ..ReportOptions::default()
+        };
+
+        assert_eq!(
+            report_with(&json!([null]), &crate::styles::COMMON_JS, &options),
+            json!([{"path": "[0]", "indices": [0], "type": "null"}])
+        );
+    }
+
+    #[test]
+    fn truncates_paths_and_values() {
+        let value = json!({"long_key": "long value"});
+        let options = RenderOptions {
+            max_value_len: 5,
+            max_path_len: Some(4),
+            ..RenderOptions::default()
+        };
+
+        assert!(
+            render_table(Iter::new(&value), &options).ends_with("| [\"lo… | string | \"long… |\n")
+        );
+    }
+}
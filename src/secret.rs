@@ -0,0 +1,195 @@
+use crate::iter::{Element, Iter};
+use crate::node::JsonNode;
+use crate::path::PathSegment;
+use serde_json::Value;
+
+/// Key patterns treated as secrets by `SecretKeys::default`
+///
+/// Patterns are compared against keys ignoring case, `_`, `-`, `.`, and spaces, so `apikey` also covers `API_KEY` and `apiKey`.
+pub const DEFAULT_SECRET_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "apikey",
+    "authorization",
+    "privatekey",
+    "accesskey",
+    "credential",
+    "cookie",
+    "sessionid",
+];
+
+/// Placeholder written over secret values by `redact`
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Set of object key patterns whose values should never be logged or exported
+///
+/// A key is a secret when it contains any of the patterns, comparing both without case, `_`, `-`, `.`, or spaces.
+/// Everything nested under a secret key is a secret as well.
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::SecretKeys;
+///
+/// let secrets = SecretKeys::default();
+/// assert!(secrets.matches_key("DB_PASSWORD"));
+/// assert!(secrets.matches_key("refreshToken"));
+/// assert!(!secrets.matches_key("username"));
+///
+/// let secrets = SecretKeys::default().with("ssn");
+/// assert!(secrets.matches_key("customer_ssn"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecretKeys {
+    patterns: Vec<String>,
+}
+
+impl Default for SecretKeys {
+    fn default() -> Self {
+        SecretKeys::new(DEFAULT_SECRET_KEYS.iter().copied())
+    }
+}
+
+impl SecretKeys {
+    /// Create a set from only the given patterns, leaving out the defaults
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        SecretKeys {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| normalize(pattern.as_ref()))
+                .filter(|pattern| !pattern.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Adds another pattern to the set
+    pub fn with(mut self, pattern: &str) -> Self {
+        let pattern = normalize(pattern);
+        if !pattern.is_empty() {
+            self.patterns.push(pattern);
+        }
+        self
+    }
+
+    /// Whether an object key names a secret
+    pub fn matches_key(&self, key: &str) -> bool {
+        let key = normalize(key);
+        self.patterns.iter().any(|pattern| key.contains(pattern))
+    }
+
+    /// Whether any object key along the path names a secret
+    pub fn matches_path(&self, path: &[PathSegment]) -> bool {
+        path.iter().any(|segment| match segment {
+            PathSegment::Key(key) => self.matches_key(key),
+            PathSegment::Index(_) => false,
+        })
+    }
+}
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| !matches!(c, '_' | '-' | '.' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Copies a document with the value of every secret key replaced by `REDACTED`
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{redact, SecretKeys};
+///
+/// let config = json!({"db": {"user": "app", "password": "hunter2"}, "auth": {"api_key": {"id": 1}}});
+///
+/// assert_eq!(redact(&config, &SecretKeys::default()), json!({
+///     "db": {"user": "app", "password": "[REDACTED]"},
+///     "auth": {"api_key": "[REDACTED]"},
+/// }));
+/// ```
+pub fn redact(json: &Value, secrets: &SecretKeys) -> Value {
+    match json {
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(key, val)| match secrets.matches_key(key) {
+                    true => (key.clone(), Value::String(REDACTED.into())),
+                    false => (key.clone(), redact(val, secrets)),
+                })
+                .collect(),
+        ),
+        Value::Array(arr) => Value::Array(arr.iter().map(|val| redact(val, secrets)).collect()),
+        leaf => leaf.clone(),
+    }
+}
+
+impl<'a, V: JsonNode> Iter<'a, V> {
+    /// Consumes the iterator, leaving out every element at or under a secret key
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, SecretKeys};
+    ///
+    /// let value = json!({"user": "app", "session_id": "abc", "keys": {"private_key": "..."}});
+    /// let paths: Vec<_> = Iterator::new(&value).skip_secrets(SecretKeys::default()).map(|el| el.path).collect();
+    ///
+    /// assert_eq!(paths, vec!["[\"user\"]"]);
+    /// ```
+    pub fn skip_secrets(self, secrets: SecretKeys) -> SkipSecrets<'a, V> {
+        SkipSecrets {
+            iter: self,
+            secrets,
+        }
+    }
+}
+
+/// Iterator adapter leaving out elements whose path passes through a secret key
+///
+/// Created by `Iterator::skip_secrets`
+#[derive(Debug)]
+pub struct SkipSecrets<'a, V: JsonNode = Value> {
+    iter: Iter<'a, V>,
+    secrets: SecretKeys,
+}
+
+impl<'a, V: JsonNode> Iterator for SkipSecrets<'a, V> {
+    type Item = Element<'a, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(value) = self.iter.advance() {
+            if !self.secrets.matches_path(self.iter.current_segments()) {
+                return Some(self.iter.element(value));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn custom_sets_leave_out_defaults() {
+        let secrets = SecretKeys::new(["pin"]);
+
+        assert!(secrets.matches_key("PIN"));
+        assert!(!secrets.matches_key("password"));
+    }
+
+    #[test]
+    fn redacts_inside_arrays() {
+        let value = json!([{"token": [1, 2]}, {"name": "x"}]);
+
+        assert_eq!(
+            redact(&value, &SecretKeys::default()),
+            json!([{"token": "[REDACTED]"}, {"name": "x"}])
+        );
+    }
+}
@@ -0,0 +1,567 @@
+use crate::style::Style;
+use serde::ser::{self, Error as _, Impossible, Serialize};
+use serde_json::{Error, Value};
+
+/// Serializer that writes any `T: Serialize` straight into flattened `(path, value)` pairs
+///
+/// Paths are formatted with the given style without building an intermediate `serde_json::Value` tree.
+/// Only leaves are produced, whatever the style says about parents, and empty containers produce nothing,
+/// matching what `Iterator` yields with the default style. Enums use serde_json's externally tagged layout,
+/// so a newtype variant `Kind::Id(3)` is written as `["Id"] = 3`. Entries are written in the order they are
+/// serialized, which for structs is declaration order rather than the sorted order of `serde_json::Map`.
+///
+/// Example:
+/// ```rust
+/// use serde::Serialize;
+/// use serde_json::json;
+/// use json_keypath_iter::{FlatSerializer, PresetStyle, Style};
+///
+/// #[derive(Serialize)]
+/// struct Event {
+///     name: &'static str,
+///     tags: Vec<u8>,
+/// }
+///
+/// let style: Style = PresetStyle::CommonJs.into();
+/// let mut pairs = Vec::new();
+/// Event { name: "login", tags: vec![1, 2] }
+///     .serialize(FlatSerializer::new(&mut pairs, &style))
+///     .unwrap();
+///
+/// assert_eq!(pairs, vec![
+///     (".name".into(), json!("login")),
+///     (".tags[0]".into(), json!(1)),
+///     (".tags[1]".into(), json!(2)),
+/// ]);
+/// ```
+#[derive(Debug)]
+pub struct FlatSerializer<'o, 's> {
+    pairs: &'o mut Vec<(String, Value)>,
+    style: &'o Style<'s>,
+    path: String,
+}
+
+impl<'o, 's> FlatSerializer<'o, 's> {
+    /// Create a serializer appending its pairs onto the given vector
+    pub fn new(pairs: &'o mut Vec<(String, Value)>, style: &'o Style<'s>) -> Self {
+        FlatSerializer {
+            pairs,
+            style,
+            path: String::new(),
+        }
+    }
+
+    fn leaf<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        self.pairs.push((self.path, serde_json::to_value(value)?));
+        Ok(())
+    }
+
+    fn compound(self) -> FlatCompound<'o, 's> {
+        FlatCompound {
+            pairs: self.pairs,
+            style: self.style,
+            path: self.path,
+            index: 0,
+            key: None,
+        }
+    }
+
+    fn variant(self, variant: &str) -> Self {
+        let path = self.style.object_format(&self.path, variant);
+        FlatSerializer { path, ..self }
+    }
+}
+
+/// Serializes a value into flattened `(path, value)` pairs, see `FlatSerializer`
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{to_flat_pairs, PresetStyle};
+///
+/// let pairs = to_flat_pairs(&vec![Some(1), None], &PresetStyle::SquareBrackets.into()).unwrap();
+///
+/// assert_eq!(pairs, vec![("[0]".into(), json!(1)), ("[1]".into(), json!(null))]);
+/// ```
+pub fn to_flat_pairs<T>(value: &T, style: &Style) -> Result<Vec<(String, Value)>, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut pairs = Vec::new();
+    value.serialize(FlatSerializer::new(&mut pairs, style))?;
+    Ok(pairs)
+}
+
+macro_rules! serialize_leaves {
+    ($($method:ident: $ty:ty,)*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<(), Error> {
+                self.leaf(&value)
+            }
+        )*
+    };
+}
+
+impl<'o, 's> ser::Serializer for FlatSerializer<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = FlatCompound<'o, 's>;
+    type SerializeTuple = FlatCompound<'o, 's>;
+    type SerializeTupleStruct = FlatCompound<'o, 's>;
+    type SerializeTupleVariant = FlatCompound<'o, 's>;
+    type SerializeMap = FlatCompound<'o, 's>;
+    type SerializeStruct = FlatCompound<'o, 's>;
+    type SerializeStructVariant = FlatCompound<'o, 's>;
+
+    serialize_leaves! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), Error> {
+        let mut seq = self.compound();
+        for byte in value {
+            ser::SerializeSeq::serialize_element(&mut seq, byte)?;
+        }
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.leaf(&Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.leaf(&Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.leaf(&Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.leaf(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self.variant(variant))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<FlatCompound<'o, 's>, Error> {
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<FlatCompound<'o, 's>, Error> {
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<FlatCompound<'o, 's>, Error> {
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<FlatCompound<'o, 's>, Error> {
+        Ok(self.variant(variant).compound())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<FlatCompound<'o, 's>, Error> {
+        Ok(self.compound())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<FlatCompound<'o, 's>, Error> {
+        Ok(self.compound())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<FlatCompound<'o, 's>, Error> {
+        Ok(self.variant(variant).compound())
+    }
+}
+
+/// In-progress array, object, or struct whose children are written with extended paths
+#[derive(Debug)]
+pub struct FlatCompound<'o, 's> {
+    pairs: &'o mut Vec<(String, Value)>,
+    style: &'o Style<'s>,
+    path: String,
+    index: usize,
+    key: Option<String>,
+}
+
+impl<'o, 's> FlatCompound<'o, 's> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let path = self.style.array_format(&self.path, self.index);
+        self.index += 1;
+        value.serialize(FlatSerializer {
+            pairs: self.pairs,
+            style: self.style,
+            path,
+        })
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        value.serialize(FlatSerializer {
+            pairs: self.pairs,
+            style: self.style,
+            path: self.style.object_format(&self.path, key),
+        })
+    }
+}
+
+impl<'o, 's> ser::SerializeSeq for FlatCompound<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'o, 's> ser::SerializeTuple for FlatCompound<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'o, 's> ser::SerializeTupleStruct for FlatCompound<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'o, 's> ser::SerializeTupleVariant for FlatCompound<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'o, 's> ser::SerializeMap for FlatCompound<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::custom("serialize_value called before serialize_key"))?;
+        self.field(&key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'o, 's> ser::SerializeStruct for FlatCompound<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'o, 's> ser::SerializeStructVariant for FlatCompound<'o, 's> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Renders map keys as strings, accepting the same key types as serde_json
+struct MapKeySerializer;
+
+macro_rules! serialize_keys_to_string {
+    ($($method:ident: $ty:ty,)*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<String, Error> {
+                Ok(value.to_string())
+            }
+        )*
+    };
+}
+
+fn key_must_be_a_string() -> Error {
+    Error::custom("map key must be a string")
+}
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    serialize_keys_to_string! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_char: char,
+        serialize_str: &str,
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::Iter;
+    use crate::style::PresetStyle;
+    use serde::Serialize;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    enum Shape {
+        Unit,
+        Circle(f64),
+        Point(i32, i32),
+        Rect { h: u8, w: u8 },
+    }
+
+    #[test]
+    fn matches_iterating_the_serialized_value() {
+        let mut map = BTreeMap::new();
+        map.insert(1, vec![Shape::Unit, Shape::Circle(0.5)]);
+        map.insert(2, vec![Shape::Point(1, -1), Shape::Rect { h: 3, w: 2 }]);
+        let style = PresetStyle::PostgresJson.into();
+
+        let expected: Vec<_> = Iter::new(&serde_json::to_value(&map).unwrap())
+            .use_style(PresetStyle::PostgresJson.into())
+            .map(|el| (el.path, el.value.clone()))
+            .collect();
+
+        assert_eq!(to_flat_pairs(&map, &style).unwrap(), expected);
+    }
+
+    #[test]
+    fn empty_containers_produce_nothing() {
+        let value: (Vec<u8>, BTreeMap<String, u8>, Option<u8>) = (vec![], BTreeMap::new(), None);
+        let pairs = to_flat_pairs(&value, &PresetStyle::SquareBrackets.into()).unwrap();
+
+        assert_eq!(pairs, vec![("[2]".into(), json!(null))]);
+    }
+
+    #[test]
+    fn rejects_non_string_keys() {
+        let mut map = BTreeMap::new();
+        map.insert(vec![1], true);
+
+        assert!(to_flat_pairs(&map, &PresetStyle::SquareBrackets.into()).is_err());
+    }
+}
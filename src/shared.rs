@@ -0,0 +1,216 @@
+use crate::node::JsonNode;
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Upper bound on the number of distinct key and index segments cached by one `SharedIter`
+const MAX_CACHED_SEGMENTS: usize = 1024;
+
+/// Element whose path is split into a prefix shared with its siblings and its own final segment
+///
+/// The full path is only concatenated when `path` is called or the element is displayed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedElement<'a, V = Value> {
+    /// Path of the parent container, shared by every sibling
+    pub prefix: Arc<str>,
+    /// Formatted key or index of this element within its parent, shared by every element with the same key or index
+    pub segment: Arc<str>,
+    /// The full set of _array_ indices in the path, see `Element::indices`
+    pub indices: Vec<usize>,
+    /// The value described by the path
+    pub value: &'a V,
+}
+
+impl<'a, V> SharedElement<'a, V> {
+    /// Concatenates the prefix and final segment into the full path
+    pub fn path(&self) -> String {
+        let mut path = String::with_capacity(self.prefix.len() + self.segment.len());
+        path.push_str(&self.prefix);
+        path.push_str(&self.segment);
+        path
+    }
+}
+
+impl<'a, V> fmt::Display for SharedElement<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.prefix)?;
+        f.write_str(&self.segment)
+    }
+}
+
+/// Queued value whose path has not been concatenated yet
+#[derive(Debug)]
+struct SharedNode<'a, V> {
+    prefix: Arc<str>,
+    segment: Arc<str>,
+    indices: Arc<Vec<usize>>,
+    value: &'a V,
+}
+
+/// Iterator like `Iterator`, but yielding `SharedElement`s so that siblings share one allocation of their parent's path
+///
+/// Only one full path is allocated per container, instead of one per element, which cuts allocations on deep documents.
+/// Formatted key and index segments are cached as well, so arrays of objects with the same keys reuse a single allocation per key.
+/// The style's whole-path prefix and suffix are not applied, since they would stop prefixes being shared between depths.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{PresetStyle, SharedIter};
+///
+/// let value = json!({"a": {"b": 1, "c": 2}});
+/// let items: Vec<_> = SharedIter::new(&value).use_style(PresetStyle::CommonJs.into()).collect();
+///
+/// assert_eq!(&*items[0].prefix, ".a");
+/// assert_eq!(&*items[0].segment, ".b");
+/// assert_eq!(items[1].path(), ".a.c");
+/// assert!(std::sync::Arc::ptr_eq(&items[0].prefix, &items[1].prefix));
+/// ```
+#[derive(Debug)]
+pub struct SharedIter<'a, V = Value> {
+    style: Style<'a>,
+    items: Vec<SharedNode<'a, V>>,
+    key_segments: HashMap<&'a str, Arc<str>>,
+    index_segments: Vec<Arc<str>>,
+}
+
+impl<'a, V: JsonNode> SharedIter<'a, V> {
+    /// Create a new iterator with shared path prefixes, see `Iterator::new`
+    pub fn new(json: &'a V) -> Self {
+        Self {
+            style: PresetStyle::SquareBrackets.into(),
+            items: vec![SharedNode {
+                prefix: Arc::from(""),
+                segment: Arc::from(""),
+                indices: Arc::new(Vec::new()),
+                value: json,
+            }],
+            key_segments: HashMap::new(),
+            index_segments: Vec::new(),
+        }
+    }
+
+    /// Optionally used to set a custom style for the path in elements, see `Iterator::use_style`
+    pub fn use_style(mut self, style: Style<'a>) -> Self {
+        self.style = style;
+        self.key_segments.clear();
+        self.index_segments.clear();
+        self
+    }
+
+    fn key_segment(&mut self, key: &'a str) -> Arc<str> {
+        if let Some(segment) = self.key_segments.get(key) {
+            return Arc::clone(segment);
+        }
+        let segment: Arc<str> = Arc::from(self.style.object_format("", key));
+        if self.key_segments.len() < MAX_CACHED_SEGMENTS {
+            self.key_segments.insert(key, Arc::clone(&segment));
+        }
+        segment
+    }
+
+    fn index_segment(&mut self, index: usize) -> Arc<str> {
+        while self.index_segments.len() <= index && self.index_segments.len() < MAX_CACHED_SEGMENTS
+        {
+            let segment = self.style.array_format("", self.index_segments.len());
+            self.index_segments.push(Arc::from(segment));
+        }
+        match self.index_segments.get(index) {
+            Some(segment) => Arc::clone(segment),
+            None => Arc::from(self.style.array_format("", index)),
+        }
+    }
+}
+
+impl<'a, V: JsonNode> From<&'a V> for SharedIter<'a, V> {
+    fn from(item: &'a V) -> SharedIter<'a, V> {
+        SharedIter::new(item)
+    }
+}
+
+impl<'a, V: JsonNode> Iterator for SharedIter<'a, V> {
+    type Item = SharedElement<'a, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.items.pop() {
+            let skip = if let Some(entries) = node.value.entries() {
+                let prefix: Arc<str> = Arc::from(format!("{}{}", node.prefix, node.segment));
+                for (key, val) in entries.rev() {
+                    let segment = self.key_segment(key);
+                    self.items.push(SharedNode {
+                        prefix: Arc::clone(&prefix),
+                        segment,
+                        indices: Arc::clone(&node.indices),
+                        value: val,
+                    });
+                }
+                self.style.should_skip_object_parents()
+            } else if let Some(items) = node.value.items() {
+                let prefix: Arc<str> = Arc::from(format!("{}{}", node.prefix, node.segment));
+                for (index, val) in items.enumerate().rev() {
+                    let mut indices = Vec::with_capacity(node.indices.len() + 1);
+                    indices.extend_from_slice(&node.indices);
+                    indices.push(index);
+                    let segment = self.index_segment(index);
+                    self.items.push(SharedNode {
+                        prefix: Arc::clone(&prefix),
+                        segment,
+                        indices: Arc::new(indices),
+                        value: val,
+                    });
+                }
+                self.style.should_skip_array_parents()
+            } else {
+                false
+            };
+
+            if !skip {
+                return Some(SharedElement {
+                    prefix: node.prefix,
+                    segment: node.segment,
+                    indices: Arc::try_unwrap(node.indices).unwrap_or_else(|shared| shared.to_vec()),
+                    value: node.value,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::Iter;
+    use crate::style::StyleBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn repeated_keys_share_segments() {
+        let value = json!([{"a": 1}, {"a": 2}]);
+        let items: Vec<_> = SharedIter::new(&value).collect();
+
+        assert!(Arc::ptr_eq(&items[0].segment, &items[1].segment));
+        assert_eq!(items[1].path(), "[1][\"a\"]");
+    }
+
+    #[test]
+    fn matches_plain_iterator() {
+        let value = json!({"a": [1, {"b": [true]}], "c": {}, "d": "e"});
+        let style = StyleBuilder::new()
+            .include_object_parents()
+            .include_array_parents()
+            .build();
+        let plain: Vec<_> = Iter::new(&value)
+            .use_style(style.clone())
+            .map(|el| (el.path, el.indices, el.value))
+            .collect();
+        let shared: Vec<_> = SharedIter::new(&value)
+            .use_style(style)
+            .map(|el| (el.path(), el.indices, el.value))
+            .collect();
+
+        assert_eq!(plain, shared);
+    }
+}
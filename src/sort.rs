@@ -0,0 +1,224 @@
+use crate::dedup::HashedValue;
+use crate::glob::PathGlob;
+use crate::mutate::{for_each_mut, for_each_mut_unwrapped};
+use crate::node::compare_numbers;
+use crate::path::{get, PathSegment};
+use crate::style::{PresetStyle, Style};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// Direction arrays are sorted in by `sort_arrays`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// Smallest sort key first
+    Ascending,
+    /// Largest sort key first
+    Descending,
+}
+
+/// Where `sort_arrays` puts elements that have no value at the sort path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MissingKeys {
+    /// Before every element with a sort key, whatever the order
+    First,
+    /// After every element with a sort key, whatever the order
+    Last,
+}
+
+/// Sorts every array matching the glob by the value found at `by` within each of its elements
+///
+/// An empty `by` sorts by the elements themselves. The sort is stable, so elements with equal keys keep their
+/// relative order in both directions. Keys of different kinds are ordered `null`, booleans, numbers, strings,
+/// arrays, then objects, and arrays or objects compare equal to others of their kind.
+/// Matching values that are not arrays are left alone.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{sort_arrays, MissingKeys, PathSegment, SortOrder};
+///
+/// let mut value = json!({"teams": [{"members": [
+///     {"name": "c", "age": 30},
+///     {"name": "a"},
+///     {"name": "b", "age": 25},
+///     {"name": "d", "age": 30},
+/// ]}]});
+/// let by = [PathSegment::Key("age")];
+/// sort_arrays(&mut value, &"teams[*].members".parse().unwrap(), &by, SortOrder::Descending, MissingKeys::Last);
+///
+/// let names: Vec<_> = value["teams"][0]["members"].as_array().unwrap().iter().map(|m| m["name"].clone()).collect();
+/// assert_eq!(names, vec!["c", "d", "b", "a"]);
+/// ```
+pub fn sort_arrays(
+    json: &mut Value,
+    glob: &PathGlob,
+    by: &[PathSegment],
+    order: SortOrder,
+    missing: MissingKeys,
+) {
+    for_each_mut(json, glob, |_, value| {
+        if let Value::Array(arr) = value {
+            arr.sort_by(|a, b| match (get(a, by), get(b, by)) {
+                (Some(a), Some(b)) => match order {
+                    SortOrder::Ascending => compare(a, b),
+                    SortOrder::Descending => compare(b, a),
+                },
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => match missing {
+                    MissingKeys::First => Ordering::Less,
+                    MissingKeys::Last => Ordering::Greater,
+                },
+                (Some(_), None) => match missing {
+                    MissingKeys::First => Ordering::Greater,
+                    MissingKeys::Last => Ordering::Less,
+                },
+            });
+        }
+    });
+}
+
+/// Removes later duplicates from every array matching the glob, returning the paths removed formatted with the default style
+///
+/// Elements are compared whole when `key` is `None`, and otherwise by the value found at `key` within them,
+/// in which case elements without that value are always kept. Removed elements are reported at their index
+/// before anything was removed.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{dedup_arrays, PathSegment};
+///
+/// let mut value = json!({"tags": ["a", "b", "a"], "users": [{"id": 1, "v": 1}, {"id": 2}, {"id": 1, "v": 2}, {}]});
+/// let mut removed = dedup_arrays(&mut value, &"tags".parse().unwrap(), None);
+/// removed.extend(dedup_arrays(&mut value, &"users".parse().unwrap(), Some(&[PathSegment::Key("id")])));
+///
+/// assert_eq!(value, json!({"tags": ["a", "b"], "users": [{"id": 1, "v": 1}, {"id": 2}, {}]}));
+/// assert_eq!(removed, vec!["[\"tags\"][2]", "[\"users\"][2]"]);
+/// ```
+pub fn dedup_arrays(json: &mut Value, glob: &PathGlob, key: Option<&[PathSegment]>) -> Vec<String> {
+    dedup_arrays_with(json, glob, key, &PresetStyle::SquareBrackets.into())
+}
+
+/// Removes later duplicates from every array matching the glob, returning the paths removed in the given style,
+/// see `dedup_arrays`
+pub fn dedup_arrays_with(
+    json: &mut Value,
+    glob: &PathGlob,
+    key: Option<&[PathSegment]>,
+    style: &Style,
+) -> Vec<String> {
+    let mut removed = Vec::new();
+    for_each_mut_unwrapped(json, glob, style, |path, value| {
+        let arr = match value {
+            Value::Array(arr) => arr,
+            _ => return,
+        };
+
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for (index, elem) in arr.iter().enumerate() {
+            let compared = match key {
+                None => Some(elem),
+                Some(key) => get(elem, key),
+            };
+            if let Some(compared) = compared {
+                if !seen.insert(HashedValue(compared)) {
+                    duplicates.push(index);
+                }
+            }
+        }
+
+        let path_len = path.len();
+        for &index in &duplicates {
+            style.push_array_segment(path, index);
+            removed.push(style.wrap_path(path));
+            path.truncate(path_len);
+        }
+        let mut index = 0;
+        arr.retain(|_| {
+            index += 1;
+            duplicates.binary_search(&(index - 1)).is_err()
+        });
+    });
+    removed
+}
+
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        // equal values such as `-0.0` and `0` are told apart by their floats, so the order stays total
+        (Value::Number(x), Value::Number(y)) => compare_numbers(a, b)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| match (x.as_f64(), y.as_f64()) {
+                (Some(x), Some(y)) => x.total_cmp(&y),
+                _ => Ordering::Equal,
+            }),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn numbers_order_totally_across_integers_and_floats() {
+        let numbers = [
+            json!(9007199254740993u64),
+            json!(9007199254740992.0),
+            json!(9007199254740992u64),
+            json!(-0.0),
+            json!(0),
+            json!(0.5),
+            json!(-1),
+        ];
+        for a in numbers.iter() {
+            for b in numbers.iter() {
+                assert_eq!(compare(a, b), compare(b, a).reverse());
+                for c in numbers.iter() {
+                    if compare(a, b).is_le() && compare(b, c).is_le() {
+                        assert!(compare(a, c).is_le(), "{} <= {} <= {}", a, b, c);
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            compare(&json!(9007199254740993u64), &json!(9007199254740992.0)),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn mixed_kinds_sort_by_kind_and_missing_keys_go_first() {
+        let mut value =
+            json!([{"k": "x"}, {"k": 2}, {}, {"k": null}, {"k": 1.5}, {"k": [1]}, {"k": true}]);
+        let glob = PathGlob::new("").unwrap();
+        let by = [PathSegment::Key("k")];
+        sort_arrays(
+            &mut value,
+            &glob,
+            &by,
+            SortOrder::Ascending,
+            MissingKeys::First,
+        );
+
+        assert_eq!(
+            value,
+            json!([{}, {"k": null}, {"k": true}, {"k": 1.5}, {"k": 2}, {"k": "x"}, {"k": [1]}])
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::path::PathSegment;
+use serde_json::Value;
+
+/// Sql text with `$n` placeholders, along with the json values to bind to them in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlFragment {
+    /// Sql text, with object keys escaped as string literals and values replaced by placeholders
+    pub sql: String,
+    /// Values for the placeholders, to be bound as `jsonb`
+    pub params: Vec<Value>,
+}
+
+impl SqlFragment {
+    /// Builds `column->'key'->0 = $n` comparing the value at a path with a bound parameter
+    ///
+    /// `param` is the number of the placeholder, so fragments can be combined into a larger query.
+    /// The column is written as is and must come from trusted input.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{PathSegment, SqlFragment};
+    ///
+    /// let path = [PathSegment::Key("it's"), PathSegment::Index(0)];
+    /// let fragment = SqlFragment::path_eq("data", &path, json!("x"), 3);
+    ///
+    /// assert_eq!(fragment.sql, "data->'it''s'->0 = $3");
+    /// assert_eq!(fragment.params, vec![json!("x")]);
+    /// ```
+    pub fn path_eq(column: &str, path: &[PathSegment], value: Value, param: usize) -> Self {
+        SqlFragment {
+            sql: format!("{}{} = ${}", column, postgres_path(path), param),
+            params: vec![value],
+        }
+    }
+}
+
+/// Formats path segments as a `PresetStyle::PostgresJson` path, escaping quotes within object keys
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::{postgres_path, PathSegment};
+///
+/// assert_eq!(postgres_path(&[PathSegment::Key("a'b"), PathSegment::Index(2)]), "->'a''b'->2");
+/// ```
+pub fn postgres_path(path: &[PathSegment]) -> String {
+    let mut sql = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                sql.push_str("->'");
+                sql.push_str(&key.replace('\'', "''"));
+                sql.push('\'');
+            }
+            PathSegment::Index(index) => {
+                sql.push_str("->");
+                sql.push_str(&index.to_string());
+            }
+        }
+    }
+    sql
+}
+
+/// Builds a condition matching every leaf of a json value against a `jsonb` column, joined with `AND`
+///
+/// Placeholders are numbered from `$1` in document order, and empty objects and arrays are skipped.
+/// A value without any leaves produces `TRUE`.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::postgres_conditions;
+///
+/// let fragment = postgres_conditions("data", &json!({"a": [1, {"b": null}]}));
+///
+/// assert_eq!(fragment.sql, "data->'a'->0 = $1 AND data->'a'->1->'b' = $2");
+/// assert_eq!(fragment.params, vec![json!(1), json!(null)]);
+/// ```
+pub fn postgres_conditions(column: &str, json: &Value) -> SqlFragment {
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
+    let mut stack = vec![(Vec::new(), json)];
+
+    while let Some((path, value)) = stack.pop() {
+        match value {
+            Value::Object(obj) => {
+                for (key, val) in obj.iter().rev() {
+                    let mut segments = path.clone();
+                    segments.push(PathSegment::Key(key));
+                    stack.push((segments, val));
+                }
+            }
+            Value::Array(arr) => {
+                for (index, val) in arr.iter().enumerate().rev() {
+                    let mut segments = path.clone();
+                    segments.push(PathSegment::Index(index));
+                    stack.push((segments, val));
+                }
+            }
+            leaf => {
+                let fragment = SqlFragment::path_eq(column, &path, leaf.clone(), params.len() + 1);
+                conditions.push(fragment.sql);
+                params.extend(fragment.params);
+            }
+        }
+    }
+
+    SqlFragment {
+        sql: match conditions.is_empty() {
+            true => String::from("TRUE"),
+            false => conditions.join(" AND "),
+        },
+        params,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn root_leaf_compares_column() {
+        let fragment = postgres_conditions("data", &json!(5));
+
+        assert_eq!(fragment.sql, "data = $1");
+        assert_eq!(fragment.params, vec![json!(5)]);
+    }
+
+    #[test]
+    fn values_never_reach_sql() {
+        let fragment = postgres_conditions("data", &json!({"k": "'; DROP TABLE t; --"}));
+
+        assert_eq!(fragment.sql, "data->'k' = $1");
+        assert_eq!(postgres_conditions("data", &json!({"a": []})).sql, "TRUE");
+    }
+}
@@ -0,0 +1,377 @@
+use crate::iter::Iter;
+use crate::owned::{IntoIter, OwnedElement};
+use crate::style::{PresetStyle, Style};
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::de::IoRead;
+use serde_json::{Map, Number, Value};
+use std::fmt;
+use std::io;
+
+/// Element yielded from a multi-document stream, annotated with the document it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentElement {
+    /// Zero based sequence number of the document within the stream
+    pub document: usize,
+    /// The element within that document
+    pub element: OwnedElement,
+}
+
+/// How a `StreamIter` treats an object key that appears more than once in the same object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuplicateKeyPolicy {
+    /// Keeps the first value and ignores later ones
+    FirstWins,
+    /// Keeps the last value, as `serde_json::Value` does
+    LastWins,
+    /// Yields the elements of every value, with those of later values after the rest of their document
+    YieldBoth,
+    /// Fails to parse the document, ending the stream
+    Error,
+}
+
+/// Iteration struct over a stream of whitespace or newline separated json documents, such as NDJSON log files
+///
+/// Each document is parsed only once the previous one has been fully yielded.
+/// Iteration stops after the first parse error.
+pub struct StreamIter<'s, R: io::Read> {
+    style: Style<'s>,
+    documents: serde_json::Deserializer<IoRead<R>>,
+    duplicate_keys: DuplicateKeyPolicy,
+    current: Option<IntoIter<'s>>,
+    document: usize,
+    failed: bool,
+}
+
+impl<'s, R: io::Read> StreamIter<'s, R> {
+    /// Create a new json keypath iterator over every document in a reader
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::StreamIter;
+    ///
+    /// let lines = "{\"level\": \"info\"}\n{\"level\": \"warn\"}\n";
+    /// let items: Vec<_> = StreamIter::new(lines.as_bytes()).collect::<Result<_, _>>().unwrap();
+    ///
+    /// assert_eq!(items[0].document, 0);
+    /// assert_eq!(items[1].document, 1);
+    /// assert_eq!(items[1].element.path, "[\"level\"]");
+    /// assert_eq!(items[1].element.value, json!("warn"));
+    /// ```
+    pub fn new(reader: R) -> Self {
+        Self {
+            style: PresetStyle::SquareBrackets.into(),
+            documents: serde_json::Deserializer::from_reader(reader),
+            duplicate_keys: DuplicateKeyPolicy::LastWins,
+            current: None,
+            document: 0,
+            failed: false,
+        }
+    }
+
+    /// Optionally used to set a custom style for the path in elements
+    pub fn use_style(mut self, style: Style<'s>) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets how keys repeated within an object are treated, which is `DuplicateKeyPolicy::LastWins` by default
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{DuplicateKeyPolicy, PresetStyle, StreamIter};
+    ///
+    /// let lines = "{\"a\": 1, \"b\": 2, \"a\": 3}";
+    /// let items: Vec<_> = StreamIter::new(lines.as_bytes())
+    ///     .duplicate_keys(DuplicateKeyPolicy::YieldBoth)
+    ///     .map(|el| el.unwrap().element)
+    ///     .map(|el| (el.path, el.value))
+    ///     .collect();
+    ///
+    /// assert_eq!(items, vec![
+    ///     ("[\"a\"]".to_string(), json!(1)),
+    ///     ("[\"b\"]".to_string(), json!(2)),
+    ///     ("[\"a\"]".to_string(), json!(3)),
+    /// ]);
+    ///
+    /// let lines = "{\"a\": [{\"b\": 1, \"b\": 2}]}";
+    /// let err = StreamIter::new(lines.as_bytes())
+    ///     .use_style(PresetStyle::CommonJs.into())
+    ///     .duplicate_keys(DuplicateKeyPolicy::Error)
+    ///     .next()
+    ///     .unwrap()
+    ///     .unwrap_err();
+    ///
+    /// assert!(err.to_string().starts_with("duplicate key at path .a[0].b"));
+    /// ```
+    pub fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Parses the next document, or `None` once only whitespace is left
+    fn next_document(&mut self) -> Option<Result<IntoIter<'s>, serde_json::Error>> {
+        if self.documents.end().is_ok() {
+            return None;
+        }
+
+        let mut state = ParseState {
+            policy: self.duplicate_keys,
+            style: &self.style,
+            path: Vec::new(),
+            duplicates: Vec::new(),
+        };
+        let value = ValueSeed(&mut state).deserialize(&mut self.documents);
+        let duplicates = state.duplicates;
+        Some(value.map(|value| {
+            let mut iter = IntoIter::new(value).use_style(self.style.clone());
+            for duplicate in duplicates {
+                iter.push_back(duplicate);
+            }
+            iter
+        }))
+    }
+}
+
+impl<'a> Iter<'a> {
+    /// Create a json keypath iterator over every document in a reader, see `StreamIter::new`
+    pub fn from_reader<R: io::Read>(reader: R) -> StreamIter<'static, R> {
+        StreamIter::new(reader)
+    }
+}
+
+impl<'s, R: io::Read> fmt::Debug for StreamIter<'s, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamIter")
+            .field("style", &self.style)
+            .field("duplicate_keys", &self.duplicate_keys)
+            .field("current", &self.current)
+            .field("document", &self.document)
+            .field("failed", &self.failed)
+            .finish()
+    }
+}
+
+impl<'s, R: io::Read> Iterator for StreamIter<'s, R> {
+    type Item = Result<DocumentElement, serde_json::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            if let Some(element) = self.current.as_mut().and_then(|iter| iter.next()) {
+                return Some(Ok(DocumentElement {
+                    document: self.document - 1,
+                    element,
+                }));
+            }
+
+            match self.next_document()? {
+                Ok(iter) => {
+                    self.current = Some(iter);
+                    self.document += 1;
+                }
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Step within a document being parsed
+enum ParsedSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a document is up to while being parsed, and the repeated keys set aside so far
+struct ParseState<'p, 's> {
+    policy: DuplicateKeyPolicy,
+    style: &'p Style<'s>,
+    path: Vec<ParsedSegment>,
+    duplicates: Vec<OwnedElement>,
+}
+
+impl<'p, 's> ParseState<'p, 's> {
+    /// Path of the value being parsed as an element still to be yielded, without the whole-path wrapper
+    fn element(&self, value: Value) -> OwnedElement {
+        let mut path = String::new();
+        let mut indices = Vec::new();
+        for segment in &self.path {
+            match segment {
+                ParsedSegment::Key(key) => self.style.push_object_segment(&mut path, key),
+                ParsedSegment::Index(index) => {
+                    self.style.push_array_segment(&mut path, *index);
+                    indices.push(*index);
+                }
+            }
+        }
+        OwnedElement {
+            path,
+            indices,
+            value,
+        }
+    }
+}
+
+/// Builds a `Value` like its own `Deserialize` implementation, applying the duplicate key policy to objects
+struct ValueSeed<'a, 'p, 's>(&'a mut ParseState<'p, 's>);
+
+impl<'de, 'a, 'p, 's> DeserializeSeed<'de> for ValueSeed<'a, 'p, 's> {
+    type Value = Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a, 'p, 's> Visitor<'de> for ValueSeed<'a, 'p, 's> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Value, E> {
+        Ok(Value::Bool(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<Value, E> {
+        Ok(Value::Number(n.into()))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<Value, E> {
+        Ok(Value::Number(n.into()))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(n).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Value, E> {
+        Ok(Value::String(s.into()))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Value, E> {
+        Ok(Value::String(s))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let state = self.0;
+        let mut arr = Vec::new();
+        loop {
+            state.path.push(ParsedSegment::Index(arr.len()));
+            let val = seq.next_element_seed(ValueSeed(&mut *state));
+            state.path.pop();
+            match val? {
+                Some(val) => arr.push(val),
+                None => return Ok(Value::Array(arr)),
+            }
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let state = self.0;
+        let mut obj = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            // numbers reach the visitor as a map with a single private key when their text is kept
+            #[cfg(feature = "arbitrary_precision")]
+            if obj.is_empty() && key == "$serde_json::private::Number" {
+                let text: String = map.next_value()?;
+                return text.parse().map(Value::Number).map_err(de::Error::custom);
+            }
+
+            let duplicate = obj.contains_key(&key);
+            state.path.push(ParsedSegment::Key(key.clone()));
+            let val = map.next_value_seed(ValueSeed(&mut *state));
+            let val = match (duplicate, state.policy, val) {
+                (_, _, Err(err)) => Err(err),
+                (true, DuplicateKeyPolicy::Error, _) => {
+                    let path = state.style.finish_path(state.element(Value::Null).path);
+                    Err(de::Error::custom(format!("duplicate key at path {}", path)))
+                }
+                (true, DuplicateKeyPolicy::YieldBoth, Ok(val)) => {
+                    let element = state.element(val);
+                    state.duplicates.push(element);
+                    Ok(None)
+                }
+                (true, DuplicateKeyPolicy::FirstWins, Ok(_)) => Ok(None),
+                (_, _, Ok(val)) => Ok(Some(val)),
+            };
+            state.path.pop();
+            if let Some(val) = val? {
+                obj.insert(key, val);
+            }
+        }
+        Ok(Value::Object(obj))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn empty_documents_are_still_counted() {
+        let lines = "[]\n[1]\n";
+        let items: Vec<_> = StreamIter::new(lines.as_bytes())
+            .map(|el| el.unwrap())
+            .collect();
+
+        assert_eq!(
+            items,
+            vec![DocumentElement {
+                document: 1,
+                element: OwnedElement {
+                    path: "[0]".into(),
+                    indices: vec![0],
+                    value: json!(1),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn first_wins_keeps_nested_duplicates_out() {
+        let lines = "{\"a\": {\"b\": 1}, \"a\": {\"c\": 2}} [{\"d\": 3, \"d\": 4}]";
+        let items: Vec<_> = StreamIter::new(lines.as_bytes())
+            .duplicate_keys(DuplicateKeyPolicy::FirstWins)
+            .map(|el| el.unwrap().element.path)
+            .collect();
+
+        assert_eq!(items, vec!["[\"a\"][\"b\"]", "[0][\"d\"]"]);
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn keeps_every_digit_of_numbers() {
+        let lines = "{\"a\": 0.1000000000000000000001, \"a\": 12345678901234567890123}";
+        let item = StreamIter::new(lines.as_bytes())
+            .duplicate_keys(DuplicateKeyPolicy::FirstWins)
+            .next()
+            .unwrap()
+            .unwrap();
+
+        match item.element.value {
+            Value::Number(n) => assert_eq!(n.as_str(), "0.1000000000000000000001"),
+            other => panic!("expected a number, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stops_after_a_parse_error() {
+        let lines = "{\"a\": 1}\n{\"b\": \n{\"c\": 3}\n";
+        let items: Vec<_> = StreamIter::new(lines.as_bytes()).collect();
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_ok());
+        assert!(items[1].is_err());
+    }
+}
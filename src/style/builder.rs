@@ -1,28 +1,51 @@
 use super::*;
+use serde::de::{self, MapAccess, Visitor};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+/// Largest index base that `try_build` accepts, since no array index exceeds `isize::MAX`
+/// and so adding one to it always fits in a `usize`
+pub(crate) const MAX_INDEX_BASE: usize = isize::MAX as usize;
 
 /// Builder to customise path styling
+#[derive(Debug, Clone, Default)]
 pub struct StyleBuilder<'a> {
-    object_key_prefix: Option<&'a str>,
-    object_key_suffix: Option<&'a str>,
+    object_key_prefix: Option<Cow<'a, str>>,
+    object_key_suffix: Option<Cow<'a, str>>,
     object_keys_in_path: Option<bool>,
     skip_object_parents: Option<bool>,
-    array_key_prefix: Option<&'a str>,
-    array_key_suffix: Option<&'a str>,
-    array_keys_in_path: Option<bool>,
+    array_key_prefix: Option<Cow<'a, str>>,
+    array_key_suffix: Option<Cow<'a, str>>,
+    array_keys_in_path: Option<IndexFormat>,
     skip_array_parents: Option<bool>,
+    index_base: Option<usize>,
+    path_prefix: Option<Cow<'a, str>>,
+    path_suffix: Option<Cow<'a, str>>,
+    key_escape: Option<KeyEscape>,
+    ascii_keys: Option<bool>,
 }
 
 impl<'a> StyleBuilder<'a> {
     pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a builder with every option taken from an existing style
+    fn from_style(style: &Style<'a>) -> Self {
         StyleBuilder {
-            object_key_prefix: None,
-            object_key_suffix: None,
-            object_keys_in_path: None,
-            skip_object_parents: None,
-            array_key_prefix: None,
-            array_key_suffix: None,
-            array_keys_in_path: None,
-            skip_array_parents: None,
+            object_key_prefix: Some(style.object_key_prefix.clone()),
+            object_key_suffix: Some(style.object_key_suffix.clone()),
+            object_keys_in_path: Some(style.object_keys_in_path),
+            skip_object_parents: Some(style.skip_object_parents),
+            array_key_prefix: Some(style.array_key_prefix.clone()),
+            array_key_suffix: Some(style.array_key_suffix.clone()),
+            array_keys_in_path: Some(style.array_keys_in_path),
+            skip_array_parents: Some(style.skip_array_parents),
+            index_base: Some(style.index_base),
+            path_prefix: Some(style.path_prefix.clone()),
+            path_suffix: Some(style.path_suffix.clone()),
+            key_escape: style.key_escape,
+            ascii_keys: Some(style.ascii_keys),
         }
     }
 
@@ -45,8 +68,8 @@ impl<'a> StyleBuilder<'a> {
     ///
     /// assert_eq!(items[0], Element { path: ">>>apple\"][0]".into(), indices: vec![0], value: &json!(1), });
     /// ```
-    pub fn object_key_prefix(mut self, value: &'a str) -> Self {
-        self.object_key_prefix = Some(value);
+    pub fn object_key_prefix(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.object_key_prefix = Some(value.into());
         self
     }
 
@@ -69,8 +92,8 @@ impl<'a> StyleBuilder<'a> {
     ///
     /// assert_eq!(items[0], Element { path: "[\"apple$$$[0]".into(), indices: vec![0], value: &json!(1), });
     /// ```
-    pub fn object_key_suffix(mut self, value: &'a str) -> Self {
-        self.object_key_suffix = Some(value);
+    pub fn object_key_suffix(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.object_key_suffix = Some(value.into());
         self
     }
 
@@ -177,8 +200,8 @@ impl<'a> StyleBuilder<'a> {
     ///
     /// assert_eq!(items[0], Element { path: "[\"apple\"]:::0]".into(), indices: vec![0], value: &json!(1), });
     /// ```
-    pub fn array_key_prefix(mut self, value: &'a str) -> Self {
-        self.array_key_prefix = Some(value);
+    pub fn array_key_prefix(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.array_key_prefix = Some(value.into());
         self
     }
 
@@ -201,12 +224,12 @@ impl<'a> StyleBuilder<'a> {
     ///
     /// assert_eq!(items[0], Element { path: "[\"apple\"][0!!!".into(), indices: vec![0], value: &json!(1), });
     /// ```
-    pub fn array_key_suffix(mut self, value: &'a str) -> Self {
-        self.array_key_suffix = Some(value);
+    pub fn array_key_suffix(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.array_key_suffix = Some(value.into());
         self
     }
 
-    /// Clears whether to show or hide array key values in the Element path
+    /// Clears whether to show, hide, or wildcard array key values in the Element path
     pub fn default_array_keys_in_path(mut self) -> Self {
         self.array_keys_in_path = None;
         self
@@ -226,7 +249,7 @@ impl<'a> StyleBuilder<'a> {
     /// assert_eq!(items[0], Element { path: "[\"apple\"][0]".into(), indices: vec![0], value: &json!(1), });
     /// ```
     pub fn show_array_keys_in_path(mut self) -> Self {
-        self.array_keys_in_path = Some(true);
+        self.array_keys_in_path = Some(IndexFormat::Show);
         self
     }
     /// Sets the array key values to be hidden in the Element path
@@ -244,7 +267,26 @@ impl<'a> StyleBuilder<'a> {
     /// assert_eq!(items[0], Element { path: "[\"apple\"][]".into(), indices: vec![0], value: &json!(1), });
     /// ```
     pub fn hide_array_keys_in_path(mut self) -> Self {
-        self.array_keys_in_path = Some(false);
+        self.array_keys_in_path = Some(IndexFormat::Hide);
+        self
+    }
+    /// Sets the array key values to be replaced by a `*` wildcard in the Element path,
+    /// so that paths to the same field of different array entries are identical
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Style, StyleBuilder, Iterator, Element};
+    ///
+    /// let style: Style = StyleBuilder::new()
+    ///     .wildcard_array_keys_in_path()
+    ///     .build();
+    /// let value = json!({"apple": [1, true, "three"]});
+    /// let iter = Iterator::new(&value).use_style(style);
+    /// let items: Vec<_> = iter.collect();
+    ///
+    /// assert_eq!(items[2], Element { path: "[\"apple\"][*]".into(), indices: vec![2], value: &json!("three"), });
+    /// ```
+    pub fn wildcard_array_keys_in_path(mut self) -> Self {
+        self.array_keys_in_path = Some(IndexFormat::Wildcard);
         self
     }
 
@@ -290,17 +332,392 @@ impl<'a> StyleBuilder<'a> {
         self
     }
 
+    /// Clears the number that the first array index is shown as
+    pub fn default_index_base(mut self) -> Self {
+        self.index_base = None;
+        self
+    }
+    /// Sets the number that the first array index is shown as in the Element path,
+    /// without changing the zero-based `indices` of the Element
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Style, StyleBuilder, Iterator, Element};
+    ///
+    /// let style: Style = StyleBuilder::new()
+    ///     .index_base(1)
+    ///     .build();
+    /// let value = json!({"apple": [1, true, "three"]});
+    /// let iter = Iterator::new(&value).use_style(style);
+    /// let items: Vec<_> = iter.collect();
+    ///
+    /// assert_eq!(items[0], Element { path: "[\"apple\"][1]".into(), indices: vec![0], value: &json!(1), });
+    /// ```
+    pub fn index_base(mut self, value: usize) -> Self {
+        self.index_base = Some(value);
+        self
+    }
+    /// Shows array indices counting from `0`, the same as `index_base(0)`
+    pub fn zero_based_indices(self) -> Self {
+        self.index_base(0)
+    }
+    /// Shows array indices counting from `1`, the same as `index_base(1)`
+    pub fn one_based_indices(self) -> Self {
+        self.index_base(1)
+    }
+
+    /// Clears the currently specified whole-path prefix value
+    pub fn default_path_prefix(mut self) -> Self {
+        self.path_prefix = None;
+        self
+    }
+    /// Sets a prefix added once to the front of every Element path, including the base of the json structure
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{PresetStyle, Style, Iterator, Element};
+    ///
+    /// let style: Style = PresetStyle::CommonJs.builder()
+    ///     .path_prefix("data")
+    ///     .build();
+    /// let value = json!({"apple": [1, true, "three"]});
+    /// let iter = Iterator::new(&value).use_style(style);
+    /// let items: Vec<_> = iter.collect();
+    ///
+    /// assert_eq!(items[0], Element { path: "data.apple[0]".into(), indices: vec![0], value: &json!(1), });
+    /// ```
+    pub fn path_prefix(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.path_prefix = Some(value.into());
+        self
+    }
+
+    /// Clears the currently specified whole-path suffix value
+    pub fn default_path_suffix(mut self) -> Self {
+        self.path_suffix = None;
+        self
+    }
+    /// Sets a suffix added once to the end of every Element path, including the base of the json structure
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{PresetStyle, Style, Iterator, Element};
+    ///
+    /// let style: Style = PresetStyle::CommonJs.builder()
+    ///     .path_prefix("'$")
+    ///     .path_suffix("'")
+    ///     .build();
+    /// let value = json!({"apple": [1, true, "three"]});
+    /// let iter = Iterator::new(&value).use_style(style);
+    /// let items: Vec<_> = iter.collect();
+    ///
+    /// assert_eq!(items[0], Element { path: "'$.apple[0]'".into(), indices: vec![0], value: &json!(1), });
+    /// ```
+    pub fn path_suffix(mut self, value: impl Into<Cow<'a, str>>) -> Self {
+        self.path_suffix = Some(value.into());
+        self
+    }
+
+    /// Clears the escape function, so object keys are formatted as they are
+    pub fn default_key_escape(mut self) -> Self {
+        self.key_escape = None;
+        self
+    }
+    /// Sets a function that every object key is passed through before being formatted into the Element path
+    ///
+    /// Keys parsed back out of a path with `Style::parse_path` are left escaped.
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// use serde_json::json;
+    /// use json_keypath_iter::{PresetStyle, Style, Iterator, Element};
+    ///
+    /// fn quote(key: &str) -> Cow<str> {
+    ///     match key.contains('\'') {
+    ///         true => Cow::Owned(key.replace('\'', "''")),
+    ///         false => Cow::Borrowed(key),
+    ///     }
+    /// }
+    ///
+    /// let style: Style = PresetStyle::PostgresJson.builder()
+    ///     .key_escape(quote)
+    ///     .build();
+    /// let value = json!({"o'clock": [1, true, "three"]});
+    /// let iter = Iterator::new(&value).use_style(style);
+    /// let items: Vec<_> = iter.collect();
+    ///
+    /// assert_eq!(items[0], Element { path: "->'o''clock'->0".into(), indices: vec![0], value: &json!(1), });
+    /// ```
+    pub fn key_escape(mut self, escape: KeyEscape) -> Self {
+        self.key_escape = Some(escape);
+        self
+    }
+
+    /// Clears the currently specified ascii keys value, so object keys are formatted with any unicode they contain
+    pub fn default_ascii_keys(mut self) -> Self {
+        self.ascii_keys = None;
+        self
+    }
+    /// Sets object keys to be `\u`-escaped in the Element path wherever they hold non-ascii or control characters
+    ///
+    /// Characters outside the basic multilingual plane become a surrogate pair as in JSON, and `\` becomes `\\`.
+    /// This runs after any `key_escape` function, and only the path changes: `Element.value` and keys seen
+    /// through other means are left as they are. `Style::parse_path` does not decode the escapes,
+    /// so `Style::is_ambiguous_key` reports every key they change.
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{PresetStyle, Style, Iterator};
+    ///
+    /// let style: Style = PresetStyle::CommonJs.builder()
+    ///     .ascii_keys()
+    ///     .build();
+    /// let value = json!({"café": {"a\\b": 3, "tab\t": 1, "😀": 2}});
+    /// let paths: Vec<_> = Iterator::new(&value).use_style(style).map(|el| el.path).collect();
+    ///
+    /// assert_eq!(paths, vec![
+    ///     ".caf\\u00e9.a\\\\b",
+    ///     ".caf\\u00e9.tab\\u0009",
+    ///     ".caf\\u00e9.\\ud83d\\ude00",
+    /// ]);
+    /// ```
+    pub fn ascii_keys(mut self) -> Self {
+        self.ascii_keys = Some(true);
+        self
+    }
+    /// Sets object keys to be formatted with any unicode they contain, which is the default
+    pub fn unicode_keys(mut self) -> Self {
+        self.ascii_keys = Some(false);
+        self
+    }
+
+    /// Sets the array key values to be hidden in the Element path, the same as `hide_array_keys_in_path`
+    pub fn hide_indices_in_path(self) -> Self {
+        self.hide_array_keys_in_path()
+    }
+    /// Sets the array key values to be visible in the Element path, the same as `show_array_keys_in_path`
+    pub fn show_indices_in_path(self) -> Self {
+        self.show_array_keys_in_path()
+    }
+
+    /// Prevents values that are objects or arrays from being yielded by the Iterator,
+    /// the same as `skip_object_parents` and `skip_array_parents` together
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Style, StyleBuilder, Iterator};
+    ///
+    /// let style: Style = StyleBuilder::new()
+    ///     .include_parents()
+    ///     .skip_parents()
+    ///     .build();
+    /// let value = json!({"apple": [1, true, "three"]});
+    ///
+    /// assert_eq!(Iterator::new(&value).use_style(style).count(), 3);
+    /// ```
+    pub fn skip_parents(self) -> Self {
+        self.skip_object_parents().skip_array_parents()
+    }
+    /// Sets values that are objects or arrays to be yielded by the Iterator,
+    /// the same as `include_object_parents` and `include_array_parents` together
+    pub fn include_parents(self) -> Self {
+        self.include_object_parents().include_array_parents()
+    }
+
     /// Builds a value Style with defaults for any value not specified or previously cleared out
     pub fn build(&self) -> Style<'a> {
         Style {
-            object_key_prefix: self.object_key_prefix.unwrap_or("[\""),
-            object_key_suffix: self.object_key_suffix.unwrap_or("\"]"),
+            object_key_prefix: self
+                .object_key_prefix
+                .clone()
+                .unwrap_or(Cow::Borrowed("[\"")),
+            object_key_suffix: self
+                .object_key_suffix
+                .clone()
+                .unwrap_or(Cow::Borrowed("\"]")),
             object_keys_in_path: self.object_keys_in_path.unwrap_or(true),
             skip_object_parents: self.skip_object_parents.unwrap_or(true),
-            array_key_prefix: self.array_key_prefix.unwrap_or("["),
-            array_key_suffix: self.array_key_suffix.unwrap_or("]"),
-            array_keys_in_path: self.array_keys_in_path.unwrap_or(true),
+            array_key_prefix: self.array_key_prefix.clone().unwrap_or(Cow::Borrowed("[")),
+            array_key_suffix: self.array_key_suffix.clone().unwrap_or(Cow::Borrowed("]")),
+            array_keys_in_path: self.array_keys_in_path.unwrap_or(IndexFormat::Show),
             skip_array_parents: self.skip_array_parents.unwrap_or(true),
+            index_base: self.index_base.unwrap_or(0),
+            path_prefix: self.path_prefix.clone().unwrap_or(Cow::Borrowed("")),
+            path_suffix: self.path_suffix.clone().unwrap_or(Cow::Borrowed("")),
+            key_escape: self.key_escape,
+            ascii_keys: self.ascii_keys.unwrap_or(false),
+        }
+    }
+
+    /// Builds a Style like `build`, but first rejects combinations of options that make paths ambiguous,
+    /// and an index base larger than `isize::MAX`
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{StyleBuilder, StyleError};
+    ///
+    /// assert!(StyleBuilder::new().try_build().is_ok());
+    /// assert_eq!(
+    ///     StyleBuilder::new().array_key_prefix("").array_key_suffix("").try_build().unwrap_err(),
+    ///     StyleError::EmptyDelimiter { kind: "array" },
+    /// );
+    /// assert_eq!(
+    ///     StyleBuilder::new().object_key_prefix("/").object_key_suffix("").array_key_prefix("/").array_key_suffix("").try_build().unwrap_err(),
+    ///     StyleError::AmbiguousFormat,
+    /// );
+    /// ```
+    pub fn try_build(&self) -> Result<Style<'a>, StyleError> {
+        let style = self.build();
+        if style.index_base > MAX_INDEX_BASE {
+            return Err(StyleError::IndexBaseTooLarge {
+                base: style.index_base,
+            });
+        }
+        let same_affixes = style.object_key_prefix == style.array_key_prefix
+            && style.object_key_suffix == style.array_key_suffix;
+
+        if style.object_keys_in_path
+            && style.object_key_prefix.is_empty()
+            && style.object_key_suffix.is_empty()
+        {
+            return Err(StyleError::EmptyDelimiter { kind: "object" });
+        }
+        if style.array_keys_in_path == IndexFormat::Show
+            && style.array_key_prefix.is_empty()
+            && style.array_key_suffix.is_empty()
+        {
+            return Err(StyleError::EmptyDelimiter { kind: "array" });
+        }
+        if same_affixes
+            && style.object_keys_in_path
+            && style.array_keys_in_path == IndexFormat::Show
+        {
+            return Err(StyleError::AmbiguousFormat);
+        }
+        if same_affixes
+            && !style.object_keys_in_path
+            && style.array_keys_in_path != IndexFormat::Show
+        {
+            return Err(StyleError::ConflictingOptions {
+                message: "object keys and array indices are both hidden behind identical affixes",
+            });
         }
+
+        Ok(style)
+    }
+}
+
+/// Starts a builder with every option taken from an existing style, so it can be tweaked
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{PresetStyle, Style, StyleBuilder, Iterator};
+///
+/// let base: Style = PresetStyle::CommonJs.into();
+/// let style = StyleBuilder::from(&base).include_array_parents().build();
+/// let value = json!({"a": [1]});
+/// let paths: Vec<_> = Iterator::new(&value).use_style(style).map(|el| el.path).collect();
+///
+/// assert_eq!(paths, vec![".a", ".a[0]"]);
+/// ```
+impl<'a> From<&Style<'a>> for StyleBuilder<'a> {
+    fn from(style: &Style<'a>) -> Self {
+        StyleBuilder::from_style(style)
+    }
+}
+
+impl<'a> From<Style<'a>> for StyleBuilder<'a> {
+    fn from(style: Style<'a>) -> Self {
+        StyleBuilder::from_style(&style)
+    }
+}
+
+/// Names of the options read by `StyleBuilder`'s `Deserialize` implementation, as written by `Style`'s `Serialize`
+const FIELDS: &[&str] = &[
+    "object_key_prefix",
+    "object_key_suffix",
+    "object_keys_in_path",
+    "skip_object_parents",
+    "array_key_prefix",
+    "array_key_suffix",
+    "array_keys_in_path",
+    "skip_array_parents",
+    "index_base",
+    "path_prefix",
+    "path_suffix",
+    "ascii_keys",
+];
+
+/// Reads the options written by `Style`'s `Serialize` implementation, leaving any that are missing unset
+///
+/// The key escape function cannot be serialized, so it is never set. Unknown options are an error.
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::StyleBuilder;
+///
+/// let builder: StyleBuilder = serde_json::from_str(r#"{"object_key_prefix": ".", "object_key_suffix": ""}"#).unwrap();
+///
+/// assert_eq!(builder.build().object_format("", "a"), ".a");
+/// assert!(serde_json::from_str::<StyleBuilder>(r#"{"array_keys_in_path": "sometimes"}"#).is_err());
+/// ```
+impl<'de, 'a> Deserialize<'de> for StyleBuilder<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BuilderVisitor<'a>(PhantomData<StyleBuilder<'a>>);
+
+        impl<'de, 'a> Visitor<'de> for BuilderVisitor<'a> {
+            type Value = StyleBuilder<'a>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of style options")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut builder = StyleBuilder::new();
+                while let Some(field) = map.next_key::<String>()? {
+                    match field.as_str() {
+                        "object_key_prefix" => {
+                            builder.object_key_prefix = Some(Cow::Owned(map.next_value()?))
+                        }
+                        "object_key_suffix" => {
+                            builder.object_key_suffix = Some(Cow::Owned(map.next_value()?))
+                        }
+                        "object_keys_in_path" => {
+                            builder.object_keys_in_path = Some(map.next_value()?)
+                        }
+                        "skip_object_parents" => {
+                            builder.skip_object_parents = Some(map.next_value()?)
+                        }
+                        "array_key_prefix" => {
+                            builder.array_key_prefix = Some(Cow::Owned(map.next_value()?))
+                        }
+                        "array_key_suffix" => {
+                            builder.array_key_suffix = Some(Cow::Owned(map.next_value()?))
+                        }
+                        "array_keys_in_path" => {
+                            let name: String = map.next_value()?;
+                            let format = IndexFormat::from_name(&name).ok_or_else(|| {
+                                de::Error::unknown_variant(&name, &["show", "hide", "wildcard"])
+                            })?;
+                            builder.array_keys_in_path = Some(format);
+                        }
+                        "skip_array_parents" => {
+                            builder.skip_array_parents = Some(map.next_value()?)
+                        }
+                        "index_base" => {
+                            let base: usize = map.next_value()?;
+                            if base > MAX_INDEX_BASE {
+                                return Err(de::Error::invalid_value(
+                                    de::Unexpected::Unsigned(base as u64),
+                                    &"an index base no larger than isize::MAX",
+                                ));
+                            }
+                            builder.index_base = Some(base);
+                        }
+                        "path_prefix" => builder.path_prefix = Some(Cow::Owned(map.next_value()?)),
+                        "path_suffix" => builder.path_suffix = Some(Cow::Owned(map.next_value()?)),
+                        "ascii_keys" => builder.ascii_keys = Some(map.next_value()?),
+                        other => return Err(de::Error::unknown_field(other, FIELDS)),
+                    }
+                }
+                Ok(builder)
+            }
+        }
+
+        deserializer.deserialize_struct("Style", FIELDS, BuilderVisitor(PhantomData))
     }
 }
@@ -0,0 +1,62 @@
+use std::fmt;
+
+/// Error describing why `StyleBuilder::try_build` rejected a combination of options, or a template could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleError {
+    /// Options that cannot be used together, such as hiding keys and indices with identical affixes
+    ConflictingOptions {
+        /// Short description of the conflict
+        message: &'static str,
+    },
+    /// Keys or indices are shown without any prefix or suffix, so neighbouring segments run together
+    EmptyDelimiter {
+        /// Either `"object"` or `"array"`
+        kind: &'static str,
+    },
+    /// Object keys and array indices are formatted identically, so a key such as `"0"` cannot be told apart from an index
+    AmbiguousFormat,
+    /// The index base is so large that adding an array index to it could overflow a `usize`
+    IndexBaseTooLarge {
+        /// The rejected index base
+        base: usize,
+    },
+    /// A template passed to `object_template` or `array_template` could not be parsed
+    InvalidTemplate {
+        /// The full template that failed to parse
+        template: String,
+        /// Byte offset into the template where parsing failed
+        position: usize,
+        /// Short description of the problem
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for StyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleError::ConflictingOptions { message } => {
+                write!(f, "conflicting style options: {}", message)
+            }
+            StyleError::EmptyDelimiter { kind } => {
+                write!(f, "{} segments are shown without a prefix or suffix", kind)
+            }
+            StyleError::AmbiguousFormat => {
+                write!(f, "object keys and array indices are formatted identically")
+            }
+            StyleError::IndexBaseTooLarge { base } => {
+                write!(f, "index base {} is larger than {}", base, isize::MAX)
+            }
+            StyleError::InvalidTemplate {
+                template,
+                position,
+                message,
+            } => write!(
+                f,
+                "invalid style template {:?} at position {}: {}",
+                template, position, message,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StyleError {}
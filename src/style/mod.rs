@@ -1,51 +1,188 @@
 mod builder;
+mod error;
+mod parse;
 mod preset;
+pub mod styles;
+mod template;
 pub use builder::StyleBuilder;
-pub use preset::PresetStyle;
+pub use error::StyleError;
+pub use preset::{PresetStyle, UnknownPresetError};
+
+use crate::path::PathSegment;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+
+/// How array indices are rendered between the array key prefix and suffix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum IndexFormat {
+    Show,
+    Hide,
+    Wildcard,
+}
+
+impl IndexFormat {
+    fn name(self) -> &'static str {
+        match self {
+            IndexFormat::Show => "show",
+            IndexFormat::Hide => "hide",
+            IndexFormat::Wildcard => "wildcard",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "show" => Some(IndexFormat::Show),
+            "hide" => Some(IndexFormat::Hide),
+            "wildcard" => Some(IndexFormat::Wildcard),
+            _ => None,
+        }
+    }
+}
+
+/// Function applied to each object key before it is formatted into a path, see `StyleBuilder::key_escape`
+pub type KeyEscape = fn(&str) -> Cow<'_, str>;
 
 /// Used by `Iterator` to format `Element.path` and
 /// determine whether or not to yield object and/or array values
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Style<'a> {
-    object_key_prefix: &'a str,
-    object_key_suffix: &'a str,
+    object_key_prefix: Cow<'a, str>,
+    object_key_suffix: Cow<'a, str>,
     object_keys_in_path: bool,
     skip_object_parents: bool,
-    array_key_prefix: &'a str,
-    array_key_suffix: &'a str,
-    array_keys_in_path: bool,
+    array_key_prefix: Cow<'a, str>,
+    array_key_suffix: Cow<'a, str>,
+    array_keys_in_path: IndexFormat,
     skip_array_parents: bool,
+    index_base: usize,
+    path_prefix: Cow<'a, str>,
+    path_suffix: Cow<'a, str>,
+    key_escape: Option<KeyEscape>,
+    ascii_keys: bool,
 }
 
 impl<'a> Style<'a> {
-    pub fn object_format(&self, base_path: &String, key: &String) -> String {
+    pub fn object_format(&self, base_path: &str, key: &str) -> String {
+        let key = self.escape_key(key);
+        let mut path = String::with_capacity(
+            base_path.len()
+                + self.object_key_prefix.len()
+                + key.len()
+                + self.object_key_suffix.len(),
+        );
+        path.push_str(base_path);
+        self.push_escaped_object_segment(&mut path, &key);
+        path
+    }
+
+    pub fn array_format(&self, base_path: &str, index: usize) -> String {
+        let mut path = String::with_capacity(
+            base_path.len()
+                + self.array_key_prefix.len()
+                + decimal_len(index as u128 + self.index_base as u128)
+                + self.array_key_suffix.len(),
+        );
+        path.push_str(base_path);
+        self.push_array_segment(&mut path, index);
+        path
+    }
+
+    /// Appends the formatted segment for an object key onto an existing path buffer
+    pub(crate) fn push_object_segment(&self, path: &mut String, key: &str) {
+        self.push_escaped_object_segment(path, &self.escape_key(key));
+    }
+
+    /// Appends the formatted segment for a key that has already been through `escape_key`,
+    /// or that should not be escaped such as a glob wildcard
+    pub(crate) fn push_escaped_object_segment(&self, path: &mut String, key: &str) {
+        path.push_str(&self.object_key_prefix);
         if self.object_keys_in_path {
-            format!(
-                "{}{}{}{}",
-                base_path, self.object_key_prefix, key, self.object_key_suffix,
-            )
-        } else {
-            format!(
-                "{}{}{}",
-                base_path, self.object_key_prefix, self.object_key_suffix,
-            )
+            path.push_str(key);
         }
+        path.push_str(&self.object_key_suffix);
     }
 
-    pub fn array_format(&self, base_path: &String, index: usize) -> String {
-        if self.array_keys_in_path {
-            format!(
-                "{}{}{}{}",
-                base_path, self.array_key_prefix, index, self.array_key_suffix,
-            )
-        } else {
-            format!(
-                "{}{}{}",
-                base_path, self.array_key_prefix, self.array_key_suffix,
-            )
+    /// Runs an object key through the style's escape function, if it has one and keys are shown,
+    /// and then `\u`-escapes it when the style keeps paths to printable ascii
+    fn escape_key<'k>(&self, key: &'k str) -> Cow<'k, str> {
+        let key = match self.key_escape {
+            Some(escape) if self.object_keys_in_path => escape(key),
+            _ => Cow::Borrowed(key),
+        };
+        match key {
+            Cow::Borrowed(key) if self.ascii_keys => escape_non_ascii(key),
+            Cow::Owned(key) if self.ascii_keys => Cow::Owned(escape_non_ascii(&key).into_owned()),
+            key => key,
         }
     }
 
+    /// Whether the key, once escaped, could be misread when parsing a path in this style
+    ///
+    /// That is when its formatted segment does not parse back, with `parse_path`, as that one key: when it would be
+    /// split on the style's delimiters, or read as an array index. Keys are never ambiguous when the style
+    /// leaves them out of paths, and the segment is parsed as though the style showed array indices.
+    /// With `ascii_keys`, any key the `\u` escaping changes is ambiguous too, since `parse_path` does not decode it.
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{PresetStyle, Style};
+    ///
+    /// let brackets: Style = PresetStyle::SquareBrackets.into();
+    /// let common_js: Style = PresetStyle::CommonJs.into();
+    /// let slashes: Style = PresetStyle::Slashes.into();
+    ///
+    /// assert!(brackets.is_ambiguous_key("x\"][\"y"));
+    /// assert!(!brackets.is_ambiguous_key("a[0]"));
+    /// assert!(common_js.is_ambiguous_key("a.b"));
+    /// assert!(common_js.is_ambiguous_key("tags[0]"));
+    /// assert!(!common_js.is_ambiguous_key("a_b"));
+    /// assert!(slashes.is_ambiguous_key("0"));
+    /// assert!(slashes.is_ambiguous_key("a/b"));
+    /// assert!(!slashes.is_ambiguous_key("a.b"));
+    /// ```
+    pub fn is_ambiguous_key(&self, key: &str) -> bool {
+        if !self.object_keys_in_path {
+            return false;
+        }
+        let key = self.escape_key(key);
+        // every `ascii_keys` escape, including the one for `\` itself, starts with a backslash
+        if self.ascii_keys && key.contains('\\') {
+            return true;
+        }
+        let mut path = String::new();
+        self.push_escaped_object_segment(&mut path, &key);
+
+        match self.parse_segments(&path, 0) {
+            Ok(segments) => segments != [PathSegment::Key(&key)],
+            Err(_) => true,
+        }
+    }
+
+    /// Appends the formatted segment for an array index onto an existing path buffer
+    pub(crate) fn push_array_segment(&self, path: &mut String, index: usize) {
+        use std::fmt::Write;
+
+        path.push_str(&self.array_key_prefix);
+        match self.array_keys_in_path {
+            IndexFormat::Show => {
+                // widened so that an index base set with `build` cannot overflow
+                let _ = write!(path, "{}", index as u128 + self.index_base as u128);
+            }
+            IndexFormat::Hide => {}
+            IndexFormat::Wildcard => path.push('*'),
+        }
+        path.push_str(&self.array_key_suffix);
+    }
+
+    /// Appends an array segment with text such as `*` in place of the index, whether or not indices are shown
+    pub(crate) fn push_array_placeholder(&self, path: &mut String, text: &str) {
+        path.push_str(&self.array_key_prefix);
+        path.push_str(text);
+        path.push_str(&self.array_key_suffix);
+    }
+
     pub fn should_skip_object_parents(&self) -> bool {
         self.skip_object_parents
     }
@@ -53,4 +190,300 @@ impl<'a> Style<'a> {
     pub fn should_skip_array_parents(&self) -> bool {
         self.skip_array_parents
     }
+
+    /// Wraps a path built from formatted segments in the style's whole-path prefix and suffix
+    ///
+    /// Iterators call this once per yielded element, so segments never include the wrapper.
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::StyleBuilder;
+    ///
+    /// let style = StyleBuilder::new().path_prefix("'$").path_suffix("'").build();
+    ///
+    /// assert_eq!(style.wrap_path(&style.object_format("", "a")), "'$[\"a\"]'");
+    /// ```
+    pub fn wrap_path(&self, path: &str) -> String {
+        let mut wrapped =
+            String::with_capacity(self.path_prefix.len() + path.len() + self.path_suffix.len());
+        wrapped.push_str(&self.path_prefix);
+        wrapped.push_str(path);
+        wrapped.push_str(&self.path_suffix);
+        wrapped
+    }
+
+    /// Wraps an owned path like `wrap_path`, reusing it untouched when the style has no wrapper
+    pub(crate) fn finish_path(&self, path: String) -> String {
+        match self.has_wrapper() {
+            true => self.wrap_path(&path),
+            false => path,
+        }
+    }
+
+    /// Formats a full path from its segments
+    pub(crate) fn format_segments(&self, segments: &[PathSegment]) -> String {
+        let mut path = String::new();
+        for segment in segments {
+            match segment {
+                PathSegment::Key(key) => self.push_object_segment(&mut path, key),
+                PathSegment::Index(index) => self.push_array_segment(&mut path, *index),
+            }
+        }
+        self.finish_path(path)
+    }
+
+    /// Length in bytes of the whole-path prefix and suffix
+    pub(crate) fn wrapper_len(&self) -> usize {
+        self.path_prefix.len() + self.path_suffix.len()
+    }
+
+    /// The whole-path prefix, the path, and the whole-path suffix, in the order `wrap_path` joins them
+    pub(crate) fn wrapped_pieces<'p>(&'p self, path: &'p str) -> [&'p str; 3] {
+        [&self.path_prefix, path, &self.path_suffix]
+    }
+
+    /// Whether the style adds a whole-path prefix or suffix
+    pub(crate) fn has_wrapper(&self) -> bool {
+        !self.path_prefix.is_empty() || !self.path_suffix.is_empty()
+    }
+}
+
+/// Serializes every option except the key escape function, with `array_keys_in_path` as one of `"show"`, `"hide"`, or `"wildcard"`
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{PresetStyle, Style};
+///
+/// let style: Style = PresetStyle::CommonJs.into();
+///
+/// assert_eq!(serde_json::to_value(&style).unwrap(), json!({
+///     "object_key_prefix": ".",
+///     "object_key_suffix": "",
+///     "object_keys_in_path": true,
+///     "skip_object_parents": true,
+///     "array_key_prefix": "[",
+///     "array_key_suffix": "]",
+///     "array_keys_in_path": "show",
+///     "skip_array_parents": true,
+///     "index_base": 0,
+///     "path_prefix": "",
+///     "path_suffix": "",
+///     "ascii_keys": false,
+/// }));
+/// ```
+impl<'a> Serialize for Style<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Style", 12)?;
+        state.serialize_field("object_key_prefix", &self.object_key_prefix)?;
+        state.serialize_field("object_key_suffix", &self.object_key_suffix)?;
+        state.serialize_field("object_keys_in_path", &self.object_keys_in_path)?;
+        state.serialize_field("skip_object_parents", &self.skip_object_parents)?;
+        state.serialize_field("array_key_prefix", &self.array_key_prefix)?;
+        state.serialize_field("array_key_suffix", &self.array_key_suffix)?;
+        state.serialize_field("array_keys_in_path", self.array_keys_in_path.name())?;
+        state.serialize_field("skip_array_parents", &self.skip_array_parents)?;
+        state.serialize_field("index_base", &self.index_base)?;
+        state.serialize_field("path_prefix", &self.path_prefix)?;
+        state.serialize_field("path_suffix", &self.path_suffix)?;
+        state.serialize_field("ascii_keys", &self.ascii_keys)?;
+        state.end()
+    }
+}
+
+/// Reads a style written by its `Serialize` implementation, see `StyleBuilder`'s `Deserialize` implementation
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::{PresetStyle, Style};
+///
+/// let style = PresetStyle::PostgresJson.builder().one_based_indices().ascii_keys().build();
+/// let text = serde_json::to_string(&style).unwrap();
+/// let read: Style = serde_json::from_str(&text).unwrap();
+///
+/// assert_eq!(read.to_string(), style.to_string());
+/// assert_eq!(read.object_format("", "é"), "->'\\u00e9'");
+/// ```
+impl<'de, 'a> Deserialize<'de> for Style<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StyleBuilder::deserialize(deserializer).map(|builder| builder.build())
+    }
+}
+
+/// Describes the style on a single line, for logging the formatting in effect
+///
+/// The first index is only mentioned when it is not `0`, and the whole-path wrapper only when it is set.
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::{PresetStyle, Style};
+///
+/// let style: Style = PresetStyle::PostgresJson.into();
+///
+/// assert_eq!(
+///     style.to_string(),
+///     "objects: \"->'\" key \"'\" (keys show, parents skip), arrays: \"->\" index \"\" (indices show, parents skip)",
+/// );
+/// ```
+impl<'a> fmt::Display for Style<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parents = |skip: bool| if skip { "skip" } else { "include" };
+        write!(
+            f,
+            "objects: {:?} key {:?} (keys {}, parents {}), arrays: {:?} index {:?} (indices {}{}, parents {})",
+            self.object_key_prefix,
+            self.object_key_suffix,
+            if self.object_keys_in_path { "show" } else { "hide" },
+            parents(self.skip_object_parents),
+            self.array_key_prefix,
+            self.array_key_suffix,
+            self.array_keys_in_path.name(),
+            match self.index_base {
+                0 => String::new(),
+                base => format!(" from {}", base),
+            },
+            parents(self.skip_array_parents),
+        )?;
+        if self.has_wrapper() {
+            write!(
+                f,
+                ", paths: {:?} path {:?}",
+                self.path_prefix, self.path_suffix
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `\`, control characters, and anything outside ascii as `\uXXXX` escapes, using surrogate pairs above the
+/// basic multilingual plane as JSON does, so the path stays printable ascii
+///
+/// This is one-way: `parse_path` does not decode the escapes, so an escaped key reads back in its escaped form.
+fn escape_non_ascii(key: &str) -> Cow<'_, str> {
+    use std::fmt::Write;
+
+    let needs_escape = |c: char| c == '\\' || !c.is_ascii() || c.is_ascii_control();
+    if !key.chars().any(needs_escape) {
+        return Cow::Borrowed(key);
+    }
+    let mut escaped = String::with_capacity(key.len() + 8);
+    for c in key.chars() {
+        if !needs_escape(c) {
+            escaped.push(c);
+        } else if c == '\\' {
+            escaped.push_str("\\\\");
+        } else {
+            let mut units = [0; 2];
+            for unit in c.encode_utf16(&mut units) {
+                let _ = write!(escaped, "\\u{:04x}", unit);
+            }
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Number of characters needed to print an index in decimal
+fn decimal_len(mut index: u128) -> usize {
+    let mut len = 1;
+    while index >= 10 {
+        index /= 10;
+        len += 1;
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatted_paths_are_allocated_exactly() {
+        let style: Style = PresetStyle::PostgresJson.into();
+        let path = style.object_format("->0", "key");
+        assert_eq!(path, "->0->'key'");
+        assert_eq!(path.capacity(), path.len());
+
+        for index in [0, 9, 10, 12345] {
+            let path = style.array_format("", index);
+            assert_eq!(path, format!("->{}", index));
+            assert_eq!(path.capacity(), path.len());
+        }
+    }
+
+    #[test]
+    fn huge_index_bases_do_not_overflow() {
+        let builder = StyleBuilder::new().index_base(usize::MAX);
+        let path = builder.build().array_format("", 1);
+        assert_eq!(path, format!("[{}]", usize::MAX as u128 + 1));
+        assert_eq!(path.capacity(), path.len());
+
+        assert_eq!(
+            builder.try_build().unwrap_err(),
+            StyleError::IndexBaseTooLarge { base: usize::MAX }
+        );
+        assert!(
+            serde_json::from_value::<Style>(serde_json::json!({ "index_base": usize::MAX }))
+                .is_err()
+        );
+        assert!(StyleBuilder::new()
+            .index_base(builder::MAX_INDEX_BASE)
+            .try_build()
+            .is_ok());
+    }
+
+    #[test]
+    fn ascii_keys_escape_after_the_key_escape() {
+        let style = PresetStyle::EnvVar.builder().ascii_keys().build();
+        assert_eq!(style.object_format("", "ab"), "_AB");
+        assert_eq!(style.object_format("", "é"), "__");
+
+        let style = PresetStyle::PostgresJson.builder().ascii_keys().build();
+        assert_eq!(style.object_format("", "é\\"), "->'\\u00e9\\\\'");
+        assert!(matches!(escape_non_ascii("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn ambiguous_keys_are_those_that_parse_back_differently() {
+        let brackets: Style = PresetStyle::SquareBrackets.into();
+        assert!(!brackets.is_ambiguous_key("a]"));
+        assert!(!brackets.is_ambiguous_key(""));
+
+        let ascii = PresetStyle::CommonJs.builder().ascii_keys().build();
+        assert!(ascii.is_ambiguous_key("café"));
+        assert!(ascii.is_ambiguous_key("a\\b"));
+        assert!(!ascii.is_ambiguous_key("cafe"));
+
+        let hidden = PresetStyle::CommonJs
+            .builder()
+            .hide_array_keys_in_path()
+            .build();
+        assert!(hidden.is_ambiguous_key("a[0]"));
+        assert!(!hidden.is_ambiguous_key("a[]"));
+    }
+
+    #[test]
+    fn serialized_styles_read_back_the_same() {
+        let custom = PresetStyle::CommonJs
+            .builder()
+            .wildcard_array_keys_in_path()
+            .include_object_parents()
+            .one_based_indices()
+            .path_prefix("$")
+            .ascii_keys()
+            .build();
+        for style in [
+            PresetStyle::SquareBrackets.into(),
+            PresetStyle::PostgresJson.into(),
+            PresetStyle::EnvVar
+                .builder()
+                .hide_array_keys_in_path()
+                .build(),
+            custom,
+        ] {
+            let written = serde_json::to_value(&style).unwrap();
+            let read: Style = serde_json::from_value(written.clone()).unwrap();
+
+            assert_eq!(serde_json::to_value(&read).unwrap(), written);
+        }
+    }
 }
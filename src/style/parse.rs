@@ -0,0 +1,233 @@
+use super::*;
+use crate::path::{PathParseError, PathSegment};
+
+impl<'a> Style<'a> {
+    /// Parses a path formatted with this style back into its segments
+    ///
+    /// Where a segment could be read as either an array index or an object key, such as `/0` with
+    /// identical prefixes, the array index wins. Keys are read as short as possible while still
+    /// allowing the rest of the path to parse, so keys containing the style's own delimiters may be split.
+    /// Styles that hide or wildcard keys cannot be parsed.
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{PathSegment, PresetStyle, Style};
+    ///
+    /// let style: Style = PresetStyle::PostgresJson.into();
+    /// let segments = style.parse_path("->'items'->0->'price'").unwrap();
+    ///
+    /// assert_eq!(segments, vec![PathSegment::Key("items"), PathSegment::Index(0), PathSegment::Key("price")]);
+    /// assert!(style.parse_path("->'items").is_err());
+    /// ```
+    pub fn parse_path<'p>(&self, path: &'p str) -> Result<Vec<PathSegment<'p>>, PathParseError> {
+        let error = |position: usize, message: &'static str| PathParseError {
+            path: path.into(),
+            position,
+            message,
+        };
+
+        if !self.object_keys_in_path {
+            return Err(error(0, "style hides object keys"));
+        }
+        if self.array_keys_in_path != IndexFormat::Show {
+            return Err(error(0, "style does not show array indices"));
+        }
+
+        if !path.starts_with(&*self.path_prefix) {
+            return Err(error(0, "missing path prefix"));
+        }
+        let end = match path[self.path_prefix.len()..].strip_suffix(&*self.path_suffix) {
+            Some(inner) => self.path_prefix.len() + inner.len(),
+            None => return Err(error(path.len(), "missing path suffix")),
+        };
+
+        self.parse_segments(&path[..end], self.path_prefix.len())
+            .map_err(|furthest| error(furthest, "no segment of the style matches"))
+    }
+
+    /// Segments of `path[start..]`, which holds no whole-path wrapper,
+    /// or the furthest position any attempt reached before no segment matched
+    pub(crate) fn parse_segments<'p>(
+        &self,
+        path: &'p str,
+        start: usize,
+    ) -> Result<Vec<PathSegment<'p>>, usize> {
+        let mut segments = Vec::new();
+        let mut furthest = start;
+        let mut failed = vec![false; path.len() + 1];
+        match self.parse_from(path, start, &mut segments, &mut furthest, &mut failed) {
+            true => Ok(segments),
+            false => Err(furthest),
+        }
+    }
+
+    /// Backtracking parse of `path[position..]`, trying an array index before each possible object key
+    ///
+    /// Whether the rest of the path parses depends only on where it starts, so positions that already failed
+    /// are remembered in `failed` and never explored twice, keeping the parse polynomial in the path length.
+    fn parse_from<'p>(
+        &self,
+        path: &'p str,
+        position: usize,
+        segments: &mut Vec<PathSegment<'p>>,
+        furthest: &mut usize,
+        failed: &mut [bool],
+    ) -> bool {
+        *furthest = (*furthest).max(position);
+        if position == path.len() {
+            return true;
+        }
+        if failed[position] {
+            return false;
+        }
+        let rest = &path[position..];
+
+        if rest.starts_with(&*self.array_key_prefix) {
+            let start = position + self.array_key_prefix.len();
+            let digits = path[start..].bytes().take_while(u8::is_ascii_digit).count();
+            let end = start + digits;
+            if digits > 0 && path[end..].starts_with(&*self.array_key_suffix) {
+                let index = path[start..end]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| index.checked_sub(self.index_base));
+                if let Some(index) = index {
+                    segments.push(PathSegment::Index(index));
+                    let next = end + self.array_key_suffix.len();
+                    if self.parse_from(path, next, segments, furthest, failed) {
+                        return true;
+                    }
+                    segments.pop();
+                }
+            }
+        }
+
+        if rest.starts_with(&*self.object_key_prefix) {
+            let start = position + self.object_key_prefix.len();
+            for (end, next) in self.key_ends(path, start) {
+                if next <= position {
+                    continue;
+                }
+                segments.push(PathSegment::Key(&path[start..end]));
+                if self.parse_from(path, next, segments, furthest, failed) {
+                    return true;
+                }
+                segments.pop();
+            }
+        }
+
+        failed[position] = true;
+        false
+    }
+
+    /// Candidate `(key end, next segment start)` offsets for a key starting at `start`, shortest first
+    fn key_ends(&self, path: &str, start: usize) -> Vec<(usize, usize)> {
+        let suffix = &*self.object_key_suffix;
+        if !suffix.is_empty() {
+            return path[start..]
+                .match_indices(suffix)
+                .map(|(offset, _)| (start + offset, start + offset + suffix.len()))
+                .collect();
+        }
+
+        // without a suffix, a key can only end where the next segment begins or at the end of the path
+        (start..=path.len())
+            .filter(|&end| path.is_char_boundary(end))
+            .filter(|&end| {
+                let rest = &path[end..];
+                rest.is_empty()
+                    || (!self.object_key_prefix.is_empty()
+                        && rest.starts_with(&*self.object_key_prefix))
+                    || (!self.array_key_prefix.is_empty()
+                        && rest.starts_with(&*self.array_key_prefix))
+            })
+            .map(|end| (end, end))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::Iter;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_every_preset() {
+        let value = json!({"a": [1, {"b.c": [true]}], "": {"0": null}});
+        for preset in [
+            PresetStyle::SquareBrackets,
+            PresetStyle::CommonJs,
+            PresetStyle::PostgresJson,
+        ] {
+            let style = StyleBuilder::from(preset).include_parents().build();
+            let paths: Vec<_> = Iter::new(&value)
+                .use_style(style.clone())
+                .map(|el| el.path)
+                .collect();
+            for path in paths {
+                let segments = style.parse_path(&path).unwrap();
+                let rebuilt = segments
+                    .iter()
+                    .fold(String::new(), |base, segment| match segment {
+                        PathSegment::Key(key) => style.object_format(&base, key),
+                        PathSegment::Index(index) => style.array_format(&base, *index),
+                    });
+                assert_eq!(rebuilt, path);
+            }
+        }
+    }
+
+    #[test]
+    fn backtracks_over_suffixes_inside_keys() {
+        let style: Style = PresetStyle::SquareBrackets.into();
+
+        assert_eq!(
+            style.parse_path("[\"a\"]b\"][0]").unwrap(),
+            vec![PathSegment::Key("a\"]b"), PathSegment::Index(0)]
+        );
+    }
+
+    #[test]
+    fn fails_long_paths_without_exploring_every_split() {
+        let style: Style = PresetStyle::SquareBrackets.into();
+        let path = "[\"a\"]".repeat(2000) + "x";
+
+        let err = style.parse_path(&path).unwrap_err();
+        assert_eq!(err.position, path.len() - 1);
+    }
+
+    #[test]
+    fn shifts_indices_by_base() {
+        let style = PresetStyle::CommonJs.builder().one_based_indices().build();
+
+        assert_eq!(
+            style.parse_path(".a[1]").unwrap(),
+            vec![PathSegment::Key("a"), PathSegment::Index(0)]
+        );
+        assert!(style.parse_path("[0]").is_err());
+    }
+
+    #[test]
+    fn strips_path_wrapper() {
+        let style = PresetStyle::CommonJs
+            .builder()
+            .path_prefix("'$")
+            .path_suffix("'")
+            .build();
+
+        assert_eq!(
+            style.parse_path("'$.a[0]'").unwrap(),
+            vec![PathSegment::Key("a"), PathSegment::Index(0)]
+        );
+        assert_eq!(style.parse_path("'$'").unwrap(), vec![]);
+        assert_eq!(style.parse_path(".a").unwrap_err().position, 0);
+    }
+
+    #[test]
+    fn rejects_lossy_styles() {
+        let style = StyleBuilder::new().hide_array_keys_in_path().build();
+
+        assert!(style.parse_path("[\"a\"][]").is_err());
+    }
+}
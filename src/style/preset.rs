@@ -1,7 +1,9 @@
 use super::*;
+use std::fmt;
+use std::str::FromStr;
 
-/// Included preset stylings
-pub enum PresetStyle {
+/// Included preset stylings, or a custom `Style` so that either can be passed wherever a preset is accepted
+pub enum PresetStyle<'a> {
     /// This yields a path that looks like: `["some_key"][123]`
     ///
     /// The Iterator also yields only non-object and non-array values with this style
@@ -14,51 +16,121 @@ pub enum PresetStyle {
     ///
     /// The Iterator also yields only non-object and non-array values with this style
     PostgresJson,
+    /// This yields a path that looks like: `/some_key/123`
+    ///
+    /// The Iterator also yields only non-object and non-array values with this style.
+    /// Paths resemble json pointers, but keys are not escaped, so one containing `/` or `~` gives a path
+    /// that is not a valid pointer and that `Style::parse_path` reads as other keys.
+    Slashes,
+    /// This yields a path that looks like: `_SOME_KEY_123`
+    ///
+    /// Keys are upper-cased with anything other than ascii letters and digits replaced by `_`,
+    /// so paths can be used as environment variable names once the leading `_` is dropped or a `path_prefix` is set.
+    /// The Iterator also yields only non-object and non-array values with this style.
+    EnvVar,
+    /// A fully custom style, usually built with `StyleBuilder`
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{PresetStyle, Style, StyleBuilder};
+    ///
+    /// let custom = PresetStyle::Custom(StyleBuilder::new().object_key_prefix("/").object_key_suffix("").build());
+    /// let style: Style = custom.into();
+    ///
+    /// assert_eq!(style.object_format("", "a"), "/a");
+    /// ```
+    Custom(Style<'a>),
 }
 
-impl<'a> From<PresetStyle> for Style<'a> {
-    fn from(style: PresetStyle) -> Style<'a> {
-        let builder: StyleBuilder<'a> = style.into();
-        builder.build()
+impl<'a> PresetStyle<'a> {
+    /// Starts a builder with every option taken from this preset
+    ///
+    /// The preset fills in every option up front, so any setter called on the returned builder
+    /// always overrides the preset rather than being replaced by it.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{PresetStyle, Iterator};
+    ///
+    /// let style = PresetStyle::PostgresJson.builder().include_parents().one_based_indices().build();
+    /// let value = json!({"a": [true]});
+    /// let paths: Vec<_> = Iterator::new(&value).use_style(style).map(|el| el.path).collect();
+    ///
+    /// assert_eq!(paths, vec!["", "->'a'", "->'a'->1"]);
+    /// ```
+    pub fn builder(self) -> StyleBuilder<'a> {
+        self.into()
+    }
+}
+
+impl<'a> From<Style<'a>> for PresetStyle<'a> {
+    fn from(style: Style<'a>) -> PresetStyle<'a> {
+        PresetStyle::Custom(style)
     }
 }
 
-impl<'a> From<PresetStyle> for StyleBuilder<'a> {
-    fn from(style: PresetStyle) -> StyleBuilder<'a> {
+impl<'a> From<PresetStyle<'a>> for Style<'a> {
+    fn from(style: PresetStyle<'a>) -> Style<'a> {
         match style {
-            PresetStyle::SquareBrackets => {
-                return StyleBuilder::new()
-                    .object_key_prefix("[\"")
-                    .object_key_suffix("\"]")
-                    .show_object_keys_in_path()
-                    .skip_object_parents()
-                    .array_key_prefix("[")
-                    .array_key_suffix("]")
-                    .show_array_keys_in_path()
-                    .skip_array_parents();
-            }
-            PresetStyle::CommonJs => {
-                return StyleBuilder::new()
-                    .object_key_prefix(".")
-                    .object_key_suffix("")
-                    .show_object_keys_in_path()
-                    .skip_object_parents()
-                    .array_key_prefix("[")
-                    .array_key_suffix("]")
-                    .show_array_keys_in_path()
-                    .skip_array_parents();
-            }
-            PresetStyle::PostgresJson => {
-                return StyleBuilder::new()
-                    .object_key_prefix("->'")
-                    .object_key_suffix("'")
-                    .show_object_keys_in_path()
-                    .skip_object_parents()
-                    .array_key_prefix("->")
-                    .array_key_suffix("")
-                    .show_array_keys_in_path()
-                    .skip_array_parents();
-            }
+            PresetStyle::SquareBrackets => styles::SQUARE_BRACKETS,
+            PresetStyle::CommonJs => styles::COMMON_JS,
+            PresetStyle::PostgresJson => styles::POSTGRES_JSON,
+            PresetStyle::Slashes => styles::SLASHES,
+            PresetStyle::EnvVar => styles::ENV_VAR,
+            PresetStyle::Custom(style) => style,
+        }
+    }
+}
+
+impl<'a> From<PresetStyle<'a>> for StyleBuilder<'a> {
+    fn from(style: PresetStyle<'a>) -> StyleBuilder<'a> {
+        Style::from(style).into()
+    }
+}
+
+/// Error returned when a string does not name one of the included presets
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownPresetError {
+    /// The name that was not recognised
+    pub name: String,
+}
+
+impl fmt::Display for UnknownPresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown style preset {:?}, expected one of square-brackets, commonjs, postgres-json, slashes, env-var",
+            self.name,
+        )
+    }
+}
+
+impl std::error::Error for UnknownPresetError {}
+
+/// Parses the name of an included preset, ignoring case and treating `_` the same as `-`
+///
+/// Example:
+/// ```rust
+/// use json_keypath_iter::{PresetStyle, Style};
+///
+/// let preset: PresetStyle = "POSTGRES_JSON".parse().unwrap();
+/// let style: Style = preset.into();
+/// assert_eq!(style.array_format("->'a'", 0), "->'a'->0");
+///
+/// assert!("xpath".parse::<PresetStyle>().is_err());
+/// ```
+impl<'a> FromStr for PresetStyle<'a> {
+    type Err = UnknownPresetError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "square-brackets" | "squarebrackets" => Ok(PresetStyle::SquareBrackets),
+            "commonjs" | "common-js" => Ok(PresetStyle::CommonJs),
+            "postgres-json" | "postgresjson" | "postgres" => Ok(PresetStyle::PostgresJson),
+            "slashes" => Ok(PresetStyle::Slashes),
+            "env-var" | "envvar" | "env" => Ok(PresetStyle::EnvVar),
+            _ => Err(UnknownPresetError { name: name.into() }),
         }
     }
 }
@@ -0,0 +1,75 @@
+//! The included presets as `const` styles, usable in statics and without building them at runtime
+//!
+//! Example:
+//! ```rust
+//! use serde_json::json;
+//! use json_keypath_iter::{styles, Iterator, Style};
+//!
+//! static STYLE: Style = styles::COMMON_JS;
+//!
+//! let value = json!({"a": [1]});
+//! let paths: Vec<_> = Iterator::new(&value).use_style(STYLE.clone()).map(|el| el.path).collect();
+//!
+//! assert_eq!(paths, vec![".a[0]"]);
+//! ```
+
+use super::{IndexFormat, KeyEscape, Style};
+use std::borrow::Cow;
+
+/// Same as `PresetStyle::SquareBrackets`, yielding paths like `["some_key"][123]`
+pub const SQUARE_BRACKETS: Style<'static> = preset("[\"", "\"]", "[", "]", None);
+
+/// Same as `PresetStyle::CommonJs`, yielding paths like `.some_key[123]`
+pub const COMMON_JS: Style<'static> = preset(".", "", "[", "]", None);
+
+/// Same as `PresetStyle::PostgresJson`, yielding paths like `->'some_key'->123`
+pub const POSTGRES_JSON: Style<'static> = preset("->'", "'", "->", "", None);
+
+/// Same as `PresetStyle::Slashes`, yielding paths like `/some_key/123`
+pub const SLASHES: Style<'static> = preset("/", "", "/", "", None);
+
+/// Same as `PresetStyle::EnvVar`, yielding paths like `_SOME_KEY_123`
+pub const ENV_VAR: Style<'static> = preset("_", "", "_", "", Some(env_var_key));
+
+/// Style showing keys, passed through the escape function, and indices between the given affixes, and yielding only non-object and non-array values
+const fn preset(
+    object_key_prefix: &'static str,
+    object_key_suffix: &'static str,
+    array_key_prefix: &'static str,
+    array_key_suffix: &'static str,
+    key_escape: Option<KeyEscape>,
+) -> Style<'static> {
+    Style {
+        object_key_prefix: Cow::Borrowed(object_key_prefix),
+        object_key_suffix: Cow::Borrowed(object_key_suffix),
+        object_keys_in_path: true,
+        skip_object_parents: true,
+        array_key_prefix: Cow::Borrowed(array_key_prefix),
+        array_key_suffix: Cow::Borrowed(array_key_suffix),
+        array_keys_in_path: IndexFormat::Show,
+        skip_array_parents: true,
+        index_base: 0,
+        path_prefix: Cow::Borrowed(""),
+        path_suffix: Cow::Borrowed(""),
+        key_escape,
+        ascii_keys: false,
+    }
+}
+
+/// Upper-cases a key and replaces anything other than ascii letters and digits with `_`
+fn env_var_key(key: &str) -> Cow<'_, str> {
+    match key
+        .bytes()
+        .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    {
+        true => Cow::Borrowed(key),
+        false => Cow::Owned(
+            key.chars()
+                .map(|c| match c.is_ascii_alphanumeric() {
+                    true => c.to_ascii_uppercase(),
+                    false => '_',
+                })
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+use std::borrow::Cow;
+
+impl<'a> StyleBuilder<'a> {
+    /// Sets the object key prefix and suffix from a template such as `['{key}']`
+    ///
+    /// The template must contain `{key}` exactly once, and literal braces are written as `{{` and `}}`.
+    /// Templates are plain strings, so they can be read straight out of a config file.
+    ///
+    /// Example:
+    /// ```rust
+    /// use json_keypath_iter::{StyleBuilder, StyleError};
+    ///
+    /// let style = StyleBuilder::new()
+    ///     .object_template("['{key}']").unwrap()
+    ///     .array_template("{{{index}}}").unwrap()
+    ///     .build();
+    ///
+    /// assert_eq!(style.object_format("", "a"), "['a']");
+    /// assert_eq!(style.array_format("['a']", 0), "['a']{0}");
+    /// assert!(matches!(
+    ///     StyleBuilder::new().object_template("[{index}]"),
+    ///     Err(StyleError::InvalidTemplate { position: 1, .. }),
+    /// ));
+    /// ```
+    pub fn object_template(self, template: &'a str) -> Result<Self, StyleError> {
+        let (prefix, suffix) = split_template(template, "key")?;
+        Ok(self.object_key_prefix(prefix).object_key_suffix(suffix))
+    }
+
+    /// Sets the array key prefix and suffix from a template such as `[{index}]`
+    ///
+    /// The template must contain `{index}` exactly once, with the same escaping as `object_template`.
+    pub fn array_template(self, template: &'a str) -> Result<Self, StyleError> {
+        let (prefix, suffix) = split_template(template, "index")?;
+        Ok(self.array_key_prefix(prefix).array_key_suffix(suffix))
+    }
+}
+
+/// Splits a template around its single `{placeholder}`, unescaping `{{` and `}}` on either side
+fn split_template<'a>(
+    template: &'a str,
+    placeholder: &str,
+) -> Result<(Cow<'a, str>, Cow<'a, str>), StyleError> {
+    let error = |position: usize, message: &'static str| StyleError::InvalidTemplate {
+        template: template.into(),
+        position,
+        message,
+    };
+
+    let bytes = template.as_bytes();
+    let mut found = None;
+    let mut position = 0;
+    while position < bytes.len() {
+        match bytes[position] {
+            b'{' if bytes.get(position + 1) == Some(&b'{') => position += 2,
+            b'}' if bytes.get(position + 1) == Some(&b'}') => position += 2,
+            b'{' => {
+                let close = template[position..]
+                    .find('}')
+                    .map(|offset| position + offset)
+                    .ok_or_else(|| error(position, "unclosed placeholder"))?;
+                if &template[position + 1..close] != placeholder {
+                    return Err(error(position, "unknown placeholder"));
+                }
+                if found.is_some() {
+                    return Err(error(position, "placeholder appears more than once"));
+                }
+                found = Some((position, close + 1));
+                position = close + 1;
+            }
+            b'}' => return Err(error(position, "unmatched `}`")),
+            _ => position += 1,
+        }
+    }
+
+    let (start, end) = found.ok_or_else(|| error(template.len(), "missing placeholder"))?;
+    Ok((unescape(&template[..start]), unescape(&template[end..])))
+}
+
+fn unescape(text: &str) -> Cow<'_, str> {
+    if text.contains("{{") || text.contains("}}") {
+        Cow::Owned(text.replace("{{", "{").replace("}}", "}"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescapes_only_when_needed() {
+        let (prefix, suffix) = split_template("->'{key}'", "key").unwrap();
+        assert!(matches!(prefix, Cow::Borrowed("->'")));
+        assert!(matches!(suffix, Cow::Borrowed("'")));
+
+        let (prefix, suffix) = split_template("{{{{{key}}}", "key").unwrap();
+        assert_eq!((&*prefix, &*suffix), ("{{", "}"));
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        let position = |template| match split_template(template, "key") {
+            Err(StyleError::InvalidTemplate { position, .. }) => position,
+            other => panic!("expected an invalid template, got {:?}", other),
+        };
+
+        assert_eq!(position("[]"), 2);
+        assert_eq!(position("{key}{key}"), 5);
+        assert_eq!(position("[{key]"), 1);
+        assert_eq!(position("{key}}"), 5);
+    }
+}
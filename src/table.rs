@@ -0,0 +1,364 @@
+use crate::iter::{Element, Iter};
+use crate::style::{PresetStyle, Style, StyleBuilder};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Flattened rows produced from an array of objects, with cells aligned to the headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// Union of flattened paths across all rows, in the order they were first seen
+    pub headers: Vec<String>,
+    /// One entry per element of the source array, each with one cell per header
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// How to fill a cell when a row does not contain one of the headers
+#[derive(Debug, Clone, PartialEq)]
+pub enum MissingPolicy {
+    /// Fill the cell with `null`
+    Null,
+    /// Fill the cell with a copy of the given value
+    Fill(Value),
+    /// Fail with `TableError::MissingValue`
+    Error,
+}
+
+/// How to handle arrays nested within each row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayPolicy {
+    /// Flatten nested arrays into one column per index
+    Flatten,
+    /// Keep each nested array whole as a single cell
+    Keep,
+}
+
+/// Options controlling how `to_table_with` builds a `Table`
+#[derive(Debug, Clone)]
+pub struct TableOptions<'a> {
+    /// Style used to format the header of each column
+    pub style: Style<'a>,
+    /// Policy for headers missing from a row
+    pub missing: MissingPolicy,
+    /// Policy for arrays nested within a row
+    pub arrays: ArrayPolicy,
+}
+
+impl<'a> Default for TableOptions<'a> {
+    fn default() -> Self {
+        TableOptions {
+            style: PresetStyle::SquareBrackets.into(),
+            missing: MissingPolicy::Null,
+            arrays: ArrayPolicy::Flatten,
+        }
+    }
+}
+
+/// Error produced while building a `Table`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableError {
+    /// The input value was not an array of rows
+    NotAnArray,
+    /// A row did not contain a header while using `MissingPolicy::Error`
+    MissingValue {
+        /// Index of the row within the source array
+        row: usize,
+        /// Header missing from the row
+        header: String,
+    },
+    /// Several leaves of a row were formatted with the same path, such as `a.b` and `a` > `b` with `PresetStyle::CommonJs`
+    DuplicatePath {
+        /// Index of the row within the source array
+        row: usize,
+        /// Header shared by the leaves
+        header: String,
+    },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::NotAnArray => write!(f, "expected an array of rows"),
+            TableError::MissingValue { row, header } => {
+                write!(f, "row {} is missing a value for {}", row, header)
+            }
+            TableError::DuplicatePath { row, header } => {
+                write!(f, "row {} has more than one value for {}", row, header)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// Flattens an array of objects into a header row plus data rows using the default `TableOptions`
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::to_table;
+///
+/// let value = json!([{"id": 1, "tags": ["a"]}, {"id": 2, "name": "two"}]);
+/// let table = to_table(&value).unwrap();
+///
+/// assert_eq!(table.headers, vec!["[\"id\"]", "[\"tags\"][0]", "[\"name\"]"]);
+/// assert_eq!(table.rows[0], vec![json!(1), json!("a"), json!(null)]);
+/// assert_eq!(table.rows[1], vec![json!(2), json!(null), json!("two")]);
+/// ```
+pub fn to_table(json: &Value) -> Result<Table, TableError> {
+    to_table_with(json, &TableOptions::default())
+}
+
+/// Flattens an array of objects into a header row plus data rows
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{to_table_with, ArrayPolicy, PresetStyle, TableOptions};
+///
+/// let value = json!([{"id": 1, "tags": ["a", "b"]}]);
+/// let options = TableOptions {
+///     style: PresetStyle::CommonJs.into(),
+///     arrays: ArrayPolicy::Keep,
+///     ..TableOptions::default()
+/// };
+/// let table = to_table_with(&value, &options).unwrap();
+///
+/// assert_eq!(table.headers, vec![".id", ".tags"]);
+/// assert_eq!(table.rows[0], vec![json!(1), json!(["a", "b"])]);
+/// ```
+pub fn to_table_with(json: &Value, options: &TableOptions) -> Result<Table, TableError> {
+    let records = json.as_array().ok_or(TableError::NotAnArray)?;
+
+    let mut headers = Vec::new();
+    let mut positions = HashMap::new();
+    let mut sparse_rows = Vec::with_capacity(records.len());
+
+    for (row, record) in records.iter().enumerate() {
+        let mut cells = HashMap::new();
+        for el in row_elements(record, options) {
+            let position = *positions.entry(el.path.clone()).or_insert_with(|| {
+                headers.push(el.path.clone());
+                headers.len() - 1
+            });
+            if cells.insert(position, el.value).is_some() {
+                return Err(TableError::DuplicatePath {
+                    row,
+                    header: el.path,
+                });
+            }
+        }
+        sparse_rows.push(cells);
+    }
+
+    let mut rows = Vec::with_capacity(sparse_rows.len());
+    for (row, mut cells) in sparse_rows.into_iter().enumerate() {
+        let mut cols = Vec::with_capacity(headers.len());
+        for (position, header) in headers.iter().enumerate() {
+            match (cells.remove(&position), &options.missing) {
+                (Some(value), _) => cols.push(value.clone()),
+                (None, MissingPolicy::Null) => cols.push(Value::Null),
+                (None, MissingPolicy::Fill(value)) => cols.push(value.clone()),
+                (None, MissingPolicy::Error) => {
+                    return Err(TableError::MissingValue {
+                        row,
+                        header: header.clone(),
+                    })
+                }
+            }
+        }
+        rows.push(cols);
+    }
+
+    Ok(Table { headers, rows })
+}
+
+/// Layout produced by `reshape`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// One flat object per element of the source array, with a field for every header of the `Table`,
+    /// returned under `records` alongside the `headers` in column order, since objects may sort their fields
+    Wide,
+    /// One object per cell present in the source, with `path`, `indices`, and `value` fields
+    Long,
+}
+
+/// Reshapes an array of objects into wide or long records using the default `TableOptions`
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{reshape, Shape};
+///
+/// let value = json!([{"id": 1, "tags": ["a"]}, {"id": 2}]);
+///
+/// assert_eq!(reshape(&value, Shape::Wide).unwrap(), json!({
+///     "headers": ["[\"id\"]", "[\"tags\"][0]"],
+///     "records": [
+///         {"[\"id\"]": 1, "[\"tags\"][0]": "a"},
+///         {"[\"id\"]": 2, "[\"tags\"][0]": null},
+///     ],
+/// }));
+/// assert_eq!(reshape(&value, Shape::Long).unwrap(), json!([
+///     {"path": "[\"id\"]", "indices": [0], "value": 1},
+///     {"path": "[\"tags\"][0]", "indices": [0, 0], "value": "a"},
+///     {"path": "[\"id\"]", "indices": [1], "value": 2},
+/// ]));
+/// ```
+pub fn reshape(json: &Value, shape: Shape) -> Result<Value, TableError> {
+    reshape_with(json, shape, &TableOptions::default())
+}
+
+/// Reshapes an array of objects into wide or long records
+///
+/// Wide records are the rows of `to_table_with` keyed by their headers, so missing cells follow the `MissingPolicy`,
+/// and come with the headers in the order of `Table.headers`, which their fields only keep with `preserve_order`.
+/// Long records only exist for cells present in the source, with paths relative to the row
+/// and the row's index in front of any nested array indices.
+pub fn reshape_with(
+    json: &Value,
+    shape: Shape,
+    options: &TableOptions,
+) -> Result<Value, TableError> {
+    match shape {
+        Shape::Wide => {
+            let Table { headers, rows } = to_table_with(json, options)?;
+            let records = rows
+                .into_iter()
+                .map(|row| Value::Object(headers.iter().cloned().zip(row).collect()))
+                .collect();
+
+            let mut wide = serde_json::Map::new();
+            wide.insert(
+                "headers".into(),
+                Value::Array(headers.into_iter().map(Value::String).collect()),
+            );
+            wide.insert("records".into(), Value::Array(records));
+            Ok(Value::Object(wide))
+        }
+        Shape::Long => {
+            let records = json.as_array().ok_or(TableError::NotAnArray)?;
+            let mut long = Vec::new();
+            for (row, record) in records.iter().enumerate() {
+                for el in row_elements(record, options) {
+                    let mut indices = Vec::with_capacity(el.indices.len() + 1);
+                    indices.push(Value::from(row));
+                    indices.extend(el.indices.into_iter().map(Value::from));
+
+                    let mut fields = serde_json::Map::new();
+                    fields.insert("path".into(), Value::String(el.path));
+                    fields.insert("indices".into(), Value::Array(indices));
+                    fields.insert("value".into(), el.value.clone());
+                    long.push(Value::Object(fields));
+                }
+            }
+            Ok(Value::Array(long))
+        }
+    }
+}
+
+/// Elements of a single row that become cells, according to the array policy
+fn row_elements<'a>(
+    record: &'a Value,
+    options: &TableOptions<'a>,
+) -> Box<dyn std::iter::Iterator<Item = Element<'a>> + 'a> {
+    match options.arrays {
+        ArrayPolicy::Flatten => {
+            let style = StyleBuilder::from(&options.style).skip_parents().build();
+            Box::new(Iter::new(record).use_style(style))
+        }
+        ArrayPolicy::Keep => {
+            let style = StyleBuilder::from(&options.style)
+                .skip_object_parents()
+                .include_array_parents()
+                .build();
+            // anything with indices lives inside an array that is already kept whole
+            Box::new(
+                Iter::new(record)
+                    .use_style(style)
+                    .filter(|el| el.indices.is_empty()),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rejects_non_arrays() {
+        assert_eq!(to_table(&json!({"a": 1})), Err(TableError::NotAnArray));
+    }
+
+    #[test]
+    fn missing_values_can_error() {
+        let value = json!([{"a": 1}, {"b": 2}]);
+        let options = TableOptions {
+            missing: MissingPolicy::Error,
+            ..TableOptions::default()
+        };
+
+        assert_eq!(
+            to_table_with(&value, &options),
+            Err(TableError::MissingValue {
+                row: 0,
+                header: "[\"b\"]".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_sharing_a_path_are_reported() {
+        let value = json!([{"a": {"b": 1}}, {"a.b": 2, "a": {"b": 3}}]);
+        let options = TableOptions {
+            style: PresetStyle::CommonJs.into(),
+            ..TableOptions::default()
+        };
+
+        assert_eq!(
+            to_table_with(&value, &options),
+            Err(TableError::DuplicatePath {
+                row: 1,
+                header: ".a.b".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn wide_records_come_with_headers_in_column_order() {
+        let value = json!([{"z": 1}, {"a": 2}]);
+
+        assert_eq!(
+            reshape(&value, Shape::Wide).unwrap()["headers"],
+            json!(["[\"z\"]", "[\"a\"]"])
+        );
+    }
+
+    #[test]
+    fn long_records_keep_whole_arrays() {
+        let value = json!([{"tags": ["a", "b"]}]);
+        let options = TableOptions {
+            arrays: ArrayPolicy::Keep,
+            ..TableOptions::default()
+        };
+
+        assert_eq!(
+            reshape_with(&value, Shape::Long, &options).unwrap(),
+            json!([{"path": "[\"tags\"]", "indices": [0], "value": ["a", "b"]}])
+        );
+    }
+
+    #[test]
+    fn missing_values_can_be_filled() {
+        let value = json!([{"a": 1}, {}]);
+        let options = TableOptions {
+            missing: MissingPolicy::Fill(json!("")),
+            ..TableOptions::default()
+        };
+        let table = to_table_with(&value, &options).unwrap();
+
+        assert_eq!(table.rows, vec![vec![json!(1)], vec![json!("")]]);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::de::unflatten;
+use crate::iter::Iter;
+use crate::style::Style;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use serde_json::{Map, Number, Value};
+
+/// Deepest nesting of objects and arrays that `arbitrary_value` generates
+const MAX_DEPTH: usize = 4;
+
+/// Most entries `arbitrary_value` puts in a single object or array
+const MAX_ENTRIES: usize = 4;
+
+/// Generates a json value from fuzzer or property-test input, to check that a custom `Style` is lossless
+///
+/// Keys are arbitrary strings, so they will include whatever delimiters a style uses. Objects and arrays are never
+/// empty, since flattening only yields leaves and an empty container has none to keep.
+/// With `proptest`, feed it bytes from `proptest::collection::vec(any::<u8>(), 0..512)`.
+///
+/// Keys are formatted without escaping their delimiters, so no style round-trips every key: with `SquareBrackets`,
+/// the key `x"]["y` reads back as two keys. The example skips values holding keys `Style::is_ambiguous_key` reports.
+///
+/// Example:
+/// ```rust
+/// use arbitrary::Unstructured;
+/// use json_keypath_iter::testing::{arbitrary_value, assert_flatten_round_trip, assert_path_round_trip};
+/// use json_keypath_iter::{AmbiguousKeys, Iterator, PresetStyle, Style};
+///
+/// let style: Style = PresetStyle::SquareBrackets.into();
+/// for seed in 0..64u8 {
+///     let bytes: Vec<u8> = (0..256u32).map(|i| (i as u8).wrapping_mul(seed).wrapping_add(seed)).collect();
+///     let value = arbitrary_value(&mut Unstructured::new(&bytes)).unwrap();
+///
+///     let mut iter = Iterator::new(&value).use_style(style.clone()).strict_keys(AmbiguousKeys::Flag);
+///     iter.by_ref().for_each(drop);
+///     if !iter.ambiguous_paths().is_empty() {
+///         continue;
+///     }
+///
+///     assert_path_round_trip(&value, &style);
+///     assert_flatten_round_trip(&value, &style);
+/// }
+/// ```
+pub fn arbitrary_value(u: &mut Unstructured<'_>) -> Result<Value> {
+    value_at(u, 0)
+}
+
+fn value_at(u: &mut Unstructured<'_>, depth: usize) -> Result<Value> {
+    let kinds = if depth < MAX_DEPTH { 6 } else { 4 };
+    Ok(match u.choose_index(kinds)? {
+        0 => Value::Null,
+        1 => Value::Bool(bool::arbitrary(u)?),
+        2 => match u.choose_index(3)? {
+            0 => Value::Number(i64::arbitrary(u)?.into()),
+            1 => Value::Number(u64::arbitrary(u)?.into()),
+            _ => Number::from_f64(f64::arbitrary(u)?).map_or(Value::Null, Value::Number),
+        },
+        3 => Value::String(String::arbitrary(u)?),
+        4 => {
+            let len = u.int_in_range(1..=MAX_ENTRIES)?;
+            let mut obj = Map::new();
+            for _ in 0..len {
+                obj.insert(String::arbitrary(u)?, value_at(u, depth + 1)?);
+            }
+            Value::Object(obj)
+        }
+        _ => {
+            let len = u.int_in_range(1..=MAX_ENTRIES)?;
+            Value::Array(
+                (0..len)
+                    .map(|_| value_at(u, depth + 1))
+                    .collect::<Result<_>>()?,
+            )
+        }
+    })
+}
+
+/// Asserts that flattening the value with the style and then rebuilding it with `unflatten` gives back the same value
+///
+/// Only holds for styles that skip parents and for values without empty objects or arrays,
+/// as generated by `arbitrary_value`.
+///
+/// # Panics
+///
+/// When a path cannot be parsed back, two values collide on one path, or the rebuilt value differs.
+#[track_caller]
+#[allow(clippy::panic)] // failing the caller's test is the point of an assertion
+pub fn assert_flatten_round_trip(json: &Value, style: &Style) {
+    let pairs: Vec<_> = Iter::new(json)
+        .use_style(style.clone())
+        .map(|el| (el.path, el.value.clone()))
+        .collect();
+    match unflatten(pairs.iter().cloned(), style) {
+        Ok(rebuilt) => assert!(
+            rebuilt == *json,
+            "unflattening did not rebuild {} with style `{}`, got {} from pairs {:?}",
+            json,
+            style,
+            rebuilt,
+            pairs,
+        ),
+        Err(err) => panic!(
+            "could not unflatten {} with style `{}`: {}, from pairs {:?}",
+            json, style, err, pairs,
+        ),
+    }
+}
+
+/// Asserts that the path formatted for each element of the value parses back, with `Style::parse_path`,
+/// into the segments it was formatted from
+///
+/// # Panics
+///
+/// At the first path that fails to parse or parses into other segments.
+#[track_caller]
+#[allow(clippy::panic)] // failing the caller's test is the point of an assertion
+pub fn assert_path_round_trip(json: &Value, style: &Style) {
+    let mut segments = Iter::new(json).use_style(style.clone()).segments();
+    while let Some((expected, _)) = segments.next_segments() {
+        let path = style.format_segments(expected);
+        match style.parse_path(&path) {
+            Ok(parsed) => assert!(
+                parsed == expected,
+                "path {:?} parsed back as {:?} with style `{}`, expected {:?}",
+                path,
+                parsed,
+                style,
+                expected,
+            ),
+            Err(err) => panic!(
+                "path {:?} did not parse with style `{}`: {}",
+                path, style, err
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::PresetStyle;
+
+    #[test]
+    fn generates_within_the_depth_limit() {
+        fn depth(value: &Value) -> usize {
+            match value {
+                Value::Object(obj) => 1 + obj.values().map(depth).max().unwrap_or(0),
+                Value::Array(arr) => 1 + arr.iter().map(depth).max().unwrap_or(0),
+                _ => 0,
+            }
+        }
+
+        let bytes = [0xff; 4096];
+        let value = arbitrary_value(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(depth(&value) <= MAX_DEPTH + 1);
+        assert_eq!(
+            arbitrary_value(&mut Unstructured::new(&[])).unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "parsed back as")]
+    fn catches_keys_read_as_several_segments() {
+        let style = PresetStyle::CommonJs.into();
+        assert_path_round_trip(&serde_json::json!({"a.b": 1}), &style);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::style::Style;
+use serde_json::Value;
+use std::fmt;
+use tracing::{Level, Span};
+
+/// Limits on how much of a json value `record_json` will record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordLimits {
+    /// Containers nested this deep are recorded as a single json string instead of being expanded
+    pub max_depth: usize,
+    /// Maximum number of fields recorded, after which the remaining leaves are ignored
+    pub max_fields: usize,
+}
+
+impl Default for RecordLimits {
+    fn default() -> Self {
+        RecordLimits {
+            max_depth: 8,
+            max_fields: 64,
+        }
+    }
+}
+
+/// Destination for leaves recorded by `record_json`
+pub trait RecordTarget {
+    /// Records a single leaf, returning whether it was recorded
+    fn record_leaf(&self, path: &str, value: &Value) -> bool;
+}
+
+/// Spans can only record fields declared when they were created, so leaves whose
+/// keypath is not a declared field (for example `".user.id" = tracing::field::Empty`) are skipped
+impl RecordTarget for Span {
+    fn record_leaf(&self, path: &str, value: &Value) -> bool {
+        if !self.has_field(path) {
+            return false;
+        }
+
+        match value {
+            Value::String(s) => self.record(path, s.as_str()),
+            Value::Bool(b) => self.record(path, *b),
+            Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+                (Some(i), _, _) => self.record(path, i),
+                (_, Some(u), _) => self.record(path, u),
+                (_, _, Some(f)) => self.record(path, f),
+                _ => self.record(path, tracing::field::display(n)),
+            },
+            other => self.record(path, tracing::field::display(other)),
+        };
+        true
+    }
+}
+
+/// Emits one event per leaf at the given level, with the fields `keypath` and `value`
+///
+/// Event field names are fixed at compile time, so the keypath is recorded as a value rather than as the field name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeafEvents {
+    /// Level of every emitted event
+    pub level: Level,
+}
+
+impl RecordTarget for LeafEvents {
+    fn record_leaf(&self, path: &str, value: &Value) -> bool {
+        let value = LeafDisplay(value);
+        if self.level == Level::ERROR {
+            tracing::error!(keypath = path, value = %value);
+        } else if self.level == Level::WARN {
+            tracing::warn!(keypath = path, value = %value);
+        } else if self.level == Level::INFO {
+            tracing::info!(keypath = path, value = %value);
+        } else if self.level == Level::DEBUG {
+            tracing::debug!(keypath = path, value = %value);
+        } else {
+            tracing::trace!(keypath = path, value = %value);
+        }
+        true
+    }
+}
+
+/// Displays strings without quotes and everything else as json
+struct LeafDisplay<'a>(&'a Value);
+
+impl fmt::Display for LeafDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::String(s) => f.write_str(s),
+            other => other.fmt(f),
+        }
+    }
+}
+
+/// Records each leaf of a json value into a span or as events, naming each by its keypath
+///
+/// Leaves are visited in document order, and empty objects and arrays are skipped.
+/// Returns the number of fields that were recorded.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use tracing::{field, info_span};
+/// use json_keypath_iter::{record_json, PresetStyle, RecordLimits};
+///
+/// let span = info_span!("request", ".user.id" = field::Empty, ".user.admin" = field::Empty);
+/// let value = json!({"user": {"id": 7, "admin": false, "name": "ferris"}});
+///
+/// // only the declared fields are recorded, and only while a subscriber is listening
+/// record_json(&span, &value, &PresetStyle::CommonJs.into(), &RecordLimits::default());
+/// ```
+pub fn record_json<T>(target: &T, json: &Value, style: &Style, limits: &RecordLimits) -> usize
+where
+    T: RecordTarget + ?Sized,
+{
+    let mut recorded = 0;
+    let mut stack = vec![(String::new(), 0, json)];
+
+    while let Some((path, depth, value)) = stack.pop() {
+        if recorded >= limits.max_fields {
+            break;
+        }
+
+        match value {
+            Value::Object(obj) if depth < limits.max_depth => {
+                for (key, val) in obj.iter().rev() {
+                    stack.push((style.object_format(&path, key), depth + 1, val));
+                }
+            }
+            Value::Array(arr) if depth < limits.max_depth => {
+                for (index, val) in arr.iter().enumerate().rev() {
+                    stack.push((style.array_format(&path, index), depth + 1, val));
+                }
+            }
+            Value::Object(obj) if obj.is_empty() => {}
+            Value::Array(arr) if arr.is_empty() => {}
+            _ => {
+                if target.record_leaf(&path, value) {
+                    recorded += 1;
+                }
+            }
+        }
+    }
+
+    recorded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::PresetStyle;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<(String, Value)>>);
+
+    impl RecordTarget for Recorder {
+        fn record_leaf(&self, path: &str, value: &Value) -> bool {
+            self.0.borrow_mut().push((path.into(), value.clone()));
+            true
+        }
+    }
+
+    #[test]
+    fn truncates_deep_containers_to_json() {
+        let recorder = Recorder::default();
+        let value = json!({"a": {"b": {"c": 1}}, "d": []});
+        let limits = RecordLimits {
+            max_depth: 2,
+            ..RecordLimits::default()
+        };
+        let count = record_json(&recorder, &value, &PresetStyle::CommonJs.into(), &limits);
+
+        assert_eq!(count, 1);
+        assert_eq!(
+            recorder.0.into_inner(),
+            vec![(".a.b".into(), json!({"c": 1}))]
+        );
+    }
+
+    #[test]
+    fn stops_at_max_fields() {
+        let recorder = Recorder::default();
+        let value = json!([1, 2, 3, 4]);
+        let limits = RecordLimits {
+            max_fields: 2,
+            ..RecordLimits::default()
+        };
+        let count = record_json(&recorder, &value, &PresetStyle::CommonJs.into(), &limits);
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            recorder.0.into_inner(),
+            vec![("[0]".into(), json!(1)), ("[1]".into(), json!(2))]
+        );
+    }
+
+    #[test]
+    fn disabled_spans_record_nothing() {
+        let span = Span::none();
+        let value = json!({"a": 1});
+
+        assert_eq!(
+            record_json(
+                &span,
+                &value,
+                &PresetStyle::CommonJs.into(),
+                &RecordLimits::default()
+            ),
+            0
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use serde_json::Value;
+
+/// Options controlling how `render_tree` draws a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeOptions {
+    /// Containers nested deeper than this are summarised on one line, or `None` to draw everything
+    pub max_depth: Option<usize>,
+    /// Rendered values longer than this many characters are cut short with `…`
+    pub max_value_len: usize,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        TreeOptions {
+            max_depth: None,
+            max_value_len: 40,
+        }
+    }
+}
+
+/// Line of the tree still to be drawn
+struct Line<'a> {
+    indent: String,
+    branch: &'static str,
+    label: Option<String>,
+    value: &'a Value,
+    depth: usize,
+}
+
+/// Draws a json document as a box-drawing tree, one line per value
+///
+/// Objects and arrays show their number of entries, and leaves show their value as json.
+///
+/// Example:
+/// ```rust
+/// use serde_json::json;
+/// use json_keypath_iter::{render_tree, TreeOptions};
+///
+/// let value = json!({"a": [1, {"b": "x"}], "c": null});
+///
+/// assert_eq!(render_tree(&value, &TreeOptions::default()), "\
+/// {2}
+/// ├── a: [2]
+/// │   ├── [0]: 1
+/// │   └── [1]: {1}
+/// │       └── b: \"x\"
+/// └── c: null
+/// ");
+///
+/// let options = TreeOptions { max_depth: Some(1), ..TreeOptions::default() };
+/// assert_eq!(render_tree(&value, &options), "{2}\n├── a: [2] …\n└── c: null\n");
+/// ```
+pub fn render_tree(json: &Value, options: &TreeOptions) -> String {
+    let mut out = String::new();
+    let mut stack = vec![Line {
+        indent: String::new(),
+        branch: "",
+        label: None,
+        value: json,
+        depth: 0,
+    }];
+
+    while let Some(line) = stack.pop() {
+        out.push_str(&line.indent);
+        out.push_str(line.branch);
+        if let Some(label) = &line.label {
+            out.push_str(label);
+            out.push_str(": ");
+        }
+
+        let children: Vec<(String, &Value)> = match line.value {
+            Value::Object(obj) => {
+                out.push_str(&format!("{{{}}}", obj.len()));
+                obj.iter().map(|(key, val)| (key.clone(), val)).collect()
+            }
+            Value::Array(arr) => {
+                out.push_str(&format!("[{}]", arr.len()));
+                arr.iter()
+                    .enumerate()
+                    .map(|(index, val)| (format!("[{}]", index), val))
+                    .collect()
+            }
+            leaf => {
+                out.push_str(&truncate(leaf.to_string(), options.max_value_len));
+                Vec::new()
+            }
+        };
+
+        if !children.is_empty() && options.max_depth.is_some_and(|max| line.depth >= max) {
+            out.push_str(" …\n");
+            continue;
+        }
+        out.push('\n');
+
+        let child_indent = match (line.depth, line.branch) {
+            (0, _) => String::new(),
+            (_, "└── ") => format!("{}    ", line.indent),
+            _ => format!("{}│   ", line.indent),
+        };
+        let last = children.len().saturating_sub(1);
+        for (index, (label, value)) in children.into_iter().enumerate().rev() {
+            stack.push(Line {
+                indent: child_indent.clone(),
+                branch: if index == last {
+                    "└── "
+                } else {
+                    "├── "
+                },
+                label: Some(label),
+                value,
+                depth: line.depth + 1,
+            });
+        }
+    }
+
+    out
+}
+
+pub(crate) fn truncate(text: String, max_len: usize) -> String {
+    match text.char_indices().nth(max_len) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn truncates_long_values() {
+        let options = TreeOptions {
+            max_value_len: 4,
+            ..TreeOptions::default()
+        };
+
+        assert_eq!(
+            render_tree(&json!(["abcdef", "ab"]), &options),
+            "[2]\n├── [0]: \"abc…\n└── [1]: \"ab\"\n"
+        );
+    }
+
+    #[test]
+    fn root_leaf_is_a_single_line() {
+        assert_eq!(render_tree(&json!(true), &TreeOptions::default()), "true\n");
+    }
+}
@@ -0,0 +1,28 @@
+use crate::iter::Iter;
+use crate::owned::IntoIter;
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+
+impl<'a> Iter<'a> {
+    /// Converts a javascript value into json and creates an iterator that owns the result
+    ///
+    /// The value is read directly through serde-wasm-bindgen, so no `JSON.stringify` round-trip is needed.
+    /// A `js_sys::Object` can be passed as is, since it dereferences to `JsValue`.
+    /// Values json cannot represent, such as functions or symbols, produce an error.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// use wasm_bindgen::JsValue;
+    /// use json_keypath_iter::Iterator;
+    ///
+    /// fn log_paths(object: &JsValue) -> Result<(), serde_wasm_bindgen::Error> {
+    ///     for el in Iterator::from_js(object)? {
+    ///         println!("{} = {}", el.path, el.value);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_js(value: &JsValue) -> Result<IntoIter<'static>, serde_wasm_bindgen::Error> {
+        serde_wasm_bindgen::from_value::<Value>(value.clone()).map(IntoIter::new)
+    }
+}
@@ -0,0 +1,158 @@
+use crate::iter::Iter;
+use crate::owned::IntoIter;
+use crate::style::{PresetStyle, Style};
+use serde_json::{Map, Number, Value};
+use serde_yaml::Value as YamlValue;
+use std::fmt;
+
+/// How to handle yaml mapping keys that are not strings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YamlKeyPolicy {
+    /// Render scalar keys such as `1`, `true`, or `null` as their yaml text
+    Stringify,
+    /// Fail with `YamlError::NonStringKey`
+    Error,
+}
+
+/// Error produced while converting a yaml value for iteration
+#[derive(Debug, Clone, PartialEq)]
+pub enum YamlError {
+    /// A mapping key could not be used as an object key
+    NonStringKey {
+        /// Path of the mapping containing the key
+        path: String,
+        /// Debug rendering of the offending key
+        key: String,
+    },
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlError::NonStringKey { path, key } => {
+                write!(f, "mapping at {:?} has non-string key {}", path, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+impl<'a> Iter<'a> {
+    /// Converts a yaml value into json and creates an iterator that owns the result
+    ///
+    /// Tags are dropped in favour of the tagged value, and numbers that json cannot represent
+    /// (`.nan`, `.inf`) are yielded as strings.
+    ///
+    /// Example:
+    /// ```rust
+    /// use serde_json::json;
+    /// use json_keypath_iter::{Iterator, OwnedElement, YamlKeyPolicy};
+    ///
+    /// let yaml: serde_yaml::Value = serde_yaml::from_str("ports:\n  80: http\n").unwrap();
+    /// let items: Vec<_> = Iterator::from_yaml(&yaml, YamlKeyPolicy::Stringify).unwrap().collect();
+    ///
+    /// assert_eq!(items[0], OwnedElement { path: "[\"ports\"][\"80\"]".into(), indices: vec![], value: json!("http"), });
+    /// assert!(Iterator::from_yaml(&yaml, YamlKeyPolicy::Error).is_err());
+    /// ```
+    pub fn from_yaml(
+        value: &YamlValue,
+        policy: YamlKeyPolicy,
+    ) -> Result<IntoIter<'static>, YamlError> {
+        let style: Style = PresetStyle::SquareBrackets.into();
+        to_json(value, policy, &style, "").map(IntoIter::new)
+    }
+}
+
+fn to_json(
+    value: &YamlValue,
+    policy: YamlKeyPolicy,
+    style: &Style,
+    path: &str,
+) -> Result<Value, YamlError> {
+    Ok(match value {
+        YamlValue::Null => Value::Null,
+        YamlValue::Bool(b) => Value::Bool(*b),
+        YamlValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Number(i.into())
+            } else if let Some(u) = n.as_u64() {
+                Value::Number(u.into())
+            } else {
+                n.as_f64()
+                    .and_then(Number::from_f64)
+                    .map_or_else(|| Value::String(n.to_string()), Value::Number)
+            }
+        }
+        YamlValue::String(s) => Value::String(s.clone()),
+        YamlValue::Sequence(seq) => Value::Array(
+            seq.iter()
+                .enumerate()
+                .map(|(index, val)| to_json(val, policy, style, &style.array_format(path, index)))
+                .collect::<Result<_, _>>()?,
+        ),
+        YamlValue::Mapping(mapping) => {
+            let mut obj = Map::new();
+            for (key, val) in mapping {
+                let key = key_to_string(key, policy).ok_or_else(|| YamlError::NonStringKey {
+                    path: path.into(),
+                    key: format!("{:?}", key),
+                })?;
+                let val = to_json(val, policy, style, &style.object_format(path, &key))?;
+                obj.insert(key, val);
+            }
+            Value::Object(obj)
+        }
+        YamlValue::Tagged(tagged) => to_json(&tagged.value, policy, style, path)?,
+    })
+}
+
+fn key_to_string(key: &YamlValue, policy: YamlKeyPolicy) -> Option<String> {
+    match (key, policy) {
+        (YamlValue::String(s), _) => Some(s.clone()),
+        (YamlValue::Null, YamlKeyPolicy::Stringify) => Some("null".into()),
+        (YamlValue::Bool(b), YamlKeyPolicy::Stringify) => Some(b.to_string()),
+        (YamlValue::Number(n), YamlKeyPolicy::Stringify) => Some(n.to_string()),
+        (YamlValue::Tagged(tagged), _) => key_to_string(&tagged.value, policy),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_nested_documents() {
+        let yaml: YamlValue =
+            serde_yaml::from_str("a:\n  - 1\n  - !tag 2.5\n  - .nan\nb: ~\n").unwrap();
+        let items: Vec<_> = Iter::from_yaml(&yaml, YamlKeyPolicy::Error)
+            .unwrap()
+            .map(|el| (el.path, el.value))
+            .collect();
+
+        assert_eq!(
+            items,
+            vec![
+                ("[\"a\"][0]".into(), json!(1)),
+                ("[\"a\"][1]".into(), json!(2.5)),
+                ("[\"a\"][2]".into(), json!(".nan")),
+                ("[\"b\"]".into(), json!(null)),
+            ]
+        );
+    }
+
+    #[test]
+    fn complex_keys_always_error() {
+        let yaml: YamlValue = serde_yaml::from_str("outer:\n  ? [1, 2]\n  : x\n").unwrap();
+
+        assert_eq!(
+            Iter::from_yaml(&yaml, YamlKeyPolicy::Stringify).unwrap_err(),
+            YamlError::NonStringKey {
+                path: "[\"outer\"]".into(),
+                key: "Sequence [Number(1), Number(2)]".into(),
+            }
+        );
+    }
+}